@@ -678,6 +678,7 @@ export default class MockBackend {
 
 		return {
 			branchToRemote: [[args.branch, `refs/remotes/origin/${args.branch}`]],
+			branchToGerritChanges: [],
 			remote: 'origin'
 		};
 	}
@@ -25,8 +25,13 @@ export type Commit = {
 	readonly createdAt: number;
 	/** The author of the commit. */
 	readonly author: Author;
+	/** Whether the commit is signed, and if its signature matches, or absent if this wasn't checked. */
+	readonly signature?: SignatureStatus;
 };
 
+/** The state of the signature of a commit. */
+export type SignatureStatus = 'unsigned' | 'valid' | 'unverified' | 'invalid';
+
 /** List of changes, stats and metadata for a commit */
 export type CommitDetails = {
 	/** The commit */
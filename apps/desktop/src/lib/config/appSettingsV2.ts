@@ -63,6 +63,14 @@ export class SettingsService {
 		await this.backend.invoke('update_reviews', { update });
 	}
 
+	async updateForges(update: Partial<Forges>) {
+		await this.backend.invoke('update_forges', { update });
+	}
+
+	async updateOplog(update: Partial<Oplog>) {
+		await this.backend.invoke('update_oplog', { update });
+	}
+
 	async updateFetch(update: Partial<Fetch>) {
 		await this.backend.invoke('update_fetch', { update });
 	}
@@ -154,6 +162,10 @@ export type AppSettings = {
 	claude: Claude;
 	/** Settings related to code reviews and pull requests */
 	reviews: Reviews;
+	/** Settings related to detecting the forge a repository is hosted on */
+	forges: Forges;
+	/** Settings related to the retention of snapshots in the operations log */
+	oplog: Oplog;
 };
 
 export type TelemetrySettings = {
@@ -206,3 +218,19 @@ export type Reviews = {
 	/** Whether to auto-fill PR title and description from the first commit when a branch has only one commit. */
 	autoFillPrDescriptionFromCommit: boolean;
 };
+
+export type Forges = {
+	/** Maps the hosts of self-hosted forges, like `git.corp.example.com`, to the forge they run. */
+	hosts: Record<string, 'github' | 'gitlab' | 'bitbucket' | 'azure'>;
+};
+
+export type Oplog = {
+	/** Whether snapshots that aren't retained are pruned automatically in the background, at most once a day. */
+	autoPrune: boolean;
+	/** The amount of most recent snapshots to always keep. */
+	keepCount: number;
+	/** Snapshots created within this amount of days are always kept. */
+	keepDays: number;
+	/** The names of operations whose snapshots are always kept, like `RestoreFromSnapshot`. */
+	keepOperations: string[];
+};
@@ -40,7 +40,8 @@ export type Operation =
 	| 'UpdateDependentBranchPrNumber'
 	| 'AutoHandleChangesBefore'
 	| 'AutoHandleChangesAfter'
-	| 'SplitBranch';
+	| 'SplitBranch'
+	| 'SplitCommit';
 
 export class Trailer {
 	key!: string;
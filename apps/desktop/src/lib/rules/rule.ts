@@ -1,3 +1,4 @@
+import type { HunkHeader } from '$lib/hunks/hunk';
 import type { BrandedId } from '@gitbutler/shared/utils/branding';
 import type { FileStatus } from '@gitbutler/ui/components/file/types';
 
@@ -201,7 +202,14 @@ export type RuleAction =
 export type Operation =
 	| { type: 'assign'; subject: { target: StackTarget } }
 	| { type: 'amend'; subject: { commit_id: string } }
-	| { type: 'newCommit'; subject: { branch_name: string } };
+	| {
+			type: 'newCommit';
+			subject: {
+				branch_name: string;
+				message_template?: string | null;
+				generate_message: boolean;
+			};
+	  };
 
 /**
  * The target stack for a given operation. It's either specifying a specific stack ID, or alternaitvely the leftmost or rightmost stack in the workspace.
@@ -325,3 +333,22 @@ export interface UpdateRuleRequest {
 	/** The new action for the rule. If not provided, the existing action is retained. */
 	action: RuleAction | null;
 }
+
+/**
+ * What happens to a hunk when an implicit operation is applied.
+ */
+export type PlannedAction =
+	| { type: 'assign'; subject: { stackId: string } }
+	| { type: 'absorb'; subject: { stackId: string; commitId: string } };
+
+/**
+ * A change that an implicit operation makes, or would make in a dry-run, to an uncommitted hunk.
+ */
+export interface PlannedChange {
+	/** The path of the file the hunk belongs to. */
+	path: string;
+	/** The hunk, or null if the change applies to the whole file. */
+	hunkHeader: HunkHeader | null;
+	/** What happens to the hunk. */
+	action: PlannedAction;
+}
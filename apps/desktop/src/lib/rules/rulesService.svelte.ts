@@ -2,6 +2,7 @@ import {
 	isAiRule,
 	type AiRule,
 	type CreateRuleRequest,
+	type PlannedChange,
 	type UpdateRuleRequest,
 	type WorkspaceRule,
 	type WorkspaceRuleId
@@ -69,6 +70,13 @@ export default class RulesService {
 		);
 	}
 
+	/**
+	 * Previews what the implicit operation of a rule would do with the current uncommitted changes.
+	 */
+	async fetchWorkspaceRulePreview(projectId: string, id: WorkspaceRuleId) {
+		return await this.api.endpoints.previewWorkspaceRule.fetch({ projectId, id });
+	}
+
 	async fetchListWorkspaceRules(projectId: string) {
 		return await this.api.endpoints.listWorkspaceRules.fetch(
 			{ projectId },
@@ -119,6 +127,13 @@ function injectEndpoints(api: BackendApi) {
 							]
 						: []
 			}),
+			previewWorkspaceRule: build.query<
+				PlannedChange[],
+				{ projectId: string; id: WorkspaceRuleId }
+			>({
+				extraOptions: { command: 'preview_workspace_rule' },
+				query: (args) => args
+			}),
 			listWorkspaceRules: build.query<
 				EntityState<WorkspaceRule, WorkspaceRuleId>,
 				{ projectId: string }
@@ -118,12 +118,23 @@ export interface BranchPushResult {
 	 * The list of pushed branches and their corresponding remote refnames.
 	 */
 	branchToRemote: [string, string][];
+	/**
+	 * The Gerrit changes created or updated for each pushed branch, only set when pushing in Gerrit mode.
+	 */
+	branchToGerritChanges: [string, GerritPushedChange[]][];
 	/**
 	 * The name of the remote to which the branches were pushed.
 	 */
 	remote: string;
 }
 
+export interface GerritPushedChange {
+	url: string;
+	number: number | null;
+	subject: string;
+	isNew: boolean;
+}
+
 /**
  * All possible reasons for a commit to be rejected.
  *
@@ -10,7 +10,7 @@ use schemars::{JsonSchema, schema_for};
 
 use crate::OpenAiProvider;
 
-#[expect(dead_code)]
+/// Like [`commit_message()`], but for callers that aren't async, running the request on its own runtime.
 pub fn commit_message_blocking(
     openai: &OpenAiProvider,
     external_summary: &str,
@@ -22,7 +22,7 @@ mod action;
 mod auto_commit;
 mod branch_changes;
 pub mod cli;
-mod generate;
+pub mod generate;
 mod grouping;
 mod openai;
 pub mod rename_branch;
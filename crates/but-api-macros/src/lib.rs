@@ -1,12 +1,17 @@
 use convert_case::{Case, Casing};
 use proc_macro::TokenStream;
 use quote::{format_ident, quote};
-use syn::{FnArg, ItemFn, Pat, parse_macro_input};
+use syn::{
+    FnArg, GenericArgument, ItemFn, Pat, PathArguments, ReturnType, Type, parse_macro_input,
+};
 
 /// To be used on functions, so a function `func` will be turned into:
 /// * `func` - the original item, unchanged
 /// * `func_params(FuncParams)` taking a struct with all parameters
 /// * `func_cmd` for calls from the frontend, taking `serde_json::Value` and returning `Result<serde_json::Value, Error>`
+///
+/// It also submits `func_cmd` to `crate::registry` under the name `func`, along with the JSON schemas of its
+/// parameters and return value. All parameter types and the return type must implement `schemars::JsonSchema`.
 #[proc_macro_attribute]
 pub fn api_cmd(_attr: TokenStream, item: TokenStream) -> TokenStream {
     let input_fn = parse_macro_input!(item as ItemFn);
@@ -19,6 +24,7 @@ pub fn api_cmd(_attr: TokenStream, item: TokenStream) -> TokenStream {
     // Collect parameter names and types
     let mut fields = Vec::new();
     let mut param_names = Vec::new();
+    let mut param_schemas = Vec::new();
     for arg in &sig.inputs {
         if let FnArg::Typed(pat_type) = arg {
             let ty = &pat_type.ty;
@@ -27,10 +33,38 @@ pub fn api_cmd(_attr: TokenStream, item: TokenStream) -> TokenStream {
                 let name = &ident.ident;
                 fields.push(quote! { pub #name: #ty });
                 param_names.push(name);
+                let json_name = name.to_string().to_case(Case::Camel);
+                let required = generic_argument_of(ty, "Option").is_none();
+                param_schemas.push(quote! {
+                    (#json_name, #required, generator.subschema_for::<#ty>())
+                });
             }
         }
     }
 
+    // The type of the value returned on success, unwrapped from `Result<T, Error>`.
+    let return_ty = match output {
+        ReturnType::Default => quote! { () },
+        ReturnType::Type(_, ty) => {
+            let ty = generic_argument_of(ty, "Result").unwrap_or(ty);
+            quote! { #ty }
+        }
+    };
+    let return_ty_name = return_ty
+        .to_string()
+        .replace(" :: ", "::")
+        .replace(" <", "<")
+        .replace("< ", "<")
+        .replace(" >", ">")
+        .replace(" ,", ",");
+
+    // Functions without parameters don't use the schema generator
+    let generator = if param_schemas.is_empty() {
+        format_ident!("_generator")
+    } else {
+        format_ident!("generator")
+    };
+
     // Struct name: <FunctionName>Params (PascalCase)
     let struct_name = format_ident!("{}Params", fn_name.to_string().to_case(Case::Pascal));
 
@@ -66,7 +100,35 @@ pub fn api_cmd(_attr: TokenStream, item: TokenStream) -> TokenStream {
             ::serde_json::to_value(result).to_error()
         }
 
+        // Registration, to call and describe the command by name
+        ::inventory::submit! {
+            crate::registry::Command {
+                name: stringify!(#fn_name),
+                returns: #return_ty_name,
+                params_schema: |#generator| crate::registry::params_schema(vec![#(#param_schemas),*]),
+                returns_schema: |generator| generator.subschema_for::<#return_ty>(),
+                call: #cmd_name,
+            }
+        }
     };
 
     expanded.into()
 }
+
+/// Return the first generic argument of `ty` if it's a path ending in `name`, like `Option<T>` for `Option`.
+fn generic_argument_of<'a>(ty: &'a Type, name: &str) -> Option<&'a Type> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+    if segment.ident != name {
+        return None;
+    }
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    args.args.iter().find_map(|arg| match arg {
+        GenericArgument::Type(ty) => Some(ty),
+        _ => None,
+    })
+}
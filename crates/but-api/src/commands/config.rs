@@ -2,6 +2,7 @@ use but_core::{RepositoryExt, settings::git::ui::GitConfigSettings};
 use gitbutler_project::ProjectId;
 use gitbutler_serde::bstring_opt_lossy;
 use gix::bstr::BString;
+use schemars::JsonSchema;
 use serde::Serialize;
 use tracing::instrument;
 
@@ -46,13 +47,15 @@ pub fn store_author_globally_if_unset(
 }
 
 /// Represents the author information from the git configuration.
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, JsonSchema)]
 pub struct AuthorInfo {
     /// The name of the author.
     #[serde(with = "bstring_opt_lossy")]
+    #[schemars(with = "Option<String>")]
     pub name: Option<BString>,
     /// The email of the author.
     #[serde(with = "bstring_opt_lossy")]
+    #[schemars(with = "Option<String>")]
     pub email: Option<BString>,
 }
 
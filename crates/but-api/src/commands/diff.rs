@@ -17,6 +17,7 @@ use gitbutler_command_context::CommandContext;
 use gitbutler_project::ProjectId;
 use gitbutler_reference::Refname;
 use gix::refs::Category;
+use schemars::JsonSchema;
 use serde::Serialize;
 use tracing::instrument;
 
@@ -36,7 +37,7 @@ pub fn tree_change_diffs(
     Ok(change.unified_diff(&repo, app_settings.context_lines)?)
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct CommitDetails {
     pub commit: but_workspace::ui::Commit,
@@ -144,12 +145,22 @@ pub fn changes_in_worktree(project_id: ProjectId) -> anyhow::Result<WorktreeChan
     };
 
     if ctx.app_settings().feature_flags.rules {
-        but_rules::handler::process_workspace_rules(
+        match but_rules::handler::process_workspace_rules(
             ctx,
             &assignments,
             &dependencies.as_ref().ok().cloned(),
-        )
-        .ok();
+        ) {
+            Ok(outcome) => {
+                for err in outcome.errors {
+                    tracing::warn!(
+                        rule = %err.rule_id,
+                        "failed to apply workspace rule: {}",
+                        err.error
+                    );
+                }
+            }
+            Err(err) => tracing::warn!("failed to process workspace rules: {err:#}"),
+        }
     }
 
     Ok(WorktreeChanges {
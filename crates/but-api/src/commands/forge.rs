@@ -3,12 +3,15 @@ use std::path::Path;
 
 use anyhow::Context;
 use but_api_macros::api_cmd;
+use but_settings::AppSettings;
 use gitbutler_forge::{
     forge::ForgeName,
+    remote::{ForgeRepoInfo, deduce_forge_repo_info},
     review::{ReviewTemplateFunctions, available_review_templates, get_review_template_functions},
 };
 use gitbutler_project::ProjectId;
 use gitbutler_repo::RepoCommands;
+use gitbutler_stack::VirtualBranchesHandle;
 use tracing::instrument;
 
 use crate::error::Error;
@@ -48,3 +51,24 @@ pub fn pr_template(
         .content
         .context("PR template was not valid UTF-8")?)
 }
+
+/// Find the forge the project is hosted on by looking at its remotes, preferring the push remote of the target.
+/// Hosts configured in the `forges` app settings take precedence over the detection by host name.
+#[api_cmd]
+#[tauri::command(async)]
+#[instrument(err(Debug))]
+pub fn forge_repo_info(project_id: ProjectId) -> Result<Option<ForgeRepoInfo>, Error> {
+    let project = gitbutler_project::get_validated(project_id)?;
+    let app_settings = AppSettings::load_from_default_path_creating()?;
+    let push_remote = VirtualBranchesHandle::new(project.gb_dir())
+        .maybe_get_default_target()?
+        .map(|target| target.push_remote_name());
+
+    let mut remotes = project.remotes()?;
+    remotes.sort_by_key(|remote| remote.name != push_remote);
+    let urls = remotes
+        .iter()
+        .filter_map(|remote| remote.url.as_deref()?.parse::<gitbutler_url::Url>().ok())
+        .collect::<Vec<_>>();
+    Ok(deduce_forge_repo_info(&urls, &app_settings.forges.hosts))
+}
@@ -1,8 +1,9 @@
 //! In place of commands.rs
 use but_api_macros::api_cmd;
 use but_rules::{
-    CreateRuleRequest, UpdateRuleRequest, WorkspaceRule, create_rule, delete_rule, list_rules,
-    update_rule,
+    CreateRuleRequest, UpdateRuleRequest, WorkspaceRule, create_rule, delete_rule,
+    evaluate::Evaluation, evaluate_rules, implicit::PlannedChange, list_rules,
+    preview_implicit_rule, update_rule,
 };
 use but_settings::AppSettings;
 use gitbutler_command_context::CommandContext;
@@ -60,3 +61,30 @@ pub fn list_workspace_rules(project_id: ProjectId) -> Result<Vec<WorkspaceRule>,
     )?;
     list_rules(ctx).map_err(Into::into)
 }
+
+/// Preview what the implicit operation of the rule with `id` would do with the current uncommitted changes.
+#[api_cmd]
+#[tauri::command(async)]
+#[instrument(err(Debug))]
+pub fn preview_workspace_rule(
+    project_id: ProjectId,
+    id: String,
+) -> Result<Vec<PlannedChange>, Error> {
+    let ctx = &mut CommandContext::open(
+        &gitbutler_project::get(project_id)?,
+        AppSettings::load_from_default_path_creating()?,
+    )?;
+    preview_implicit_rule(ctx, &id).map_err(Into::into)
+}
+
+/// Evaluate all workspace rules against the current uncommitted changes without changing anything.
+#[api_cmd]
+#[tauri::command(async)]
+#[instrument(err(Debug))]
+pub fn evaluate_workspace_rules(project_id: ProjectId) -> Result<Evaluation, Error> {
+    let ctx = &mut CommandContext::open(
+        &gitbutler_project::get(project_id)?,
+        AppSettings::load_from_default_path_creating()?,
+    )?;
+    evaluate_rules(ctx).map_err(Into::into)
+}
@@ -1,9 +1,11 @@
 //! In place of commands.rs
 use but_api_macros::api_cmd;
 use but_settings::api::{
-    ClaudeUpdate, FeatureFlagsUpdate, FetchUpdate, ReviewsUpdate, TelemetryUpdate,
+    ClaudeUpdate, FeatureFlagsUpdate, FetchUpdate, ForgesUpdate, OplogUpdate, ReviewsUpdate,
+    TelemetryUpdate,
 };
 use but_settings::{AppSettings, AppSettingsWithDiskSync};
+use gitbutler_forge::forge::ForgeName;
 use serde::Deserialize;
 use tracing::instrument;
 
@@ -107,6 +109,52 @@ pub fn update_reviews(
         .map_err(|e| e.into())
 }
 
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateForgesParams {
+    pub update: ForgesUpdate,
+}
+
+pub fn update_forges(
+    app_settings_sync: &AppSettingsWithDiskSync,
+    params: UpdateForgesParams,
+) -> Result<(), Error> {
+    ensure_known_forges(&params.update)?;
+    app_settings_sync
+        .update_forges(params.update)
+        .map_err(|e| e.into())
+}
+
+/// Fail if `update` maps a host to a forge that isn't known, so no mapping is stored that would be ignored.
+fn ensure_known_forges(update: &ForgesUpdate) -> anyhow::Result<()> {
+    if let Some((host, forge)) = update
+        .hosts
+        .iter()
+        .flatten()
+        .find(|(_, forge)| ForgeName::from_name(forge).is_none())
+    {
+        anyhow::bail!(
+            "Unknown forge '{forge}' for host '{host}', expected one of github, gitlab, bitbucket or azure"
+        );
+    }
+    Ok(())
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateOplogParams {
+    pub update: OplogUpdate,
+}
+
+pub fn update_oplog(
+    app_settings_sync: &AppSettingsWithDiskSync,
+    params: UpdateOplogParams,
+) -> Result<(), Error> {
+    app_settings_sync
+        .update_oplog(params.update)
+        .map_err(|e| e.into())
+}
+
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct UpdateFetchParams {
@@ -121,3 +169,33 @@ pub fn update_fetch(
         .update_fetch(params.update)
         .map_err(|e| e.into())
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use but_settings::api::ForgesUpdate;
+
+    use super::ensure_known_forges;
+
+    fn hosts(forge: &str) -> ForgesUpdate {
+        ForgesUpdate {
+            hosts: Some(BTreeMap::from([(
+                "git.corp.example.com".to_string(),
+                forge.to_string(),
+            )])),
+        }
+    }
+
+    #[test]
+    fn unknown_forges_are_rejected() {
+        assert!(ensure_known_forges(&hosts("GitLab")).is_ok());
+        assert!(ensure_known_forges(&ForgesUpdate { hosts: None }).is_ok());
+
+        let err = ensure_known_forges(&hosts("gitlub")).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Unknown forge 'gitlub' for host 'git.corp.example.com', expected one of github, gitlab, bitbucket or azure"
+        );
+    }
+}
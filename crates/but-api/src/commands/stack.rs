@@ -15,9 +15,10 @@ use tracing::instrument;
 
 pub mod create_reference {
     use crate::hex_hash::HexHash;
+    use schemars::JsonSchema;
     use serde::{Deserialize, Serialize};
 
-    #[derive(Deserialize, Serialize, Debug)]
+    #[derive(Deserialize, Serialize, Debug, JsonSchema)]
     #[serde(rename_all = "camelCase")]
     pub struct Request {
         /// The short name of the new branch, i.e. `foo` or `features/bar`
@@ -26,7 +27,7 @@ pub mod create_reference {
         pub anchor: Option<Anchor>,
     }
 
-    #[derive(Deserialize, Serialize, Debug)]
+    #[derive(Deserialize, Serialize, Debug, JsonSchema)]
     #[serde(tag = "type", content = "subject", rename_all = "camelCase")]
     pub enum Anchor {
         AtCommit {
@@ -257,6 +258,7 @@ pub fn push_stack(
     skip_force_push_protection: bool,
     branch: String,
     run_hooks: bool,
+    gerrit_options: Option<but_gerrit::push::PushOptions>,
 ) -> Result<PushResult, Error> {
     let project = gitbutler_project::get(project_id)?;
     let ctx = CommandContext::open(&project, AppSettings::load_from_default_path_creating()?)?;
@@ -267,6 +269,7 @@ pub fn push_stack(
         skip_force_push_protection,
         branch,
         run_hooks,
+        gerrit_options,
     )
     .map_err(|e| e.into())
 }
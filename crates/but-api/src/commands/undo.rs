@@ -2,7 +2,9 @@ use anyhow::Context;
 use but_api_macros::api_cmd;
 use but_settings::AppSettings;
 use gitbutler_command_context::CommandContext;
+use gitbutler_oplog::diff::SnapshotComparison;
 use gitbutler_oplog::entry::OperationKind;
+use gitbutler_oplog::retention::{PruneOutcome, RetentionPolicy};
 use gitbutler_oplog::{OplogExt, entry::Snapshot};
 use gitbutler_project::ProjectId;
 use tracing::instrument;
@@ -43,6 +45,29 @@ pub fn restore_snapshot(project_id: ProjectId, sha: String) -> Result<(), Error>
     Ok(())
 }
 
+/// Return the snapshot that [`redo()`] would restore, or `None` if there is nothing to redo.
+#[api_cmd]
+#[tauri::command(async)]
+#[instrument(err(Debug))]
+pub fn redo_snapshot(project_id: ProjectId) -> Result<Option<Snapshot>, Error> {
+    let project = gitbutler_project::get(project_id).context("failed to get project")?;
+    let ctx = CommandContext::open(&project, AppSettings::load_from_default_path_creating()?)?;
+    Ok(ctx.redo_snapshot()?)
+}
+
+/// Redo the most recent restore that wasn't redone yet, and return the sha of the snapshot
+/// created for it, or `None` if there was nothing to redo.
+#[api_cmd]
+#[tauri::command(async)]
+#[instrument(err(Debug))]
+pub fn redo(project_id: ProjectId) -> Result<Option<String>, Error> {
+    let project = gitbutler_project::get(project_id).context("failed to get project")?;
+    let ctx = CommandContext::open(&project, AppSettings::load_from_default_path_creating()?)?;
+    let mut guard = project.exclusive_worktree_access();
+    let snapshot_id = ctx.redo(guard.write_permission())?;
+    Ok(snapshot_id.map(|id| id.to_string()))
+}
+
 #[api_cmd]
 #[tauri::command(async)]
 #[instrument(err(Debug))]
@@ -56,3 +81,48 @@ pub fn snapshot_diff(
     let diff: Vec<but_core::ui::TreeChange> = diff.into_iter().map(Into::into).collect();
     Ok(diff)
 }
+
+/// Compare the state of the project at the snapshot `from` with the state at the snapshot `to`.
+#[api_cmd]
+#[tauri::command(async)]
+#[instrument(err(Debug))]
+pub fn compare_snapshots(
+    project_id: ProjectId,
+    from: String,
+    to: String,
+) -> Result<SnapshotComparison, Error> {
+    let project = gitbutler_project::get(project_id).context("failed to get project")?;
+    let ctx = CommandContext::open(&project, AppSettings::load_from_default_path_creating()?)?;
+    Ok(ctx.compare_snapshots(
+        from.parse().map_err(anyhow::Error::from)?,
+        to.parse().map_err(anyhow::Error::from)?,
+    )?)
+}
+
+/// Remove snapshots from the oplog that aren't retained, using the retention policy from the app settings
+/// with `keep_count`, `keep_days` and `keep_operations` overriding it if set.
+#[api_cmd]
+#[tauri::command(async)]
+#[instrument(err(Debug))]
+pub fn prune_snapshots(
+    project_id: ProjectId,
+    keep_count: Option<usize>,
+    keep_days: Option<u64>,
+    keep_operations: Option<Vec<OperationKind>>,
+    dry_run: bool,
+) -> Result<PruneOutcome, Error> {
+    let project = gitbutler_project::get(project_id).context("failed to get project")?;
+    let ctx = CommandContext::open(&project, AppSettings::load_from_default_path_creating()?)?;
+    let mut policy = RetentionPolicy::from_settings(&ctx.app_settings().oplog);
+    if let Some(keep_count) = keep_count {
+        policy.keep_count = keep_count;
+    }
+    if let Some(keep_days) = keep_days {
+        policy.max_age = Some(RetentionPolicy::max_age_from_days(keep_days));
+    }
+    if let Some(keep_operations) = keep_operations {
+        policy.keep_operations = keep_operations;
+    }
+    let mut guard = project.exclusive_worktree_access();
+    Ok(ctx.prune_snapshots(&policy, dry_run, guard.write_permission())?)
+}
@@ -1,12 +1,13 @@
 use anyhow::Result;
 use but_api_macros::api_cmd;
 use gitbutler_user::User;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use tracing::instrument;
 
 use crate::error::Error;
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
 pub struct UserWithSecrets {
     pub id: u64,
     pub name: Option<String>,
@@ -10,8 +10,9 @@ use gitbutler_branch_actions::upstream_integration::{
     StackStatuses,
 };
 use gitbutler_branch_actions::{
-    BaseBranch, BranchListing, BranchListingDetails, BranchListingFilter, MoveBranchResult,
-    MoveCommitIllegalAction, RemoteBranchData, RemoteBranchFile, RemoteCommit, StackOrder,
+    ApplyBranchPreview, BaseBranch, BranchListing, BranchListingDetails, BranchListingFilter,
+    MoveBranchResult, MoveCommitIllegalAction, RemoteBranchData, RemoteBranchFile, RemoteCommit,
+    StackOrder,
 };
 use gitbutler_command_context::CommandContext;
 use gitbutler_oxidize::ObjectIdExt;
@@ -284,6 +285,20 @@ pub fn can_apply_remote_branch(
     )?)
 }
 
+#[api_cmd]
+#[tauri::command(async)]
+#[instrument(err(Debug))]
+pub fn preview_apply_branch(
+    project_id: ProjectId,
+    branch: Refname,
+) -> Result<ApplyBranchPreview, Error> {
+    let project = gitbutler_project::get(project_id)?;
+    let ctx = CommandContext::open(&project, AppSettings::load_from_default_path_creating()?)?;
+    Ok(gitbutler_branch_actions::preview_apply_branch(
+        &ctx, &branch,
+    )?)
+}
+
 #[api_cmd]
 #[tauri::command(async)]
 #[instrument(err(Debug))]
@@ -18,6 +18,7 @@ use gitbutler_oplog::{OplogExt, SnapshotExt};
 use gitbutler_project::{Project, ProjectId};
 use gitbutler_reference::{LocalRefname, Refname};
 use gitbutler_stack::{StackId, VirtualBranchesHandle};
+use schemars::JsonSchema;
 use serde::Serialize;
 use tracing::instrument;
 
@@ -147,6 +148,31 @@ pub fn stack_details(
     .map_err(Into::into)
 }
 
+/// Check the signatures of `commit_ids`, returning the status of each in the same order, or `None` if it couldn't
+/// be checked. This is separate from [`stack_details()`] as it runs `gpg` or `ssh-keygen` for each signed commit,
+/// but results are cached by commit id.
+#[api_cmd]
+#[tauri::command(async)]
+#[instrument(err(Debug))]
+pub fn commit_signatures(
+    project_id: ProjectId,
+    commit_ids: Vec<HexHash>,
+) -> Result<Vec<Option<but_rebase::commit::SignatureStatus>>, Error> {
+    let project = gitbutler_project::get(project_id)?;
+    let repo = but_core::open_repo(project.worktree_path())?;
+    Ok(commit_ids
+        .into_iter()
+        .map(|commit_id| {
+            let commit_id = commit_id.into();
+            but_rebase::commit::verify_signature_cached(&repo, commit_id)
+                .inspect_err(|err| {
+                    tracing::warn!("Failed to check the signature of commit {commit_id}: {err:#}")
+                })
+                .ok()
+        })
+        .collect())
+}
+
 #[api_cmd]
 #[tauri::command(async)]
 #[instrument(err(Debug))]
@@ -295,7 +321,7 @@ pub fn discard_worktree_changes(
     Ok(refused)
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct UIMoveChangesResult {
     replaced_commits: Vec<(String, String)>,
@@ -348,6 +374,58 @@ pub fn move_changes_between_commits(
     Ok(result.into())
 }
 
+/// The outcome of splitting a commit, as returned by [`split_commit()`].
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UISplitCommitOutcome {
+    /// The commits created for each of the pieces, in the order of the pieces.
+    pub new_commits: Vec<String>,
+    /// The commit with the changes that no piece claimed, which keeps the original message.
+    pub remainder: Option<String>,
+    /// The commits that were rewritten along the way, as pairs of old and new commit ids.
+    pub replaced_commits: Vec<(String, String)>,
+}
+
+/// Split the commit with `commit_id` in the stack with `stack_id` into one commit per piece.
+///
+/// Each piece lists whole files or individual hunks of the commit. Everything that isn't claimed by a piece
+/// goes into a remainder commit with the original message.
+#[api_cmd]
+#[tauri::command(async)]
+#[instrument(err(Debug))]
+pub fn split_commit(
+    project_id: ProjectId,
+    stack_id: StackId,
+    commit_id: HexHash,
+    pieces: Vec<but_workspace::CommitChanges>,
+) -> Result<UISplitCommitOutcome, Error> {
+    let project = gitbutler_project::get(project_id)?;
+    let mut ctx = CommandContext::open(&project, AppSettings::load_from_default_path_creating()?)?;
+    let mut guard = project.exclusive_worktree_access();
+
+    let _ = ctx.create_snapshot(
+        SnapshotDetails::new(OperationKind::SplitCommit),
+        guard.write_permission(),
+    );
+    let context_lines = ctx.app_settings().context_lines;
+    let outcome =
+        but_workspace::split_commit(&mut ctx, stack_id, commit_id.into(), &pieces, context_lines)?;
+
+    let vb_state = VirtualBranchesHandle::new(ctx.project().gb_dir());
+    update_workspace_commit(&vb_state, &ctx)?;
+
+    let UIMoveChangesResult { replaced_commits } = outcome.move_changes_result.into();
+    Ok(UISplitCommitOutcome {
+        new_commits: outcome
+            .new_commits
+            .iter()
+            .map(|id| id.to_hex().to_string())
+            .collect(),
+        remainder: outcome.remainder.map(|id| id.to_hex().to_string()),
+        replaced_commits,
+    })
+}
+
 #[api_cmd]
 #[tauri::command(async)]
 #[instrument(err(Debug))]
@@ -1,9 +1,10 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::{ops::Deref, str::FromStr};
 
 /// A type that deserializes a hexadecimal hash into an object id automatically.
-#[derive(Debug, Clone, Copy)]
-pub struct HexHash(pub gix::ObjectId);
+#[derive(Debug, Clone, Copy, JsonSchema)]
+pub struct HexHash(#[schemars(with = "String")] pub gix::ObjectId);
 
 impl From<HexHash> for gix::ObjectId {
     fn from(value: HexHash) -> Self {
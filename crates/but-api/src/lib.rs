@@ -16,6 +16,7 @@ pub mod commands;
 pub use commands::*;
 pub mod error;
 pub mod hex_hash;
+pub mod registry;
 
 #[derive(Clone)]
 pub struct App {
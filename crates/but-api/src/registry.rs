@@ -0,0 +1,134 @@
+//! A registry of all commands declared with [`api_cmd`](but_api_macros::api_cmd), to call and describe them by name.
+use std::{collections::HashMap, sync::LazyLock};
+
+pub use schemars::{JsonSchema, Schema, SchemaGenerator};
+
+use crate::error::Error;
+
+/// A command as registered by [`api_cmd`](but_api_macros::api_cmd).
+pub struct Command {
+    /// The name of the command, which is the name of its function.
+    pub name: &'static str,
+    /// The Rust type of the value the command returns on success.
+    pub returns: &'static str,
+    /// Generate the JSON schema of the object with all parameters.
+    pub params_schema: fn(&mut SchemaGenerator) -> Schema,
+    /// Generate the JSON schema of the value the command returns on success.
+    pub returns_schema: fn(&mut SchemaGenerator) -> Schema,
+    /// Call the command with an object with all parameters, and return its serialized result.
+    pub call: fn(serde_json::Value) -> Result<serde_json::Value, Error>,
+}
+
+inventory::collect!(Command);
+
+static COMMANDS: LazyLock<HashMap<&'static str, &'static Command>> = LazyLock::new(|| {
+    inventory::iter::<Command>
+        .into_iter()
+        .map(|command| (command.name, command))
+        .collect()
+});
+
+/// All registered commands, in no particular order.
+pub fn commands() -> impl Iterator<Item = &'static Command> {
+    COMMANDS.values().copied()
+}
+
+/// Return the command called `name`, if there is one.
+pub fn find(name: &str) -> Option<&'static Command> {
+    COMMANDS.get(name).copied()
+}
+
+/// Describe all commands with the JSON schemas of their parameters and return values, sorted by name.
+///
+/// Schemas of types used by more than one command are shared, and referenced from `$defs` at the top level.
+pub fn describe() -> serde_json::Value {
+    let mut generator = SchemaGenerator::default();
+    let mut commands = commands().collect::<Vec<_>>();
+    commands.sort_by_key(|command| command.name);
+    let commands = commands
+        .into_iter()
+        .map(|command| {
+            serde_json::json!({
+                "name": command.name,
+                "params": (command.params_schema)(&mut generator),
+                "returnType": command.returns,
+                "returns": (command.returns_schema)(&mut generator),
+            })
+        })
+        .collect::<Vec<_>>();
+    serde_json::json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "commands": commands,
+        "$defs": generator.definitions(),
+    })
+}
+
+/// The schema of the object with the `properties` of a command, each with its name, whether it's required, and its schema.
+pub fn params_schema(properties: Vec<(&'static str, bool, Schema)>) -> Schema {
+    let required = properties
+        .iter()
+        .filter(|(_, required, _)| *required)
+        .map(|(name, _, _)| *name)
+        .collect::<Vec<_>>();
+    let properties = properties
+        .into_iter()
+        .map(|(name, _, schema)| (name.to_owned(), schema.to_value()))
+        .collect::<serde_json::Map<_, _>>();
+    schemars::json_schema!({
+        "type": "object",
+        "properties": properties,
+        "required": required,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_command_is_registered_once() {
+        let mut names = inventory::iter::<Command>
+            .into_iter()
+            .map(|command| command.name)
+            .collect::<Vec<_>>();
+        let count = names.len();
+        names.sort_unstable();
+        names.dedup();
+        assert_eq!(names.len(), count, "command names must be unique");
+        assert_eq!(commands().count(), count);
+        assert!(find("normalize_branch_name").is_some());
+    }
+
+    #[test]
+    fn every_command_has_schemas() {
+        let mut generator = SchemaGenerator::default();
+        for command in commands() {
+            let params = (command.params_schema)(&mut generator);
+            assert_eq!(
+                params.get("type"),
+                Some(&serde_json::json!("object")),
+                "{}",
+                command.name
+            );
+            let returns = (command.returns_schema)(&mut generator);
+            assert!(
+                returns.as_object().is_some_and(|schema| !schema.is_empty()),
+                "{} must describe its return value",
+                command.name
+            );
+        }
+        assert!(!generator.definitions().is_empty());
+    }
+
+    #[test]
+    fn call_known_command() {
+        let command = find("normalize_branch_name").expect("registered");
+        let result = (command.call)(serde_json::json!({ "name": "#[test]" })).expect("valid name");
+        assert_eq!(result, serde_json::json!("#-test]"));
+    }
+
+    #[test]
+    fn unknown_command_is_not_found() {
+        assert!(find("does_not_exist").is_none());
+    }
+}
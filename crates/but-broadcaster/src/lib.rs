@@ -1,6 +1,23 @@
-use std::collections::HashMap;
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Arc, Mutex},
+};
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+
+/// The amount of events a client may lag behind before the oldest ones are dropped.
+pub const DEFAULT_CAPACITY: usize = 1024;
+
+/// Events that describe the latest state of something, so a queued one is superseded by a newer one of the same name,
+/// which is queued at the end.
+const COALESCED_TOPICS: &[&str] = &[
+    "worktree_changes",
+    "git/head",
+    "git/activity",
+    "git/fetch",
+    "hunk-assignment-update",
+    "settings://update",
+];
 
 #[derive(Debug, Serialize, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -9,33 +26,236 @@ pub struct FrontendEvent {
     pub payload: serde_json::Value,
 }
 
+impl FrontendEvent {
+    /// The ID of the project this event is about, if its name is of the form `project://<id>/<topic>`.
+    pub fn project_id(&self) -> Option<&str> {
+        self.split_name().0
+    }
+
+    /// The name of the event without the project it's about, like `worktree_changes` or `settings://update`.
+    pub fn topic(&self) -> &str {
+        self.split_name().1
+    }
+
+    fn split_name(&self) -> (Option<&str>, &str) {
+        match self
+            .name
+            .strip_prefix("project://")
+            .and_then(|rest| rest.split_once('/'))
+        {
+            Some((project_id, topic)) => (Some(project_id), topic),
+            None => (None, &self.name),
+        }
+    }
+
+    fn is_coalesced(&self) -> bool {
+        COALESCED_TOPICS.contains(&self.topic())
+    }
+}
+
+/// A [`FrontendEvent`] as received by a single client.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SequencedEvent {
+    /// Increments by one for each event queued for the client, so a gap means that events were dropped
+    /// because the client didn't keep up.
+    pub seq: u64,
+    #[serde(flatten)]
+    pub event: FrontendEvent,
+}
+
+/// Selects the events a client receives.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Subscription {
+    /// The topic of the event, like `worktree_changes`, or `claude` to match all `claude/…` events.
+    /// `None` matches all topics.
+    pub topic: Option<String>,
+    /// The project the event is about. `None` matches events of all projects, and those not about a project.
+    pub project_id: Option<String>,
+}
+
+impl Subscription {
+    /// A subscription to all events.
+    pub fn all() -> Self {
+        Self::default()
+    }
+
+    fn matches(&self, event: &FrontendEvent) -> bool {
+        let topic_matches = self.topic.as_deref().is_none_or(|topic| {
+            event
+                .topic()
+                .strip_prefix(topic)
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+        });
+        let project_matches = self
+            .project_id
+            .as_deref()
+            .is_none_or(|project_id| event.project_id() == Some(project_id));
+        topic_matches && project_matches
+    }
+}
+
+struct State {
+    events: VecDeque<SequencedEvent>,
+    capacity: usize,
+    next_seq: u64,
+    sender_closed: bool,
+    receiver_closed: bool,
+}
+
+struct Queue {
+    state: Mutex<State>,
+    notify: tokio::sync::Notify,
+}
+
+impl Queue {
+    fn state(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+/// Create a queue holding at most `capacity` events, with the sending half to register with a [`Broadcaster`].
+///
+/// When the queue is full the oldest event is dropped, and queued events that describe the latest state of
+/// something, like worktree changes, are removed when a newer one is queued, so only the latest is received,
+/// in the order it was sent.
+pub fn channel(capacity: usize) -> (Sender, Receiver) {
+    let queue = Arc::new(Queue {
+        state: Mutex::new(State {
+            events: VecDeque::new(),
+            capacity: capacity.max(1),
+            next_seq: 0,
+            sender_closed: false,
+            receiver_closed: false,
+        }),
+        notify: tokio::sync::Notify::new(),
+    });
+    (
+        Sender {
+            queue: queue.clone(),
+        },
+        Receiver { queue },
+    )
+}
+
+pub struct Sender {
+    queue: Arc<Queue>,
+}
+
+impl Sender {
+    fn send(&self, event: FrontendEvent) {
+        let mut state = self.queue.state();
+        if state.receiver_closed {
+            return;
+        }
+        if event.is_coalesced() {
+            state
+                .events
+                .retain(|queued| queued.event.name != event.name);
+        }
+        if state.events.len() >= state.capacity {
+            state.events.pop_front();
+        }
+        let seq = state.next_seq;
+        state.next_seq += 1;
+        state.events.push_back(SequencedEvent { seq, event });
+        drop(state);
+        self.queue.notify.notify_one();
+    }
+}
+
+impl Drop for Sender {
+    fn drop(&mut self) {
+        self.queue.state().sender_closed = true;
+        self.queue.notify.notify_one();
+    }
+}
+
+pub struct Receiver {
+    queue: Arc<Queue>,
+}
+
+impl Receiver {
+    /// Wait for the next event, or return `None` once the sender was deregistered and all events were received.
+    pub async fn recv(&mut self) -> Option<SequencedEvent> {
+        loop {
+            {
+                let mut state = self.queue.state();
+                if let Some(event) = state.events.pop_front() {
+                    return Some(event);
+                }
+                if state.sender_closed {
+                    return None;
+                }
+            }
+            self.queue.notify.notified().await;
+        }
+    }
+}
+
+impl Drop for Receiver {
+    fn drop(&mut self) {
+        let mut state = self.queue.state();
+        state.receiver_closed = true;
+        state.events.clear();
+    }
+}
+
+struct Client {
+    sender: Sender,
+    subscriptions: Vec<Subscription>,
+}
+
 pub struct Broadcaster {
-    senders: HashMap<uuid::Uuid, tokio::sync::mpsc::UnboundedSender<FrontendEvent>>,
+    clients: HashMap<uuid::Uuid, Client>,
 }
 
 impl Broadcaster {
     pub fn new() -> Self {
         Self {
-            senders: HashMap::new(),
+            clients: HashMap::new(),
         }
     }
 
+    /// Queue `event` for each client with a matching subscription.
     pub fn send(&self, event: FrontendEvent) {
-        for sender in self.senders.values() {
-            let _ = sender.send(event.clone());
+        for client in self.clients.values() {
+            if client.subscriptions.iter().any(|s| s.matches(&event)) {
+                client.sender.send(event.clone());
+            }
         }
     }
 
-    pub fn register_sender(
-        &mut self,
-        id: &uuid::Uuid,
-        sender: tokio::sync::mpsc::UnboundedSender<FrontendEvent>,
-    ) {
-        self.senders.insert(*id, sender);
+    /// Register the client `id`, which is subscribed to all events until it unsubscribes from [`Subscription::all()`].
+    pub fn register_sender(&mut self, id: &uuid::Uuid, sender: Sender) {
+        self.clients.insert(
+            *id,
+            Client {
+                sender,
+                subscriptions: vec![Subscription::all()],
+            },
+        );
     }
 
     pub fn deregister_sender(&mut self, id: &uuid::Uuid) {
-        self.senders.remove(id);
+        self.clients.remove(id);
+    }
+
+    /// Let the client `id` also receive the events selected by `subscription`.
+    pub fn subscribe(&mut self, id: &uuid::Uuid, subscription: Subscription) {
+        if let Some(client) = self.clients.get_mut(id)
+            && !client.subscriptions.contains(&subscription)
+        {
+            client.subscriptions.push(subscription);
+        }
+    }
+
+    /// Remove `subscription` from the client `id`, which keeps receiving events matched by its other subscriptions.
+    pub fn unsubscribe(&mut self, id: &uuid::Uuid, subscription: &Subscription) {
+        if let Some(client) = self.clients.get_mut(id) {
+            client.subscriptions.retain(|s| s != subscription);
+        }
     }
 }
 
@@ -0,0 +1,106 @@
+use but_broadcaster::{
+    Broadcaster, FrontendEvent, Receiver, SequencedEvent, Subscription, channel,
+};
+
+fn event(name: &str, payload: u32) -> FrontendEvent {
+    FrontendEvent {
+        name: name.into(),
+        payload: payload.into(),
+    }
+}
+
+async fn received(recv: &mut Receiver) -> Vec<(u64, String, serde_json::Value)> {
+    let mut out = Vec::new();
+    while let Some(Some(event)) = try_recv(recv).await {
+        out.push((event.seq, event.event.name, event.event.payload));
+    }
+    out
+}
+
+/// Receive the next event, or `None` if none is immediately available.
+async fn try_recv(recv: &mut Receiver) -> Option<Option<SequencedEvent>> {
+    tokio::time::timeout(std::time::Duration::ZERO, recv.recv())
+        .await
+        .ok()
+}
+
+#[tokio::test]
+async fn clients_receive_only_subscribed_events() {
+    let mut broadcaster = Broadcaster::new();
+    let id = uuid::Uuid::new_v4();
+    let (send, mut recv) = channel(10);
+    broadcaster.register_sender(&id, send);
+
+    broadcaster.send(event("project://a/git/fetch", 1));
+    broadcaster.send(event("settings://update", 2));
+    assert_eq!(
+        received(&mut recv).await.len(),
+        2,
+        "everything is received by default"
+    );
+
+    broadcaster.unsubscribe(&id, &Subscription::all());
+    broadcaster.subscribe(
+        &id,
+        Subscription {
+            topic: Some("claude".into()),
+            project_id: Some("a".into()),
+        },
+    );
+    broadcaster.send(event("project://a/claude/s1/message_recieved", 3));
+    broadcaster.send(event("project://b/claude/s1/message_recieved", 4));
+    broadcaster.send(event("project://a/claude-permission-requests", 5));
+    broadcaster.send(event("settings://update", 6));
+    assert_eq!(
+        received(&mut recv).await,
+        [(2, "project://a/claude/s1/message_recieved".into(), 3.into())],
+        "sequence numbers count the events queued for this client"
+    );
+}
+
+#[tokio::test]
+async fn full_queues_drop_the_oldest_events_and_coalesce_state_updates() {
+    let mut broadcaster = Broadcaster::new();
+    let id = uuid::Uuid::new_v4();
+    let (send, mut recv) = channel(2);
+    broadcaster.register_sender(&id, send);
+
+    broadcaster.send(event("project://a/worktree_changes", 1));
+    broadcaster.send(event("project://a/db-updates", 2));
+    broadcaster.send(event("project://a/worktree_changes", 3));
+    broadcaster.send(event("project://b/worktree_changes", 4));
+    assert_eq!(
+        received(&mut recv).await,
+        [
+            (2, "project://a/worktree_changes".into(), 3.into()),
+            (3, "project://b/worktree_changes".into(), 4.into())
+        ],
+        "the superseded worktree changes were removed, and the oldest event was dropped to make room, leaving a gap"
+    );
+
+    broadcaster.deregister_sender(&id);
+    assert!(
+        matches!(try_recv(&mut recv).await, Some(None)),
+        "the receiver ends once its sender is gone"
+    );
+}
+
+#[tokio::test]
+async fn coalesced_events_are_received_in_the_order_they_were_sent() {
+    let mut broadcaster = Broadcaster::new();
+    let id = uuid::Uuid::new_v4();
+    let (send, mut recv) = channel(10);
+    broadcaster.register_sender(&id, send);
+
+    broadcaster.send(event("project://a/worktree_changes", 1));
+    broadcaster.send(event("project://a/git/head", 2));
+    broadcaster.send(event("project://a/worktree_changes", 3));
+    assert_eq!(
+        received(&mut recv).await,
+        [
+            (1, "project://a/git/head".into(), 2.into()),
+            (2, "project://a/worktree_changes".into(), 3.into())
+        ],
+        "the newer worktree changes were queued after the head change, with a new sequence number"
+    );
+}
@@ -33,9 +33,10 @@ use gitbutler_project::access::{WorktreeReadPermission, WorktreeWritePermission}
 use gitbutler_stack::VirtualBranchesHandle;
 use gitbutler_workspace::branch_trees::{WorkspaceState, update_uncommited_changes};
 use gix::{ObjectId, Repository};
+use schemars::JsonSchema;
 use serde::Serialize;
 
-#[derive(Debug, Clone, Serialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, PartialEq, JsonSchema)]
 #[serde(tag = "type", content = "subject", rename_all = "camelCase")]
 pub enum CherryApplyStatus {
     CausesWorkspaceConflict,
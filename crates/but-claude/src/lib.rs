@@ -4,6 +4,7 @@ use anyhow::Result;
 use but_broadcaster::{Broadcaster, FrontendEvent};
 use but_workspace::StackId;
 use gitbutler_command_context::CommandContext;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use tokio::sync::Mutex;
@@ -110,7 +111,7 @@ pub struct ClaudeSessionDetails {
 }
 
 /// Represents a request for permission to use a tool in the Claude MCP.
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ClaudePermissionRequest {
     /// Maps to the tool_use_id from the MCP request
@@ -2,6 +2,7 @@ use crate::Commit;
 use anyhow::{Context, bail};
 use bstr::{BString, ByteSlice};
 use gix::prelude::ObjectIdExt;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::str::FromStr;
 use std::{collections::HashSet, path::PathBuf};
@@ -298,7 +299,7 @@ impl Commit<'_> {
 }
 
 /// Represents what was causing a particular commit to conflict when rebased.
-#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ConflictEntries {
     /// The ancestors that were conflicted
@@ -1,5 +1,6 @@
 use std::{fmt, hash::Hash, str};
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use uuid::Uuid;
 
@@ -44,6 +45,16 @@ impl<'de, const KIND: char> Deserialize<'de> for Id<KIND> {
     }
 }
 
+impl<const KIND: char> JsonSchema for Id<KIND> {
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        Uuid::schema_name()
+    }
+
+    fn json_schema(generator: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        Uuid::json_schema(generator)
+    }
+}
+
 impl<const KIND: char> Serialize for Id<KIND> {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -45,6 +45,7 @@ use bstr::BString;
 use gix::object::tree::EntryKind;
 use gix::refs::FullNameRef;
 use gix::status::plumbing::index_as_worktree::ConflictIndexEntry;
+use schemars::JsonSchema;
 use serde::Serialize;
 use std::any::Any;
 use std::ops::{Deref, DerefMut};
@@ -159,7 +160,7 @@ pub struct Commit<'repo> {
 
 /// A patch in unified diff format to show how a resource changed or now looks like (in case it was newly added),
 /// or how it previously looked like in case of a deletion.
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, JsonSchema)]
 #[serde(tag = "type", content = "subject")]
 pub enum UnifiedDiff {
     /// The resource was a binary and couldn't be diffed.
@@ -317,7 +318,7 @@ pub struct ChangeState {
 }
 
 /// The status we can't handle, which always originated in the worktree.
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, JsonSchema)]
 pub enum IgnoredWorktreeTreeChangeStatus {
     /// A conflicting entry in the index. The worktree state of the entry is unclear.
     Conflict,
@@ -329,10 +330,11 @@ pub enum IgnoredWorktreeTreeChangeStatus {
 }
 
 /// A way to indicate that a path in the index isn't suitable for committing and needs to be dealt with.
-#[derive(Clone, Serialize)]
+#[derive(Clone, Serialize, JsonSchema)]
 pub struct IgnoredWorktreeChange {
     /// The worktree-relative path to the change.
     #[serde(serialize_with = "gitbutler_serde::bstring_lossy::serialize")]
+    #[schemars(with = "String")]
     pub path: BString,
     /// The status that caused this change to be ignored.
     pub status: IgnoredWorktreeTreeChangeStatus,
@@ -147,7 +147,7 @@ impl Workspace {
 }
 
 /// Metadata about branches, associated with any Git branch.
-#[derive(serde::Serialize, Clone, Eq, PartialEq, Default)]
+#[derive(serde::Serialize, Clone, Eq, PartialEq, Default, schemars::JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Branch {
     /// Standard data we want to know about any ref.
@@ -211,15 +211,25 @@ impl<T: std::fmt::Debug> std::fmt::Debug for MaybeDebug<'_, T> {
 ///
 /// It allows keeping track of when it changed, but also if we created it initially, a useful
 /// bit of information.
-#[derive(serde::Serialize, Default, Clone, Eq, PartialEq)]
+#[derive(serde::Serialize, Default, Clone, Eq, PartialEq, schemars::JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct RefInfo {
     /// The time of creation, *if we created the reference*.
+    #[schemars(with = "Option<Time>")]
     pub created_at: Option<gix::date::Time>,
     /// The time at which the reference was last modified if we modified it.
+    #[schemars(with = "Option<Time>")]
     pub updated_at: Option<gix::date::Time>,
 }
 
+/// The schema of a serialized [`gix::date::Time`].
+#[derive(schemars::JsonSchema)]
+#[allow(dead_code)]
+struct Time {
+    seconds: i64,
+    offset: i32,
+}
+
 /// Mutations
 impl RefInfo {
     /// Set the `updated_at` field to the current time.
@@ -299,7 +309,7 @@ impl WorkspaceStack {
 }
 
 /// Metadata about branches, associated with any Git branch.
-#[derive(serde::Serialize, Clone, Eq, PartialEq, Default)]
+#[derive(serde::Serialize, Clone, Eq, PartialEq, Default, schemars::JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Review {
     /// The number for the PR that was associated with this branch.
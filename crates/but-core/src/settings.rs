@@ -20,7 +20,15 @@ pub mod git {
         use gitbutler_serde::BStringForFrontend;
 
         /// See [`GitConfigSettings`](crate::GitConfigSettings) for the docs.
-        #[derive(Debug, PartialEq, Clone, Default, serde::Serialize, serde::Deserialize)]
+        #[derive(
+            Debug,
+            PartialEq,
+            Clone,
+            Default,
+            serde::Serialize,
+            serde::Deserialize,
+            schemars::JsonSchema,
+        )]
         #[serde(rename_all = "camelCase")]
         #[expect(missing_docs)]
         pub struct GitConfigSettings {
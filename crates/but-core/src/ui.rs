@@ -3,10 +3,11 @@ use crate::{IgnoredWorktreeChange, UnifiedDiff};
 use bstr::BString;
 use gitbutler_serde::BStringForFrontend;
 use gix::object::tree::EntryKind;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 /// The type returned by [`crate::diff::worktree_changes()`].
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, JsonSchema)]
 pub struct WorktreeChanges {
     /// Changes that could be committed.
     pub changes: Vec<TreeChange>,
@@ -41,7 +42,7 @@ impl From<crate::WorktreeChanges> for WorktreeChanges {
 }
 
 /// All the changes that were made to the tree, including stats
-#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+#[derive(Default, Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct TreeChanges {
     /// The changes that were made to the tree.
@@ -106,11 +107,12 @@ fn changes_to_unidiff_string(
     Ok(builder)
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct TreeChange {
     pub path: BStringForFrontend,
     /// Something silently carried back and forth between the frontend and the backend.
+    #[schemars(with = "Vec<u8>")]
     pub path_bytes: BString,
     pub status: TreeStatus,
 }
@@ -125,7 +127,7 @@ impl From<gix::object::tree::diff::Stats> for TreeStats {
     }
 }
 
-#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+#[derive(Default, Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct TreeStats {
     /// The total amount of lines added.
@@ -136,7 +138,7 @@ pub struct TreeStats {
     pub files_changed: u64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(tag = "type", content = "subject")]
 pub enum TreeStatus {
     Addition {
@@ -159,6 +161,7 @@ pub enum TreeStatus {
         previous_path: BStringForFrontend,
         /// Something silently carried back and forth between the frontend and the backend.
         #[serde(rename = "previousPathBytes")]
+        #[schemars(with = "Vec<u8>")]
         previous_path_bytes: BString,
         #[serde(rename = "previousState")]
         previous_state: ChangeState,
@@ -243,14 +246,16 @@ impl From<crate::TreeStatus> for TreeStatus {
     }
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema)]
 pub struct ChangeState {
     #[serde(with = "gitbutler_serde::object_id")]
+    #[schemars(with = "String")]
     pub id: gix::ObjectId,
+    #[schemars(schema_with = "gitbutler_serde::schema::entry_kind")]
     pub kind: EntryKind,
 }
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[expect(missing_docs)]
 pub enum ModeFlags {
     ExecutableBitAdded,
@@ -3,10 +3,11 @@ use bstr::{BStr, BString, ByteSlice};
 use gix::diff::blob::ResourceKind;
 use gix::diff::blob::platform::prepare_diff::Operation;
 use gix::diff::blob::unified_diff::{ConsumeBinaryHunk, ContextSize, HunkHeader};
+use schemars::JsonSchema;
 use serde::Serialize;
 
 /// A hunk as used in a [UnifiedDiff], which also contains all added and removed lines.
-#[derive(Clone, Serialize)]
+#[derive(Clone, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct DiffHunk {
     /// The 1-based line number at which the previous version of the file started.
@@ -34,6 +35,7 @@ pub struct DiffHunk {
     /// Also note that this has possibly been decoded lossily, assuming UTF8 if the encoding couldn't be determined,
     /// replacing invalid codepoints with markers.
     #[serde(serialize_with = "gitbutler_serde::bstring_lossy::serialize")]
+    #[schemars(with = "String")]
     pub diff: BString,
 }
 
@@ -0,0 +1,17 @@
+-- This file should undo anything in `up.sql`
+DROP TRIGGER IF EXISTS `butler_actions_insert_counter`;
+DROP TRIGGER IF EXISTS `butler_actions_update_counter`;
+DROP TRIGGER IF EXISTS `butler_actions_delete_counter`;
+DROP TRIGGER IF EXISTS `workflows_insert_counter`;
+DROP TRIGGER IF EXISTS `workflows_update_counter`;
+DROP TRIGGER IF EXISTS `workflows_delete_counter`;
+DROP TRIGGER IF EXISTS `hunk_assignments_insert_counter`;
+DROP TRIGGER IF EXISTS `hunk_assignments_update_counter`;
+DROP TRIGGER IF EXISTS `hunk_assignments_delete_counter`;
+DROP TRIGGER IF EXISTS `workspace_rules_insert_counter`;
+DROP TRIGGER IF EXISTS `workspace_rules_update_counter`;
+DROP TRIGGER IF EXISTS `workspace_rules_delete_counter`;
+DROP TRIGGER IF EXISTS `claude_permission_requests_insert_counter`;
+DROP TRIGGER IF EXISTS `claude_permission_requests_update_counter`;
+DROP TRIGGER IF EXISTS `claude_permission_requests_delete_counter`;
+DROP TABLE IF EXISTS `change_counters`;
@@ -0,0 +1,56 @@
+-- Count the changes to watched tables, so connections in other processes can tell which of them changed.
+CREATE TABLE `change_counters`(
+	`table_name` TEXT NOT NULL PRIMARY KEY,
+	`counter` BIGINT NOT NULL
+);
+INSERT INTO `change_counters` (`table_name`, `counter`) VALUES
+	('butler_actions', 0),
+	('workflows', 0),
+	('hunk_assignments', 0),
+	('workspace_rules', 0),
+	('claude_permission_requests', 0);
+CREATE TRIGGER `butler_actions_insert_counter` AFTER INSERT ON `butler_actions` BEGIN
+	UPDATE `change_counters` SET `counter` = `counter` + 1 WHERE `table_name` = 'butler_actions';
+END;
+CREATE TRIGGER `butler_actions_update_counter` AFTER UPDATE ON `butler_actions` BEGIN
+	UPDATE `change_counters` SET `counter` = `counter` + 1 WHERE `table_name` = 'butler_actions';
+END;
+CREATE TRIGGER `butler_actions_delete_counter` AFTER DELETE ON `butler_actions` BEGIN
+	UPDATE `change_counters` SET `counter` = `counter` + 1 WHERE `table_name` = 'butler_actions';
+END;
+CREATE TRIGGER `workflows_insert_counter` AFTER INSERT ON `workflows` BEGIN
+	UPDATE `change_counters` SET `counter` = `counter` + 1 WHERE `table_name` = 'workflows';
+END;
+CREATE TRIGGER `workflows_update_counter` AFTER UPDATE ON `workflows` BEGIN
+	UPDATE `change_counters` SET `counter` = `counter` + 1 WHERE `table_name` = 'workflows';
+END;
+CREATE TRIGGER `workflows_delete_counter` AFTER DELETE ON `workflows` BEGIN
+	UPDATE `change_counters` SET `counter` = `counter` + 1 WHERE `table_name` = 'workflows';
+END;
+CREATE TRIGGER `hunk_assignments_insert_counter` AFTER INSERT ON `hunk_assignments` BEGIN
+	UPDATE `change_counters` SET `counter` = `counter` + 1 WHERE `table_name` = 'hunk_assignments';
+END;
+CREATE TRIGGER `hunk_assignments_update_counter` AFTER UPDATE ON `hunk_assignments` BEGIN
+	UPDATE `change_counters` SET `counter` = `counter` + 1 WHERE `table_name` = 'hunk_assignments';
+END;
+CREATE TRIGGER `hunk_assignments_delete_counter` AFTER DELETE ON `hunk_assignments` BEGIN
+	UPDATE `change_counters` SET `counter` = `counter` + 1 WHERE `table_name` = 'hunk_assignments';
+END;
+CREATE TRIGGER `workspace_rules_insert_counter` AFTER INSERT ON `workspace_rules` BEGIN
+	UPDATE `change_counters` SET `counter` = `counter` + 1 WHERE `table_name` = 'workspace_rules';
+END;
+CREATE TRIGGER `workspace_rules_update_counter` AFTER UPDATE ON `workspace_rules` BEGIN
+	UPDATE `change_counters` SET `counter` = `counter` + 1 WHERE `table_name` = 'workspace_rules';
+END;
+CREATE TRIGGER `workspace_rules_delete_counter` AFTER DELETE ON `workspace_rules` BEGIN
+	UPDATE `change_counters` SET `counter` = `counter` + 1 WHERE `table_name` = 'workspace_rules';
+END;
+CREATE TRIGGER `claude_permission_requests_insert_counter` AFTER INSERT ON `claude_permission_requests` BEGIN
+	UPDATE `change_counters` SET `counter` = `counter` + 1 WHERE `table_name` = 'claude_permission_requests';
+END;
+CREATE TRIGGER `claude_permission_requests_update_counter` AFTER UPDATE ON `claude_permission_requests` BEGIN
+	UPDATE `change_counters` SET `counter` = `counter` + 1 WHERE `table_name` = 'claude_permission_requests';
+END;
+CREATE TRIGGER `claude_permission_requests_delete_counter` AFTER DELETE ON `claude_permission_requests` BEGIN
+	UPDATE `change_counters` SET `counter` = `counter` + 1 WHERE `table_name` = 'claude_permission_requests';
+END;
@@ -1,6 +1,7 @@
 use diesel::dsl::count_star;
 use diesel::{ExpressionMethods, QueryDsl, RunQueryDsl, associations::HasTable};
 
+use crate::poll::ItemKind;
 use crate::schema::butler_actions::dsl::butler_actions;
 use crate::{DbHandle, schema::butler_actions as schema};
 
@@ -48,6 +49,7 @@ impl ButlerActionsHandle<'_> {
         diesel::insert_into(butler_actions)
             .values(&action)
             .execute(&mut self.db.conn)?;
+        self.db.notify(ItemKind::Actions);
         Ok(())
     }
 
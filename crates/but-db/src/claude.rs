@@ -1,6 +1,7 @@
 use diesel::{ExpressionMethods, QueryDsl, RunQueryDsl};
 
 use crate::DbHandle;
+use crate::poll::ItemKind;
 use crate::schema::claude_messages::dsl::claude_messages;
 use crate::schema::claude_sessions::dsl::claude_sessions;
 use diesel::prelude::*;
@@ -112,11 +113,12 @@ impl ClaudePermissionRequestsHandle<'_> {
         diesel::insert_into(crate::schema::claude_permission_requests::table)
             .values(request)
             .execute(&mut self.db.conn)?;
+        self.db.notify(ItemKind::ClaudePermissionRequests);
         Ok(())
     }
 
     pub fn set_approval(&mut self, id: &str, approved: bool) -> Result<(), diesel::result::Error> {
-        diesel::update(
+        let changed = diesel::update(
             crate::schema::claude_permission_requests::table
                 .filter(crate::schema::claude_permission_requests::id.eq(id)),
         )
@@ -126,6 +128,9 @@ impl ClaudePermissionRequestsHandle<'_> {
                 .eq(chrono::Local::now().naive_local()),
         ))
         .execute(&mut self.db.conn)?;
+        if changed > 0 {
+            self.db.notify(ItemKind::ClaudePermissionRequests);
+        }
         Ok(())
     }
 
@@ -141,11 +146,14 @@ impl ClaudePermissionRequestsHandle<'_> {
     }
 
     pub fn delete(&mut self, id: &str) -> Result<(), diesel::result::Error> {
-        diesel::delete(
+        let changed = diesel::delete(
             crate::schema::claude_permission_requests::table
                 .filter(crate::schema::claude_permission_requests::id.eq(id)),
         )
         .execute(&mut self.db.conn)?;
+        if changed > 0 {
+            self.db.notify(ItemKind::ClaudePermissionRequests);
+        }
         Ok(())
     }
 
@@ -1,5 +1,6 @@
 use diesel::RunQueryDsl;
 
+use crate::poll::ItemKind;
 use crate::{DbHandle, schema::hunk_assignments::dsl::*};
 
 use diesel::prelude::{Insertable, Queryable, Selectable};
@@ -35,12 +36,16 @@ impl HunkAssignmentsHandle<'_> {
 
     /// Sets the hunk assignments in the database to the provided values. Any existing entries
     /// that are not in the provided values are deleted.
+    /// Nothing is written if the assignments didn't change, so watchers aren't informed needlessly.
     pub fn set_all(&mut self, assignments: Vec<HunkAssignment>) -> anyhow::Result<()> {
         // Set the hunk_assignments table to the values in `assignments`.
         // Any existing entries that are not in `assignments` are deleted.
         use crate::schema::hunk_assignments::dsl::hunk_assignments as all_assignments;
         use diesel::prelude::*;
-        self.db.conn.transaction(|conn| {
+        let changed = self.db.conn.transaction(|conn| {
+            if all_assignments.load::<HunkAssignment>(conn)? == assignments {
+                return diesel::result::QueryResult::Ok(false);
+            }
             // Delete all existing assignments
             diesel::delete(all_assignments).execute(conn)?;
             // Insert the new assignments
@@ -49,8 +54,11 @@ impl HunkAssignmentsHandle<'_> {
                     .values(&assignment)
                     .execute(conn)?;
             }
-            diesel::result::QueryResult::Ok(())
+            diesel::result::QueryResult::Ok(true)
         })?;
+        if changed {
+            self.db.notify(ItemKind::Assignments);
+        }
         Ok(())
     }
 }
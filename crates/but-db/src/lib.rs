@@ -98,5 +98,5 @@ fn run_migrations(
     }
 }
 
-/// polling primitives
+/// Notifications about changes, pushed for writes in this process and polled for writes by other processes.
 pub mod poll;
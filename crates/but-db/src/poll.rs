@@ -1,5 +1,11 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{LazyLock, Mutex, MutexGuard};
+
 use crate::DbHandle;
 use bitflags::bitflags;
+use diesel::sql_types::BigInt;
+use diesel::{QueryDsl, QueryableByName, RunQueryDsl};
 use tokio::sync::oneshot;
 use tokio::task::JoinHandle;
 
@@ -15,214 +21,264 @@ bitflags! {
     }
 }
 
+/// The tables whose changes are counted in `change_counters`, and the kind of data they hold.
+const TABLES: &[(ItemKind, &str)] = &[
+    (ItemKind::Actions, "butler_actions"),
+    (ItemKind::Workflows, "workflows"),
+    (ItemKind::Assignments, "hunk_assignments"),
+    (ItemKind::Rules, "workspace_rules"),
+    (
+        ItemKind::ClaudePermissionRequests,
+        "claude_permission_requests",
+    ),
+];
+
+/// Receives the kind of data that was changed.
+type Subscriber = Box<dyn Fn(ItemKind) + Send>;
+
+/// Subscribers to changes made in this process, by the URL of the database they watch, along with their ID.
+static SUBSCRIBERS: LazyLock<Mutex<HashMap<String, Vec<(u64, Subscriber)>>>> =
+    LazyLock::new(Default::default);
+
+fn subscribers() -> MutexGuard<'static, HashMap<String, Vec<(u64, Subscriber)>>> {
+    SUBSCRIBERS.lock().unwrap_or_else(|err| err.into_inner())
+}
+
+/// A subscriber registered with [`DbHandle::subscribe()`], which is unregistered when this is dropped.
+struct Subscription {
+    url: String,
+    id: u64,
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        let mut subscribers = subscribers();
+        if let Some(url_subscribers) = subscribers.get_mut(&self.url) {
+            url_subscribers.retain(|(id, _)| *id != self.id);
+            if url_subscribers.is_empty() {
+                subscribers.remove(&self.url);
+            }
+        }
+    }
+}
+
+/// Receives the changes reported by [`DbHandle::poll_changes()`], one kind per message.
+/// Dropping it stops the watching.
+pub struct ChangeReceiver {
+    rx: std::sync::mpsc::Receiver<anyhow::Result<ItemKind>>,
+    /// Dropping it disconnects the watcher thread, which then stops.
+    _subscription: Subscription,
+}
+
+impl ChangeReceiver {
+    /// Wait for the next change, like [`std::sync::mpsc::Receiver::recv()`].
+    pub fn recv(&self) -> Result<anyhow::Result<ItemKind>, std::sync::mpsc::RecvError> {
+        self.rx.recv()
+    }
+
+    /// Wait for the next change for at most `timeout`, like [`std::sync::mpsc::Receiver::recv_timeout()`].
+    pub fn recv_timeout(
+        &self,
+        timeout: std::time::Duration,
+    ) -> Result<anyhow::Result<ItemKind>, std::sync::mpsc::RecvTimeoutError> {
+        self.rx.recv_timeout(timeout)
+    }
+}
+
+impl Iterator for ChangeReceiver {
+    type Item = anyhow::Result<ItemKind>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.rx.recv().ok()
+    }
+}
+
 impl DbHandle {
-    /// Register polling at `interval` for any `kind` of data and return a channel to be informed about the changes
-    /// for the respective kind.
-    /// Drop the receiver for the polling to stop.
-    /// Note that this opens a new connection.
-    ///
-    /// ### Shortcoming
+    /// Inform everybody watching this database in the current process that data of `kind` was changed.
+    pub(crate) fn notify(&self, kind: ItemKind) {
+        if let Some(subscribers) = subscribers().get(&self.url) {
+            for (_, subscriber) in subscribers {
+                subscriber(kind);
+            }
+        }
+    }
+
+    /// Call `subscriber` with each change made in this process until the returned subscription is dropped.
+    fn subscribe(&self, subscriber: Subscriber) -> Subscription {
+        static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+        let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+        subscribers()
+            .entry(self.url.clone())
+            .or_default()
+            .push((id, subscriber));
+        Subscription {
+            url: self.url.clone(),
+            id,
+        }
+    }
+
+    /// Register for changes to any `kind` of data and return a receiver to be informed about the changes
+    /// for the respective kind, one kind per message.
+    /// Drop the receiver for the watching to stop.
     ///
-    /// The current implementation will send a change event the first time it starts up.
+    /// Changes made through any handle of this process are sent right away. Changes made by other processes,
+    /// like the `but` CLI, are picked up by checking the change counters of the database every `interval`.
+    /// Note that this opens a new connection.
     pub fn poll_changes(
         &self,
         kind: ItemKind,
         interval: std::time::Duration,
-    ) -> anyhow::Result<std::sync::mpsc::Receiver<anyhow::Result<ItemKind>>> {
+    ) -> anyhow::Result<ChangeReceiver> {
         let (tx, rx) = std::sync::mpsc::channel();
-        let mut this = DbHandle::new_at_url(&self.url)?;
+        let mut watcher = Watcher::new(DbHandle::new_at_url(&self.url)?, kind)?;
+        let (local_tx, local_rx) = std::sync::mpsc::channel();
+        let subscription = self.subscribe(Box::new(move |kind| {
+            local_tx.send(kind).ok();
+        }));
         std::thread::Builder::new()
             .name("Gitbutler-DB-watcher".into())
             .spawn(move || {
-                let mut prev_assignments = Vec::new();
-                let mut prev_workflows = Vec::new();
-                let mut prev_actions = Vec::new();
-                let mut prev_rules = Vec::new();
-                let mut prev_claude_requests = Vec::new();
-                'outer: loop {
-                    std::thread::sleep(interval);
-                    for to_check in ItemKind::all().iter() {
-                        let send_result = if kind & to_check == ItemKind::Actions {
-                            let res = this.butler_actions().list(0, i64::MAX);
-                            match res {
-                                Ok((_num_items, items)) => {
-                                    if items != prev_actions {
-                                        prev_actions = items;
-                                        tx.send(Ok(ItemKind::Actions))
-                                    } else {
-                                        continue;
-                                    }
-                                }
-                                Err(e) => tx.send(Err(e)),
-                            }
-                        } else if kind & to_check == ItemKind::Workflows {
-                            let res = this.workflows().list(0, i64::MAX);
-                            match res {
-                                Ok((_num_items, items)) => {
-                                    if items != prev_workflows {
-                                        prev_workflows = items;
-                                        tx.send(Ok(ItemKind::Workflows))
-                                    } else {
-                                        continue;
-                                    }
-                                }
-                                Err(e) => tx.send(Err(e)),
-                            }
-                        } else if kind & to_check == ItemKind::Assignments {
-                            let res = this.hunk_assignments().list_all();
-                            match res {
-                                Ok(items) => {
-                                    if items != prev_assignments {
-                                        prev_assignments = items;
-                                        tx.send(Ok(ItemKind::Assignments))
-                                    } else {
-                                        continue;
-                                    }
-                                }
-                                Err(e) => tx.send(Err(e)),
-                            }
-                        } else if kind & to_check == ItemKind::Rules {
-                            let res = this.workspace_rules().list();
-                            match res {
-                                Ok(items) => {
-                                    if items != prev_rules {
-                                        prev_rules = items;
-                                        tx.send(Ok(ItemKind::Rules))
-                                    } else {
-                                        continue;
-                                    }
-                                }
-                                Err(e) => tx.send(Err(anyhow::Error::from(e))),
-                            }
-                        } else if kind & to_check == ItemKind::ClaudePermissionRequests {
-                            let res = this.claude_permission_requests().list();
-                            match res {
-                                Ok(items) => {
-                                    if items != prev_claude_requests {
-                                        prev_claude_requests = items;
-                                        tx.send(Ok(ItemKind::ClaudePermissionRequests))
-                                    } else {
-                                        continue;
-                                    }
-                                }
-                                Err(e) => tx.send(Err(anyhow::Error::from(e))),
-                            }
-                        } else {
-                            eprintln!("BUG: didn't implement a branch for {to_check:?}");
-                            break 'outer;
-                        };
-                        if send_result.is_err() {
-                            break 'outer;
-                        }
+                loop {
+                    let changes = match local_rx.recv_timeout(interval) {
+                        Ok(local) => watcher.local_change(local),
+                        Err(std::sync::mpsc::RecvTimeoutError::Timeout) => watcher.check(),
+                        Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => break,
+                    };
+                    let send_result = match changes {
+                        Ok(changes) => changes.iter().try_for_each(|kind| tx.send(Ok(kind))),
+                        Err(err) => tx.send(Err(err)),
+                    };
+                    if send_result.is_err() {
+                        break;
                     }
                 }
             })?;
-        Ok(rx)
+        Ok(ChangeReceiver {
+            rx,
+            _subscription: subscription,
+        })
     }
 
-    /// Register async polling at `interval` for any `kind` of data and return a channel to be informed about the changes
-    /// for the respective kind. Uses async tokio task and channel for efficiency.
+    /// Register for changes to any `kind` of data and return a channel to be informed about the changes
+    /// for the respective kind, like [`poll_changes()`](Self::poll_changes()), but with an async tokio task and channel.
+    /// The task stops once the receiver is dropped.
     pub fn poll_changes_async(
         &self,
         kind: ItemKind,
         interval: std::time::Duration,
     ) -> anyhow::Result<tokio::sync::mpsc::Receiver<anyhow::Result<ItemKind>>> {
         let (tx, rx) = tokio::sync::mpsc::channel(8);
-        let url = self.url.clone();
+        let mut watcher = Watcher::new(DbHandle::new_at_url(&self.url)?, kind)?;
+        let (local_tx, mut local_rx) = tokio::sync::mpsc::unbounded_channel();
+        let subscription = self.subscribe(Box::new(move |kind| {
+            local_tx.send(kind).ok();
+        }));
         tokio::spawn(async move {
-            let mut this = match DbHandle::new_at_url(&url) {
-                Ok(db) => db,
-                Err(e) => {
-                    let _ = tx.send(Err(e)).await;
-                    return;
-                }
-            };
-            let mut prev_assignments = Vec::new();
-            let mut prev_workflows = Vec::new();
-            let mut prev_actions = Vec::new();
-            let mut prev_rules = Vec::new();
-            let mut prev_claude_requests = Vec::new();
+            let _subscription = subscription;
             let mut ticker = tokio::time::interval(interval);
             loop {
-                ticker.tick().await;
-                for to_check in ItemKind::all().iter() {
-                    let send_result = if kind & to_check == ItemKind::Actions {
-                        let res = this.butler_actions().list(0, i64::MAX);
-                        match res {
-                            Ok((_num_items, items)) => {
-                                if items != prev_actions {
-                                    prev_actions = items;
-                                    tx.send(Ok(ItemKind::Actions)).await
-                                } else {
-                                    continue;
-                                }
-                            }
-                            Err(e) => tx.send(Err(e)).await,
-                        }
-                    } else if kind & to_check == ItemKind::Workflows {
-                        let res = this.workflows().list(0, i64::MAX);
-                        match res {
-                            Ok((_num_items, items)) => {
-                                if items != prev_workflows {
-                                    prev_workflows = items;
-                                    tx.send(Ok(ItemKind::Workflows)).await
-                                } else {
-                                    continue;
-                                }
-                            }
-                            Err(e) => tx.send(Err(e)).await,
-                        }
-                    } else if kind & to_check == ItemKind::Assignments {
-                        let res = this.hunk_assignments().list_all();
-                        match res {
-                            Ok(items) => {
-                                if items != prev_assignments {
-                                    prev_assignments = items;
-                                    tx.send(Ok(ItemKind::Assignments)).await
-                                } else {
-                                    continue;
-                                }
+                let changes = tokio::select! {
+                    _ = tx.closed() => return,
+                    local = local_rx.recv() => match local {
+                        Some(local) => watcher.local_change(local),
+                        None => return,
+                    },
+                    _ = ticker.tick() => watcher.check(),
+                };
+                match changes {
+                    Ok(changes) => {
+                        for kind in changes.iter() {
+                            if tx.send(Ok(kind)).await.is_err() {
+                                return;
                             }
-                            Err(e) => tx.send(Err(e)).await,
                         }
-                    } else if kind & to_check == ItemKind::Rules {
-                        let res = this.workspace_rules().list();
-                        match res {
-                            Ok(items) => {
-                                if items != prev_rules {
-                                    prev_rules = items;
-                                    tx.send(Ok(ItemKind::Rules)).await
-                                } else {
-                                    continue;
-                                }
-                            }
-                            Err(e) => tx.send(Err(anyhow::Error::from(e))).await,
-                        }
-                    } else if kind & to_check == ItemKind::ClaudePermissionRequests {
-                        let res = this.claude_permission_requests().list();
-                        match res {
-                            Ok(items) => {
-                                if items != prev_claude_requests {
-                                    prev_claude_requests = items;
-                                    tx.send(Ok(ItemKind::ClaudePermissionRequests)).await
-                                } else {
-                                    continue;
-                                }
-                            }
-                            Err(e) => tx.send(Err(anyhow::Error::from(e))).await,
+                    }
+                    Err(err) => {
+                        if tx.send(Err(err)).await.is_err() {
+                            return;
                         }
-                    } else {
-                        eprintln!("BUG: didn't implement a branch for {to_check:?}");
-                        return;
-                    };
-                    if send_result.is_err() {
-                        return;
                     }
                 }
             }
         });
         Ok(rx)
     }
+
+    /// The value of `PRAGMA data_version`, which changes whenever another connection commits to the database.
+    fn data_version(&mut self) -> anyhow::Result<i64> {
+        #[derive(QueryableByName)]
+        struct DataVersion {
+            #[diesel(sql_type = BigInt)]
+            data_version: i64,
+        }
+        Ok(diesel::sql_query("PRAGMA data_version")
+            .get_result::<DataVersion>(&mut self.conn)?
+            .data_version)
+    }
+
+    fn change_counters(&mut self) -> anyhow::Result<HashMap<String, i64>> {
+        use crate::schema::change_counters::dsl::{change_counters, counter, table_name};
+        Ok(change_counters
+            .select((table_name, counter))
+            .load::<(String, i64)>(&mut self.conn)?
+            .into_iter()
+            .collect())
+    }
+}
+
+/// Turns changes made in this process and the change counters of the database into the changes to report.
+struct Watcher {
+    /// A connection of its own, so `data_version` changes with every commit to the database.
+    db: DbHandle,
+    /// The kinds of data to report changes for.
+    kind: ItemKind,
+    data_version: i64,
+    counters: HashMap<String, i64>,
+}
+
+impl Watcher {
+    fn new(mut db: DbHandle, kind: ItemKind) -> anyhow::Result<Self> {
+        let data_version = db.data_version()?;
+        let counters = db.change_counters()?;
+        Ok(Watcher {
+            db,
+            kind,
+            data_version,
+            counters,
+        })
+    }
+
+    /// Report the changes since the last check right away, as `local` was changed in this process.
+    ///
+    /// The change is reported through the counters it changed, so it's reported once, no matter if the
+    /// [check](Self::check()) of the last interval already saw it or the next one would.
+    fn local_change(&mut self, local: ItemKind) -> anyhow::Result<ItemKind> {
+        if (local & self.kind).is_empty() {
+            return Ok(ItemKind::empty());
+        }
+        self.data_version = self.db.data_version()?;
+        Ok(self.changed_counters()? & self.kind)
+    }
+
+    /// Report the changes made by other connections since the last check, which is cheap if there were none.
+    fn check(&mut self) -> anyhow::Result<ItemKind> {
+        let data_version = self.db.data_version()?;
+        if data_version == self.data_version {
+            return Ok(ItemKind::empty());
+        }
+        self.data_version = data_version;
+        Ok(self.changed_counters()? & self.kind)
+    }
+
+    fn changed_counters(&mut self) -> anyhow::Result<ItemKind> {
+        let counters = self.db.change_counters()?;
+        let changed = TABLES
+            .iter()
+            .filter(|(_, table)| counters.get(*table) != self.counters.get(*table))
+            .fold(ItemKind::empty(), |changed, (kind, _)| changed | *kind);
+        self.counters = counters;
+        Ok(changed)
+    }
 }
 
 pub struct DBWatcherHandle {
@@ -289,3 +345,95 @@ pub fn watch_in_background(
 
     Ok(watcher_handle)
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{ItemKind, Watcher, subscribers};
+    use crate::{DbHandle, WorkspaceRule};
+
+    fn rule(id: &str) -> WorkspaceRule {
+        WorkspaceRule {
+            id: id.into(),
+            created_at: chrono::Local::now().naive_local(),
+            enabled: true,
+            trigger: "fileSytemChange".into(),
+            filters: "[]".into(),
+            action: "{}".into(),
+        }
+    }
+
+    fn subscriber_count(db: &DbHandle) -> usize {
+        subscribers().get(&db.url).map_or(0, Vec::len)
+    }
+
+    #[test]
+    fn local_write_seen_by_an_interval_check_first_is_reported_once() -> anyhow::Result<()> {
+        let tmp = tempfile::tempdir()?;
+        let mut db = DbHandle::new_in_directory(tmp.path())?;
+        let mut watcher = Watcher::new(DbHandle::new_at_url(&db.url)?, ItemKind::all())?;
+
+        db.workspace_rules().insert(rule("1"))?;
+        assert_eq!(
+            watcher.check()?,
+            ItemKind::Rules,
+            "the interval check runs before the local notification arrives"
+        );
+        assert_eq!(
+            watcher.local_change(ItemKind::Rules)?,
+            ItemKind::empty(),
+            "the notification of the write that was already reported adds nothing"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn local_write_reported_right_away_isnt_reported_by_the_interval_check() -> anyhow::Result<()> {
+        let tmp = tempfile::tempdir()?;
+        let mut db = DbHandle::new_in_directory(tmp.path())?;
+        let mut watcher = Watcher::new(DbHandle::new_at_url(&db.url)?, ItemKind::all())?;
+
+        db.workspace_rules().insert(rule("1"))?;
+        assert_eq!(watcher.local_change(ItemKind::Rules)?, ItemKind::Rules);
+        assert_eq!(watcher.check()?, ItemKind::empty());
+
+        db.workspace_rules().insert(rule("2"))?;
+        db.workspace_rules().insert(rule("3"))?;
+        assert_eq!(
+            watcher.local_change(ItemKind::Rules)?,
+            ItemKind::Rules,
+            "writes made before a notification is handled are reported together"
+        );
+        assert_eq!(watcher.local_change(ItemKind::Rules)?, ItemKind::empty());
+        assert_eq!(watcher.check()?, ItemKind::empty());
+        Ok(())
+    }
+
+    #[test]
+    fn dropping_the_receiver_unregisters_the_subscriber() -> anyhow::Result<()> {
+        let tmp = tempfile::tempdir()?;
+        let db = DbHandle::new_in_directory(tmp.path())?;
+        let rx = db.poll_changes(ItemKind::all(), Duration::from_secs(3600))?;
+        assert_eq!(subscriber_count(&db), 1);
+        drop(rx);
+        assert_eq!(subscriber_count(&db), 0);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn dropping_the_async_receiver_unregisters_the_subscriber() -> anyhow::Result<()> {
+        let tmp = tempfile::tempdir()?;
+        let db = DbHandle::new_in_directory(tmp.path())?;
+        let rx = db.poll_changes_async(ItemKind::all(), Duration::from_secs(3600))?;
+        assert_eq!(subscriber_count(&db), 1);
+        drop(rx);
+        tokio::time::timeout(Duration::from_secs(10), async {
+            while subscriber_count(&db) != 0 {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await?;
+        Ok(())
+    }
+}
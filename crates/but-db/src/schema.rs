@@ -86,3 +86,10 @@ diesel::table! {
         approved -> Nullable<Bool>,
     }
 }
+
+diesel::table! {
+    change_counters (table_name) {
+        table_name -> Text,
+        counter -> BigInt,
+    }
+}
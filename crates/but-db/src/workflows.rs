@@ -1,6 +1,7 @@
 use diesel::dsl::count_star;
 use diesel::{ExpressionMethods, QueryDsl, RunQueryDsl, associations::HasTable};
 
+use crate::poll::ItemKind;
 use crate::schema::workflows::dsl::workflows;
 use crate::{DbHandle, schema::workflows as schema};
 
@@ -44,6 +45,7 @@ impl WorkflowsHandle<'_> {
         diesel::insert_into(workflows)
             .values(&workflow)
             .execute(&mut self.db.conn)?;
+        self.db.notify(ItemKind::Workflows);
         Ok(())
     }
 
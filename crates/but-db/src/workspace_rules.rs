@@ -1,6 +1,7 @@
 use diesel::{ExpressionMethods, OptionalExtension, QueryDsl, RunQueryDsl};
 
 use crate::DbHandle;
+use crate::poll::ItemKind;
 use crate::schema::workspace_rules::dsl::workspace_rules;
 
 use diesel::prelude::{Insertable, Queryable, Selectable};
@@ -32,24 +33,33 @@ impl WorkspaceRulesHandle<'_> {
         diesel::insert_into(workspace_rules)
             .values(rule)
             .execute(&mut self.db.conn)?;
+        self.db.notify(ItemKind::Rules);
         Ok(())
     }
 
     pub fn update(&mut self, id: &str, rule: WorkspaceRule) -> Result<(), diesel::result::Error> {
-        diesel::update(workspace_rules.filter(crate::schema::workspace_rules::id.eq(id)))
-            .set((
-                crate::schema::workspace_rules::enabled.eq(rule.enabled),
-                crate::schema::workspace_rules::trigger.eq(rule.trigger),
-                crate::schema::workspace_rules::filters.eq(rule.filters),
-                crate::schema::workspace_rules::action.eq(rule.action),
-            ))
-            .execute(&mut self.db.conn)?;
+        let changed =
+            diesel::update(workspace_rules.filter(crate::schema::workspace_rules::id.eq(id)))
+                .set((
+                    crate::schema::workspace_rules::enabled.eq(rule.enabled),
+                    crate::schema::workspace_rules::trigger.eq(rule.trigger),
+                    crate::schema::workspace_rules::filters.eq(rule.filters),
+                    crate::schema::workspace_rules::action.eq(rule.action),
+                ))
+                .execute(&mut self.db.conn)?;
+        if changed > 0 {
+            self.db.notify(ItemKind::Rules);
+        }
         Ok(())
     }
 
     pub fn delete(&mut self, id: &str) -> Result<(), diesel::result::Error> {
-        diesel::delete(workspace_rules.filter(crate::schema::workspace_rules::id.eq(id)))
-            .execute(&mut self.db.conn)?;
+        let changed =
+            diesel::delete(workspace_rules.filter(crate::schema::workspace_rules::id.eq(id)))
+                .execute(&mut self.db.conn)?;
+        if changed > 0 {
+            self.db.notify(ItemKind::Rules);
+        }
         Ok(())
     }
 
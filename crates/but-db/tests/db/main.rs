@@ -47,3 +47,72 @@ fn init_in_parallel() -> anyhow::Result<()> {
     });
     Ok(())
 }
+
+mod poll {
+    use std::time::Duration;
+
+    use but_db::{DbHandle, WorkspaceRule, poll::ItemKind};
+    use diesel::{Connection, SqliteConnection, connection::SimpleConnection};
+
+    fn rule(id: &str) -> WorkspaceRule {
+        WorkspaceRule {
+            id: id.into(),
+            created_at: chrono::Local::now().naive_local(),
+            enabled: true,
+            trigger: "fileSytemChange".into(),
+            filters: "[]".into(),
+            action: "{}".into(),
+        }
+    }
+
+    #[test]
+    fn changes_in_process_are_pushed() -> anyhow::Result<()> {
+        let tmp = tempfile::tempdir()?;
+        let db = DbHandle::new_in_directory(tmp.path())?;
+        let rx = db.poll_changes(
+            ItemKind::Rules | ItemKind::Actions,
+            Duration::from_secs(3600),
+        )?;
+        assert!(
+            rx.recv_timeout(Duration::from_millis(100)).is_err(),
+            "nothing is sent before anything changed"
+        );
+
+        let mut other_db = DbHandle::new_in_directory(tmp.path())?;
+        other_db.workspace_rules().insert(rule("1"))?;
+        assert_eq!(
+            rx.recv_timeout(Duration::from_secs(10))??,
+            ItemKind::Rules,
+            "the change is sent right away, without waiting for the next interval"
+        );
+
+        other_db.hunk_assignments().set_all(Vec::new())?;
+        other_db.workspace_rules().delete("does-not-exist")?;
+        assert!(
+            rx.recv_timeout(Duration::from_millis(100)).is_err(),
+            "unwatched kinds and writes that didn't change anything aren't sent"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn changes_by_other_processes_are_picked_up_by_counters() -> anyhow::Result<()> {
+        let tmp = tempfile::tempdir()?;
+        let db = DbHandle::new_in_directory(tmp.path())?;
+        let rx = db.poll_changes(ItemKind::all(), Duration::from_millis(10))?;
+
+        // Write without `DbHandle`, like another process would.
+        let url = DbHandle::db_file_path(tmp.path());
+        let mut conn = SqliteConnection::establish(url.to_str().expect("valid UTF-8"))?;
+        conn.batch_execute(
+            "INSERT INTO workspace_rules (id, created_at, enabled, trigger, filters, action)
+             VALUES ('1', '2025-01-01 00:00:00', 1, 'fileSytemChange', '[]', '{}')",
+        )?;
+        assert_eq!(rx.recv_timeout(Duration::from_secs(10))??, ItemKind::Rules);
+        assert!(
+            rx.recv_timeout(Duration::from_millis(100)).is_err(),
+            "each change is sent once"
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,169 @@
+//! Pushing to Gerrit, which creates or updates changes when pushing to the magic `refs/for/<branch>` refs.
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Options to apply to all changes created or updated by a push to Gerrit.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PushOptions {
+    /// The topic to put the changes into, typically the name of the branch that is pushed.
+    pub topic: Option<String>,
+    /// Users to add as reviewers, identified by email or username.
+    #[serde(default)]
+    pub reviewers: Vec<String>,
+    /// Hashtags to add to the changes.
+    #[serde(default)]
+    pub hashtags: Vec<String>,
+}
+
+impl PushOptions {
+    /// Return the ref to push to so that changes for `target_branch`, like `main`, are created
+    /// with all options applied, like `refs/for/main%topic=feat,r=alice@example.com`.
+    pub fn destination_ref(&self, target_branch: &str) -> String {
+        let options: Vec<String> = self
+            .topic
+            .iter()
+            .map(|topic| format!("topic={}", encode_value(topic)))
+            .chain(
+                self.reviewers
+                    .iter()
+                    .map(|r| format!("r={}", encode_value(r))),
+            )
+            .chain(
+                self.hashtags
+                    .iter()
+                    .map(|h| format!("hashtag={}", encode_value(h))),
+            )
+            .collect();
+        if options.is_empty() {
+            format!("refs/for/{target_branch}")
+        } else {
+            format!("refs/for/{target_branch}%{}", options.join(","))
+        }
+    }
+}
+
+/// Percent-encode the characters that would otherwise be taken as separators in the options
+/// of a Gerrit destination ref.
+fn encode_value(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-_.~@+/:".contains(&byte) {
+            out.push(byte as char);
+        } else {
+            out.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    out
+}
+
+/// A change that Gerrit reported as created or updated in response to a push.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PushedChange {
+    /// The URL of the change in Gerrit's web interface.
+    pub url: String,
+    /// The number of the change, if it could be extracted from the `url`.
+    pub number: Option<u64>,
+    /// The subject of the change, as shown by Gerrit.
+    pub subject: String,
+    /// `true` if the change was created by the push, `false` if an existing change got a new patch set.
+    pub is_new: bool,
+}
+
+/// Extract the changes Gerrit reports in the remote messages of a push, which `git` prints
+/// to stderr in lines like `remote:   https://review.example.com/c/project/+/123 Subject [NEW]`.
+pub fn parse_push_output(stderr: &str) -> Vec<PushedChange> {
+    stderr
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("remote:"))
+        .filter_map(|line| {
+            let line = line.trim();
+            let (url, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+            if !(url.starts_with("https://") || url.starts_with("http://")) {
+                return None;
+            }
+            let rest = rest.trim();
+            let (subject, is_new) = match rest.strip_suffix("[NEW]") {
+                Some(subject) => (subject.trim_end(), true),
+                None => (rest, false),
+            };
+            let number = url
+                .trim_end_matches('/')
+                .rsplit('/')
+                .next()
+                .and_then(|n| n.parse().ok());
+            Some(PushedChange {
+                url: url.to_owned(),
+                number,
+                subject: subject.to_owned(),
+                is_new,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn destination_without_options() {
+        assert_eq!(
+            PushOptions::default().destination_ref("main"),
+            "refs/for/main"
+        );
+    }
+
+    #[test]
+    fn destination_with_options() {
+        let options = PushOptions {
+            topic: Some("feat/login page".into()),
+            reviewers: vec!["alice@example.com".into(), "bob".into()],
+            hashtags: vec!["ui,web".into()],
+        };
+        assert_eq!(
+            options.destination_ref("main"),
+            "refs/for/main%topic=feat/login%20page,r=alice@example.com,r=bob,hashtag=ui%2Cweb"
+        );
+    }
+
+    #[test]
+    fn parse_new_and_updated_changes() {
+        let stderr = "remote: \n\
+            remote: Processing changes: refs: 1, new: 1, updated: 1, done\n\
+            remote: \n\
+            remote: SUCCESS\n\
+            remote: \n\
+            remote:   https://review.example.com/c/project/+/123 Add login page [NEW]\n\
+            remote:   https://review.example.com/c/project/+/122 Refactor session handling\n\
+            remote: \n\
+            To ssh://review.example.com:29418/project\n \
+            * [new reference]   abc123 -> refs/for/main\n";
+        let changes = parse_push_output(stderr);
+        assert_eq!(
+            changes,
+            vec![
+                PushedChange {
+                    url: "https://review.example.com/c/project/+/123".into(),
+                    number: Some(123),
+                    subject: "Add login page".into(),
+                    is_new: true,
+                },
+                PushedChange {
+                    url: "https://review.example.com/c/project/+/122".into(),
+                    number: Some(122),
+                    subject: "Refactor session handling".into(),
+                    is_new: false,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_output_without_changes() {
+        let stderr =
+            "remote: \nremote: Processing changes: refs: 1, done\nremote: error: no new changes\n";
+        assert!(parse_push_output(stderr).is_empty());
+    }
+}
@@ -186,6 +186,9 @@ mod stack {
 
         #[serde(default)]
         pub review_id: Option<String>,
+        /// The URLs of the Gerrit changes that were created or updated when the branch was last pushed in Gerrit mode.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        pub gerrit_change_urls: Vec<String>,
     }
 
     impl StackBranch {
@@ -202,6 +205,7 @@ mod stack {
                 pr_number,
                 archived,
                 review_id,
+                gerrit_change_urls: Vec::new(),
                 head: CommitOrChangeId::CommitId(gix::hash::Kind::Sha1.null().to_string()),
             }
         }
@@ -228,6 +232,19 @@ mod stack {
         }
     }
 
+    impl schemars::JsonSchema for BranchOwnershipClaims {
+        fn schema_name() -> std::borrow::Cow<'static, str> {
+            "BranchOwnershipClaims".into()
+        }
+
+        fn json_schema(_generator: &mut schemars::SchemaGenerator) -> schemars::Schema {
+            schemars::json_schema!({
+                "type": "string",
+                "description": "One claim per line, each a path followed by `:` and a comma-separated list of hunks.",
+            })
+        }
+    }
+
     impl<'de> Deserialize<'de> for BranchOwnershipClaims {
         fn deserialize<D>(deserializer: D) -> anyhow::Result<Self, D::Error>
         where
@@ -23,11 +23,12 @@ use gitbutler_command_context::CommandContext;
 use gitbutler_stack::VirtualBranchesHandle;
 use itertools::Itertools;
 use reconcile::MultipleOverlapping;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use tracing::instrument;
 use uuid::Uuid;
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct HunkAssignment {
     /// A stable identifier for the hunk assignment.
@@ -41,6 +42,7 @@ pub struct HunkAssignment {
     /// The file path of the hunk.
     pub path: String,
     /// The file path of the hunk in bytes.
+    #[schemars(with = "Vec<u8>")]
     pub path_bytes: BString,
     /// The stack to which the hunk is assigned. If None, the hunk is not assigned to any stack.
     pub stack_id: Option<StackId>,
@@ -123,7 +125,7 @@ impl From<HunkAssignment> for but_workspace::DiffSpec {
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 /// Indicates that the assignment request was rejected due to locking - the hunk depends on a commit in the stack it is currently in.
 pub struct AssignmentRejection {
@@ -133,7 +135,7 @@ pub struct AssignmentRejection {
     locks: Vec<HunkLock>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 /// A request to update a hunk assignment.
 /// If a a file has multiple hunks, the UI client should send a list of assignment requests with the appropriate hunk headers.
@@ -143,24 +145,35 @@ pub struct HunkAssignmentRequest {
     /// If the file has hunk headers, then header info MUST be provided.
     pub hunk_header: Option<HunkHeader>,
     /// The file path of the hunk in bytes.
+    #[schemars(with = "Vec<u8>")]
     pub path_bytes: BString,
     /// The stack to which the hunk is assigned. If set to None, the hunk is set as "unassigned".
     /// If a stack id is set, it must be one of the applied stacks.
     pub stack_id: Option<StackId>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 /// Same as `but_core::ui::WorktreeChanges`, but with the addition of hunk assignments.
 pub struct WorktreeChanges {
     #[serde(flatten)]
     pub worktree_changes: but_core::ui::WorktreeChanges,
     pub assignments: Vec<HunkAssignment>,
+    #[schemars(with = "Option<SerdeError>")]
     pub assignments_error: Option<serde_error::Error>,
     pub dependencies: Option<HunkDependencies>,
+    #[schemars(with = "Option<SerdeError>")]
     pub dependencies_error: Option<serde_error::Error>,
 }
 
+/// The schema of a [`serde_error::Error`], which doesn't provide one.
+#[derive(JsonSchema)]
+#[allow(dead_code)]
+struct SerdeError {
+    description: String,
+    source: Option<Box<SerdeError>>,
+}
+
 impl From<but_core::ui::WorktreeChanges> for WorktreeChanges {
     fn from(worktree_changes: but_core::ui::WorktreeChanges) -> Self {
         WorktreeChanges {
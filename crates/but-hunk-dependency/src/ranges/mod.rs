@@ -3,6 +3,7 @@ use but_core::TreeStatusKind;
 use but_workspace::StackId;
 use gix::bstr::BString;
 use itertools::Itertools;
+use schemars::JsonSchema;
 use serde::Serialize;
 use std::collections::{HashMap, HashSet};
 
@@ -21,14 +22,16 @@ pub struct WorkspaceRanges {
 }
 
 /// An error that can say what went wrong when computing the hunk ranges for a commit in a stack at a given path.
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 #[expect(missing_docs)]
 pub struct CalculationError {
     pub error_message: String,
     pub stack_id: StackId,
     #[serde(serialize_with = "gitbutler_serde::object_id::serialize")]
+    #[schemars(with = "String")]
     pub commit_id: gix::ObjectId,
+    #[schemars(with = "Vec<u8>")]
     pub path: BString,
 }
 
@@ -3,6 +3,7 @@ use but_core::unified_diff::DiffHunk;
 use gitbutler_command_context::{CommandContext, gix_repo_for_merging};
 use gitbutler_oxidize::OidExt;
 use gitbutler_stack::StackId;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::path::Path;
 
@@ -44,7 +45,7 @@ pub fn hunk_dependencies_for_workspace_changes_by_worktree_dir(
 ///
 /// Note that the [`errors`](Self::errors) field may contain information about specific failures, while other paths
 /// may have succeeded computing.
-#[derive(Debug, Clone, Serialize, Default)]
+#[derive(Debug, Clone, Serialize, Default, JsonSchema)]
 pub struct HunkDependencies {
     /// A map from hunk diffs to stack and commit dependencies.
     pub diffs: Vec<(String, DiffHunk, Vec<HunkLock>)>,
@@ -92,12 +93,13 @@ impl HunkDependencies {
 /// A commit that owns this lock, along with the stack that owns it.
 /// A hunk is locked when it depends on changes in commits that are in your workspace. A hunk can
 /// be locked to more than one branch if it overlaps with more than one committed hunk.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct HunkLock {
     /// The ID of the stack that contains [`commit_id`](Self::commit_id).
     pub stack_id: StackId,
     /// The commit the hunk applies to.
     #[serde(with = "gitbutler_serde::object_id")]
+    #[schemars(with = "String")]
     pub commit_id: gix::ObjectId,
 }
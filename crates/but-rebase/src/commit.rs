@@ -6,9 +6,11 @@ use gitbutler_error::error::Code;
 use gix::config::Source;
 use gix::objs::WriteTo;
 use std::borrow::Cow;
+use std::collections::HashMap;
 use std::io::Write;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::Stdio;
+use std::sync::{LazyLock, Mutex, PoisonError};
 
 /// What to do with the committer (actor) and the commit time when [creating a new commit](create()).
 #[derive(Debug, Copy, Clone)]
@@ -179,9 +181,7 @@ pub(crate) fn update_author_time(
 
 /// Sign the given `buffer` using configuration from `repo`, just like Git would.
 pub fn sign_buffer(repo: &gix::Repository, buffer: &[u8]) -> anyhow::Result<BString> {
-    // TODO: support gpg.ssh.defaultKeyCommand to get the signing key if this value doesn't exist
     let config = repo.config_snapshot();
-    let signing_key = signing_key(repo)?;
     let sign_format = config.string("gpg.format");
     let is_ssh = if let Some(sign_format) = sign_format {
         sign_format.as_ref() == "ssh"
@@ -190,18 +190,13 @@ pub fn sign_buffer(repo: &gix::Repository, buffer: &[u8]) -> anyhow::Result<BStr
     };
 
     if is_ssh {
+        let signing_key = ssh_signing_key(repo)?;
         // write commit data to a temp file so we can sign it
         let mut signature_storage = tempfile::NamedTempFile::new()?;
         signature_storage.write_all(buffer)?;
         let buffer_file_to_sign_path = signature_storage.into_temp_path();
 
-        let gpg_program = config
-            .trusted_program("gpg.ssh.program")
-            .filter(|program| !program.is_empty())
-            .map_or_else(
-                || Path::new("ssh-keygen").into(),
-                |program| Cow::Owned(program.into_owned().into()),
-            );
+        let gpg_program = ssh_program(&config);
 
         let mut signing_cmd = prepare_with_shell_on_windows(gpg_program.into_owned())
             .args(["-Y", "sign", "-n", "git", "-f"]);
@@ -258,13 +253,8 @@ pub fn sign_buffer(repo: &gix::Repository, buffer: &[u8]) -> anyhow::Result<BStr
             bail!("Failed to sign SSH: {}", std_both);
         }
     } else {
-        let gpg_program = config
-            .trusted_program("gpg.program")
-            .filter(|program| !program.is_empty())
-            .map_or_else(
-                || Path::new("gpg").into(),
-                |program| Cow::Owned(program.into_owned().into()),
-            );
+        let signing_key = signing_key(repo)?;
+        let gpg_program = gpg_program(&config);
 
         let mut cmd = into_command(
             prepare_with_shell_on_windows(gpg_program.as_ref())
@@ -304,6 +294,201 @@ pub fn sign_buffer(repo: &gix::Repository, buffer: &[u8]) -> anyhow::Result<BStr
     }
 }
 
+/// The state of the signature of a commit, as determined by [`verify_signature()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum SignatureStatus {
+    /// The commit isn't signed.
+    Unsigned,
+    /// The signature matches the commit and was made by a trusted key.
+    Valid,
+    /// The signature matches the commit, but the key that made it isn't known to be trusted, or there is
+    /// nothing configured to check it against, like `gpg.ssh.allowedSignersFile` for SSH signatures.
+    Unverified,
+    /// The signature doesn't match the commit, typically because the commit was rewritten while keeping its signature.
+    Invalid,
+}
+
+/// Check the signature of the commit with `commit_id` using configuration from `repo`, just like Git would.
+///
+/// SSH signatures are checked against the keys in `gpg.ssh.allowedSignersFile` using `gpg.ssh.program`,
+/// and GPG signatures against the keyring of `gpg.program`.
+pub fn verify_signature(
+    repo: &gix::Repository,
+    commit_id: gix::ObjectId,
+) -> anyhow::Result<SignatureStatus> {
+    let mut commit = repo.find_commit(commit_id)?.decode()?.to_owned();
+    let Some(pos) = commit
+        .extra_headers()
+        .find_pos(gix::objs::commit::SIGNATURE_FIELD_NAME)
+    else {
+        return Ok(SignatureStatus::Unsigned);
+    };
+    // The signature was made over the commit without the signature header.
+    let (_, signature) = commit.extra_headers.remove(pos);
+    let mut signed_data = Vec::new();
+    commit.write_to(&mut signed_data)?;
+
+    let mut signature_file = tempfile::NamedTempFile::new()?;
+    signature_file.write_all(&signature)?;
+    let signature_path = signature_file.into_temp_path();
+
+    let config = repo.config_snapshot();
+    if signature.starts_with(b"-----BEGIN SSH SIGNATURE-----") {
+        let Some(allowed_signers) = config
+            .trusted_path("gpg.ssh.allowedSignersFile")
+            .transpose()?
+        else {
+            return Ok(SignatureStatus::Unverified);
+        };
+        let program = ssh_program(&config);
+        let principals = into_command(
+            prepare_with_shell_on_windows(program.as_ref())
+                .args(["-Y", "find-principals", "-f"])
+                .arg(allowed_signers.as_ref())
+                .arg("-s")
+                .arg(signature_path.to_path_buf()),
+        )
+        .stdin(Stdio::null())
+        .output()?;
+        let principal = principals
+            .status
+            .success()
+            .then(|| principals.stdout.lines().next().map(|l| l.to_str_lossy()))
+            .flatten()
+            .filter(|principal| !principal.is_empty());
+
+        let status = match principal {
+            Some(principal) => {
+                let verified = output_with_stdin(
+                    prepare_with_shell_on_windows(program.as_ref())
+                        .args(["-Y", "verify", "-n", "git", "-f"])
+                        .arg(allowed_signers.as_ref())
+                        .arg("-I")
+                        .arg(principal.as_ref())
+                        .arg("-s")
+                        .arg(signature_path.to_path_buf()),
+                    &signed_data,
+                )?;
+                if verified.status.success() {
+                    SignatureStatus::Valid
+                } else {
+                    SignatureStatus::Invalid
+                }
+            }
+            None => {
+                // The key isn't trusted, but the signature may still match the commit.
+                let checked = output_with_stdin(
+                    prepare_with_shell_on_windows(program.as_ref())
+                        .args(["-Y", "check-novalidate", "-n", "git", "-s"])
+                        .arg(signature_path.to_path_buf()),
+                    &signed_data,
+                )?;
+                if checked.status.success() {
+                    SignatureStatus::Unverified
+                } else {
+                    SignatureStatus::Invalid
+                }
+            }
+        };
+        Ok(status)
+    } else {
+        let program = gpg_program(&config);
+        let verified = output_with_stdin(
+            prepare_with_shell_on_windows(program.as_ref())
+                .args(["--status-fd=1", "--verify"])
+                .arg(signature_path.to_path_buf())
+                .arg("-"),
+            &signed_data,
+        )?;
+        let status_lines = verified.stdout.lines().collect::<Vec<_>>();
+        let has_status = |status: &[u8]| {
+            status_lines.iter().any(|line| {
+                line.strip_prefix(b"[GNUPG:] ")
+                    .is_some_and(|l| l.starts_with(status))
+            })
+        };
+        let status = if has_status(b"BADSIG") {
+            SignatureStatus::Invalid
+        } else if has_status(b"GOODSIG") {
+            if has_status(b"TRUST_FULLY") || has_status(b"TRUST_ULTIMATE") {
+                SignatureStatus::Valid
+            } else {
+                SignatureStatus::Unverified
+            }
+        } else if has_status(b"ERRSIG") || has_status(b"NO_PUBKEY") {
+            SignatureStatus::Unverified
+        } else {
+            SignatureStatus::Invalid
+        };
+        Ok(status)
+    }
+}
+
+/// Like [`verify_signature()`], but remember the status of each commit for the lifetime of the process,
+/// as checking a signed commit runs `gpg` or `ssh-keygen`.
+///
+/// Commits never change, so only changes to the trusted keys go unnoticed until the process restarts.
+pub fn verify_signature_cached(
+    repo: &gix::Repository,
+    commit_id: gix::ObjectId,
+) -> anyhow::Result<SignatureStatus> {
+    static CACHE: LazyLock<Mutex<HashMap<(PathBuf, gix::ObjectId), SignatureStatus>>> =
+        LazyLock::new(Default::default);
+    let key = (repo.git_dir().to_owned(), commit_id);
+    if let Some(status) = CACHE
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get(&key)
+    {
+        return Ok(*status);
+    }
+    let status = verify_signature(repo, commit_id)?;
+    CACHE
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .insert(key, status);
+    Ok(status)
+}
+
+/// Run the command in `prepare` with `stdin` as its input, and collect its output.
+fn output_with_stdin(
+    prepare: gix::command::Prepare,
+    stdin: &[u8],
+) -> anyhow::Result<std::process::Output> {
+    let mut cmd = into_command(prepare);
+    cmd.stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .stdin(Stdio::piped());
+    let mut child = cmd
+        .spawn()
+        .with_context(|| format!("Could not execute {cmd:?}"))?;
+    child.stdin.take().expect("configured").write_all(stdin)?;
+    Ok(child.wait_with_output()?)
+}
+
+/// The program to use for signing and verifying with SSH keys.
+fn ssh_program<'a>(config: &'a gix::config::Snapshot<'_>) -> Cow<'a, Path> {
+    config
+        .trusted_program("gpg.ssh.program")
+        .filter(|program| !program.is_empty())
+        .map_or_else(
+            || Path::new("ssh-keygen").into(),
+            |program| Cow::Owned(program.into_owned().into()),
+        )
+}
+
+/// The program to use for signing and verifying with GPG keys.
+fn gpg_program<'a>(config: &'a gix::config::Snapshot<'_>) -> Cow<'a, Path> {
+    config
+        .trusted_program("gpg.program")
+        .filter(|program| !program.is_empty())
+        .map_or_else(
+            || Path::new("gpg").into(),
+            |program| Cow::Owned(program.into_owned().into()),
+        )
+}
+
 fn into_command(prepare: gix::command::Prepare) -> std::process::Command {
     let cmd: std::process::Command = prepare.into();
     tracing::debug!(?cmd, "command to produce commit signature");
@@ -320,6 +505,44 @@ fn as_literal_key(maybe_key: &BStr) -> Option<&BStr> {
     None
 }
 
+/// Obtain the key to sign with SSH from `user.signingKey`, or from the first line of the output of
+/// `gpg.ssh.defaultKeyCommand` if it isn't set, just like Git would.
+/// The latter is typically used to pick a key from the `ssh-agent`.
+fn ssh_signing_key(repo: &gix::Repository) -> anyhow::Result<BString> {
+    let config = repo.config_snapshot();
+    if let Some(key) = config.string("user.signingkey") {
+        return Ok(key.into_owned());
+    }
+    let Some(key_command) = config.string("gpg.ssh.defaultKeyCommand") else {
+        bail!("Neither user.signingKey nor gpg.ssh.defaultKeyCommand are configured");
+    };
+    // Like Git, split the command at whitespace instead of passing it to a shell.
+    let mut args = key_command
+        .fields()
+        .map(|arg| arg.to_str_lossy().into_owned());
+    let program = args.next().context("gpg.ssh.defaultKeyCommand is empty")?;
+    let output = into_command(prepare_with_shell_on_windows(program).args(args))
+        .stdin(Stdio::null())
+        .output()
+        .with_context(|| format!("Could not execute gpg.ssh.defaultKeyCommand '{key_command}'"))?;
+    if !output.status.success() {
+        bail!(
+            "gpg.ssh.defaultKeyCommand '{key_command}' failed: {}",
+            output.stderr.as_bstr()
+        );
+    }
+    let key = output
+        .stdout
+        .lines()
+        .next()
+        .map(|line| line.trim().as_bstr())
+        .filter(|key| as_literal_key(key).is_some())
+        .with_context(|| {
+            format!("gpg.ssh.defaultKeyCommand '{key_command}' didn't output a public key on its first line")
+        })?;
+    Ok(key.to_owned())
+}
+
 /// Fail if there is no usable signing key.
 fn signing_key(repo: &gix::Repository) -> anyhow::Result<BString> {
     if let Some(key) = repo.config_snapshot().string("user.signingkey") {
@@ -0,0 +1,53 @@
+use anyhow::{Context, Result, bail};
+use bstr::ByteSlice;
+use std::process::Stdio;
+
+/// Check out the tree of `commit_id` into a temporary directory and run `command` in it, failing if it fails.
+/// The directory is removed afterwards, and nothing in `repo` or its worktree is changed.
+pub(crate) fn run(repo: &gix::Repository, commit_id: gix::ObjectId, command: &str) -> Result<()> {
+    let tree_id = repo.find_commit(commit_id)?.tree_id()?;
+    let mut index = repo.index_from_tree(&tree_id)?;
+    for entry in index
+        .entries_mut()
+        .iter_mut()
+        .filter(|e| e.mode.contains(gix::index::entry::Mode::COMMIT))
+    {
+        entry.flags.insert(gix::index::entry::Flags::SKIP_WORKTREE);
+    }
+
+    let worktree = tempfile::tempdir().context("Could not create temporary worktree")?;
+    let mut opts =
+        repo.checkout_options(gix::worktree::stack::state::attributes::Source::IdMapping)?;
+    opts.destination_is_initially_empty = true;
+    gix::worktree::state::checkout(
+        &mut index,
+        worktree.path(),
+        repo.clone(),
+        &gix::progress::Discard,
+        &gix::progress::Discard,
+        &gix::interrupt::IS_INTERRUPTED,
+        opts,
+    )
+    .with_context(|| format!("Could not check out the tree of {commit_id}"))?;
+
+    let output = std::process::Command::from(
+        gix::command::prepare(command)
+            .command_may_be_shell_script()
+            .with_shell()
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped()),
+    )
+    .current_dir(worktree.path())
+    .env("GITBUTLER_REBASE_COMMIT", commit_id.to_string())
+    .output()
+    .with_context(|| format!("Could not run '{command}'"))?;
+    if !output.status.success() {
+        bail!(
+            "Command '{command}' failed for commit {commit_id} with {status}:\n{stderr}",
+            status = output.status,
+            stderr = output.stderr.to_str_lossy().trim_end()
+        );
+    }
+    Ok(())
+}
@@ -20,6 +20,8 @@ pub mod commit;
 /// Utilities around merging
 pub mod merge;
 
+mod exec;
+
 /// An instruction for [`RebaseBuilder::rebase()`].
 #[derive(Debug, Clone)]
 pub enum RebaseStep {
@@ -50,6 +52,29 @@ pub enum RebaseStep {
     /// If this is the first step in the list, the reference will be to the `base` commit.
     /// If the step before this one is another `Reference` step, this reference will point to the same commit.
     Reference(but_core::Reference),
+    /// Leave out an existing commit, so that the commits of the following steps are placed on top of
+    /// the commit preceding it.
+    Drop {
+        /// Id of an already existing commit
+        commit_id: gix::ObjectId,
+    },
+    /// Pick an existing commit just like `Pick`, and mark it to be edited once the rebase is done.
+    ///
+    /// As the rebase never touches the worktree, it doesn't actually stop here. Instead, callers look up the
+    /// rewritten commit with [`RebaseOutput::rewritten_commit()`] and hand it over to edit mode, which
+    /// rebases all commits above it once the edit is done. Only one commit can be edited per rebase.
+    Edit {
+        /// Id of an already existing commit
+        commit_id: gix::ObjectId,
+    },
+    /// Run `command` in a temporary worktree with the tree of the commit that precedes this step checked out,
+    /// and abort the rebase if it fails.
+    ///
+    /// If this is the first step in the list, the command runs against the `base` commit.
+    Exec {
+        /// The command to run, which may be a shell script.
+        command: String,
+    },
 }
 
 impl RebaseStep {
@@ -57,8 +82,10 @@ impl RebaseStep {
     pub fn commit_id(&self) -> Option<&gix::oid> {
         match self {
             RebaseStep::Pick { commit_id, .. }
-            | RebaseStep::SquashIntoPreceding { commit_id, .. } => Some(commit_id),
-            RebaseStep::Reference { .. } => None,
+            | RebaseStep::SquashIntoPreceding { commit_id, .. }
+            | RebaseStep::Drop { commit_id }
+            | RebaseStep::Edit { commit_id } => Some(commit_id),
+            RebaseStep::Reference { .. } | RebaseStep::Exec { .. } => None,
         }
     }
 }
@@ -162,26 +189,64 @@ impl Rebase<'_> {
     /// - The commit must not be a commit that is already in a pick, merge or fixup step
     ///
     /// Fixup operations:
-    /// - Must not be a reference step immediately before it
-    /// - Must not be the first operation
+    /// - Must not be a reference step before it, ignoring drop and exec steps
+    /// - Must be preceded by a pick, edit or fixup step, ignoring drop and exec steps
     ///
     /// Reference operations:
     /// - The refname must be a valid reference name
+    ///
+    /// Drop and Edit operations:
+    /// - The commit must exist, must not be the base commit, and must not be in another step
+    /// - There must be at most one Edit operation
+    ///
+    /// Exec operations:
+    /// - The command must not be empty
+    /// - There must be a base or a preceding step to run the command against
     fn validate_step(&self, step: &RebaseStep) -> Result<()> {
         match step {
             RebaseStep::Pick { commit_id, .. } => {
                 self.assure_unique_step_and_existing_non_base(commit_id, "Picked")?;
             }
+            RebaseStep::Drop { commit_id } => {
+                self.assure_unique_step_and_existing_non_base(commit_id, "Dropped")?;
+            }
+            RebaseStep::Edit { commit_id } => {
+                self.assure_unique_step_and_existing_non_base(commit_id, "Edited")?;
+                if self
+                    .steps
+                    .iter()
+                    .any(|s| matches!(s, RebaseStep::Edit { .. }))
+                {
+                    bail!("Only one commit can be edited per rebase");
+                }
+            }
+            RebaseStep::Exec { command } => {
+                if command.trim().is_empty() {
+                    bail!("Exec step must have a non-empty command");
+                }
+                if self.base.is_none() && self.steps.is_empty() {
+                    bail!("Exec must have a commit to run against");
+                }
+            }
             RebaseStep::SquashIntoPreceding {
                 commit_id,
                 new_message: _,
             } => {
                 self.assure_unique_step_and_existing_non_base(commit_id, "Fixup")?;
-                if matches!(self.steps.last(), Some(RebaseStep::Reference { .. })) {
-                    bail!("Fixup commit must not come after a reference step");
-                }
-                if self.steps.is_empty() {
-                    bail!("Fixup must have a commit to work on");
+                // Dropped commits and commands don't produce a commit to squash into.
+                let preceding = self
+                    .steps
+                    .iter()
+                    .rev()
+                    .find(|s| !matches!(s, RebaseStep::Drop { .. } | RebaseStep::Exec { .. }));
+                match preceding {
+                    Some(
+                        RebaseStep::Pick { .. }
+                        | RebaseStep::Edit { .. }
+                        | RebaseStep::SquashIntoPreceding { .. },
+                    ) => {}
+                    Some(_) => bail!("Fixup commit must not come after a reference step"),
+                    None => bail!("Fixup must have a commit to work on"),
                 }
             }
             RebaseStep::Reference(name) => {
@@ -224,9 +289,18 @@ fn rebase(
         Vec::<(Option<gix::ObjectId>, gix::ObjectId, gix::ObjectId)>::new(),
     );
     let (mut cursor, mut last_seen_commit) = (base, base);
+    let mut dropped = Vec::new();
     let cache = repo.commit_graph_if_enabled()?;
     let mut graph = repo.revision_graph(cache.as_ref());
     for step in steps {
+        // Edits are only marked for the caller, to the rebase they are picks.
+        let step = match step {
+            RebaseStep::Edit { commit_id } => RebaseStep::Pick {
+                commit_id,
+                new_message: None,
+            },
+            step => step,
+        };
         match step {
             RebaseStep::Pick {
                 commit_id,
@@ -330,8 +404,23 @@ fn rebase(
                         .expect("Validation assures there is a commit prior"),
                 });
             }
+            RebaseStep::Drop { commit_id } => {
+                last_seen_commit = Some(commit_id);
+                dropped.push(commit_id);
+            }
+            RebaseStep::Exec { command } => {
+                let Some(cursor) = cursor else {
+                    bail!("Can't run '{command}' as there is no commit to run it against");
+                };
+                exec::run(repo, cursor, &command)?;
+            }
+            RebaseStep::Edit { .. } => unreachable!("edits were turned into picks"),
         }
-        if let Some((old, new)) = last_seen_commit.zip(cursor) {
+        // Dropped commits aren't rewritten, so there is no mapping for them.
+        if let Some((old, new)) = last_seen_commit
+            .filter(|id| !dropped.contains(id))
+            .zip(cursor)
+        {
             commit_mapping.push((base, old, new));
         }
     }
@@ -381,6 +470,15 @@ pub fn replace_commit_tree(
     )?)
 }
 
+impl RebaseOutput {
+    /// Return the rewritten version of `commit_id`, or `None` if it wasn't part of the rebase or was dropped.
+    pub fn rewritten_commit(&self, commit_id: gix::ObjectId) -> Option<gix::ObjectId> {
+        self.commit_mapping
+            .iter()
+            .find_map(|(_base, old, new)| (*old == commit_id).then_some(*new))
+    }
+}
+
 /// A reference that is an output of a rebase operation.
 /// This is simply a marker for where the actual reference should point to after the rebase operation.
 #[derive(Debug, Clone)]
@@ -112,6 +112,94 @@ pub fn octopus(
     }
 }
 
+/// A path that conflicts when merging the trees of multiple commits, as found by [`octopus_conflicts()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OctopusConflict {
+    /// The conflicting path, at the location of the *ours* side.
+    pub path: BString,
+    /// The commits whose changes to `path` take part in the conflict, in the order they were merged.
+    pub commits: Vec<gix::ObjectId>,
+}
+
+/// Merge the trees of `commits` just like [`octopus()`] would, but without creating a merge commit or
+/// stopping at the first conflict. Return all paths that conflict along with the commits that changed them,
+/// or an empty list if the octopus merge would succeed.
+///
+/// The merge writes trees, so `repo` should typically be [configured to keep objects in memory](gix::Repository::with_object_memory())
+/// if nothing should be persisted.
+pub fn octopus_conflicts(
+    repo: &gix::Repository,
+    commits: &[gix::ObjectId],
+) -> Result<Vec<OctopusConflict>> {
+    if commits.len() < 2 {
+        return Ok(Vec::new());
+    }
+    let mut graph = repo.revision_graph(None);
+    let merge_base = but_core::Commit::from_id(
+        repo.merge_base_octopus_with_graph(commits.iter().copied(), &mut graph)?,
+    )?
+    .tree_id_or_kind(TreeKind::Base)?
+    .detach();
+    let trees = commits
+        .iter()
+        .map(|commit_id| -> Result<_> {
+            Ok(but_core::Commit::from_id(commit_id.attach(repo))?
+                .tree_id_or_kind(TreeKind::Theirs)?
+                .detach())
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let (merge_options, unresolved) = repo.merge_options_fail_fast()?;
+    let merge_options = merge_options.with_fail_on_conflict(None);
+    let mut conflicts = Vec::<OctopusConflict>::new();
+    let mut ours = trees[0];
+    for (idx, tree_to_merge) in trees.iter().copied().enumerate().skip(1) {
+        let mut merge = repo.merge_trees(
+            merge_base,
+            ours,
+            tree_to_merge,
+            repo.default_merge_labels(),
+            merge_options.clone(),
+        )?;
+        for conflict in merge
+            .conflicts
+            .iter()
+            .filter(|c| c.is_unresolved(unresolved))
+        {
+            let path = conflict.ours.location().to_owned();
+            if conflicts.iter().any(|c| c.path == path) {
+                continue;
+            }
+            // Everything merged so far that changed the path is part of the conflict.
+            let base_entry = entry_id(repo, merge_base, path.as_ref())?;
+            let mut involved = Vec::new();
+            for (commit_id, tree) in commits.iter().zip(&trees).take(idx + 1) {
+                if entry_id(repo, *tree, path.as_ref())? != base_entry {
+                    involved.push(*commit_id);
+                }
+            }
+            conflicts.push(OctopusConflict {
+                path,
+                commits: involved,
+            });
+        }
+        ours = merge.tree.write()?.detach();
+    }
+    Ok(conflicts)
+}
+
+/// Return the id of the entry at `path` in `tree_id`, if it exists.
+fn entry_id(
+    repo: &gix::Repository,
+    tree_id: gix::ObjectId,
+    path: &bstr::BStr,
+) -> Result<Option<gix::ObjectId>> {
+    Ok(repo
+        .find_tree(tree_id)?
+        .lookup_entry(path.split_str("/"))?
+        .map(|entry| entry.object_id()))
+}
+
 /// A type that can be retrieved as an `anyhow` context to see if the rebase failed due to merge conflicts.
 #[derive(Debug, Clone)]
 pub struct ConflictErrorContext {
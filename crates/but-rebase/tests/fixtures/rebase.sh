@@ -47,3 +47,19 @@ git init merge-in-the-middle
   git checkout with-inner-merge && git merge --no-ff B
   echo seq 10 >'added-after-with-inner-merge' && git add . && git commit -m "on top of inner merge"
 )
+
+git init three-branches-conflicting
+(cd three-branches-conflicting
+  seq 10 >file && git add . && git commit -m "base" && git tag base
+  git branch B
+  git branch C
+
+  git checkout -b A
+  { seq 4; echo "A"; seq 6 10; } >file && git add . && git commit -m "A: change line 5"
+
+  git checkout B
+  seq 10 >other-file && git add . && git commit -m "B: add another file"
+
+  git checkout C
+  { seq 4; echo "C"; seq 6 10; } >file && git add . && git commit -m "C: change line 5"
+)
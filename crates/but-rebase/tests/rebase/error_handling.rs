@@ -203,6 +203,54 @@ fn fixup_is_only_preceeded_by_a_reference_step() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[test]
+fn fixup_is_preceded_by_a_reference_step_and_a_drop_step() -> anyhow::Result<()> {
+    let (repo, commits) = four_commits()?;
+    let mut builder = Rebase::new(&repo, commits.base, None)?;
+    let result = builder.steps([
+        RebaseStep::Pick {
+            commit_id: commits.a,
+            new_message: None,
+        },
+        RebaseStep::Reference(but_core::Reference::Virtual("x".into())),
+        RebaseStep::Drop {
+            commit_id: commits.c,
+        },
+        RebaseStep::SquashIntoPreceding {
+            commit_id: commits.b,
+            new_message: None,
+        },
+        RebaseStep::Reference(but_core::Reference::Virtual("y".into())),
+    ]);
+    assert_eq!(
+        result.unwrap_err().to_string(),
+        "Fixup commit must not come after a reference step",
+        "the squash would otherwise cross the boundary between the two branches"
+    );
+    Ok(())
+}
+
+#[test]
+fn fixup_is_only_preceded_by_a_drop_step() -> anyhow::Result<()> {
+    let (repo, commits) = four_commits()?;
+    let mut builder = Rebase::new(&repo, commits.base, None)?;
+    let result = builder.steps([
+        RebaseStep::Drop {
+            commit_id: commits.a,
+        },
+        RebaseStep::SquashIntoPreceding {
+            commit_id: commits.b,
+            new_message: None,
+        },
+    ]);
+    assert_eq!(
+        result.unwrap_err().to_string(),
+        "Fixup must have a commit to work on",
+        "the base must not be squashed into"
+    );
+    Ok(())
+}
+
 #[test]
 fn empty_reference_step() -> anyhow::Result<()> {
     let (repo, commits) = four_commits()?;
@@ -64,6 +64,243 @@ mod commit {
             Ok(())
         }
     }
+
+    mod verify_signature {
+        use crate::utils::four_commits;
+        use but_rebase::commit::{self, SignatureStatus};
+
+        #[test]
+        fn unsigned() -> anyhow::Result<()> {
+            let (repo, commits) = four_commits()?;
+            assert_eq!(
+                commit::verify_signature(&repo, commits.c)?,
+                SignatureStatus::Unsigned
+            );
+            Ok(())
+        }
+
+        #[test]
+        fn ssh_signature_without_allowed_signers_is_unverified() -> anyhow::Result<()> {
+            let (repo, commits) = four_commits()?;
+            let mut signed = repo.find_commit(commits.c)?.decode()?.to_owned();
+            signed.extra_headers.push((
+                gix::objs::commit::SIGNATURE_FIELD_NAME.into(),
+                "-----BEGIN SSH SIGNATURE-----\nU1NIU0lH\n-----END SSH SIGNATURE-----\n".into(),
+            ));
+            let signed_id = repo.write_object(&signed)?.detach();
+            assert_eq!(
+                commit::verify_signature(&repo, signed_id)?,
+                SignatureStatus::Unverified,
+                "without gpg.ssh.allowedSignersFile there is nothing to check against"
+            );
+            Ok(())
+        }
+
+        #[test]
+        fn cached_status_is_reused() -> anyhow::Result<()> {
+            let (mut repo, commits) = four_commits()?;
+            let mut signed = repo.find_commit(commits.c)?.decode()?.to_owned();
+            signed.extra_headers.push((
+                gix::objs::commit::SIGNATURE_FIELD_NAME.into(),
+                "-----BEGIN SSH SIGNATURE-----\nU1NIU0lH\n-----END SSH SIGNATURE-----\n".into(),
+            ));
+            let signed_id = repo.write_object(&signed)?.detach();
+            assert_eq!(
+                commit::verify_signature_cached(&repo, signed_id)?,
+                SignatureStatus::Unverified
+            );
+
+            {
+                let mut config = repo.config_snapshot_mut();
+                config.set_raw_value(&"gpg.ssh.allowedSignersFile", "allowed-signers")?;
+                config.set_raw_value(&"gpg.ssh.program", "false")?;
+            }
+            assert_eq!(
+                commit::verify_signature(&repo, signed_id)?,
+                SignatureStatus::Invalid,
+                "now the signature is checked, and it fails"
+            );
+            assert_eq!(
+                commit::verify_signature_cached(&repo, signed_id)?,
+                SignatureStatus::Unverified,
+                "the commit isn't checked again"
+            );
+            Ok(())
+        }
+    }
+
+    mod sign_buffer {
+        use crate::utils::fixture;
+        use but_rebase::commit;
+
+        #[test]
+        fn ssh_needs_a_signing_key_or_default_key_command() -> anyhow::Result<()> {
+            let mut repo = fixture("four-commits")?;
+            repo.config_snapshot_mut()
+                .set_raw_value(&"gpg.format", "ssh")?;
+            let err = commit::sign_buffer(&repo, b"data").unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                "Neither user.signingKey nor gpg.ssh.defaultKeyCommand are configured"
+            );
+            Ok(())
+        }
+
+        #[test]
+        fn default_key_command_must_output_a_public_key() -> anyhow::Result<()> {
+            let mut repo = fixture("four-commits")?;
+            {
+                let mut config = repo.config_snapshot_mut();
+                config.set_raw_value(&"gpg.format", "ssh")?;
+                config.set_raw_value(&"gpg.ssh.defaultKeyCommand", "echo not-a-key")?;
+            }
+            let err = commit::sign_buffer(&repo, b"data").unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                "gpg.ssh.defaultKeyCommand 'echo not-a-key' didn't output a public key on its first line"
+            );
+            Ok(())
+        }
+    }
+}
+
+mod merge {
+    use crate::utils::fixture;
+    use but_rebase::merge::{OctopusConflict, octopus_conflicts};
+
+    #[test]
+    fn octopus_conflicts_list_paths_and_the_commits_changing_them() -> anyhow::Result<()> {
+        let repo = fixture("three-branches-conflicting")?;
+        let [a, b, c] = ["A", "B", "C"].map(|name| repo.rev_parse_single(name).unwrap().detach());
+
+        assert_eq!(
+            octopus_conflicts(&repo, &[a, b])?,
+            [],
+            "non-overlapping changes merge cleanly"
+        );
+        assert_eq!(
+            octopus_conflicts(&repo, &[a, b, c])?,
+            [OctopusConflict {
+                path: "file".into(),
+                commits: vec![a, c],
+            }],
+            "B didn't touch the conflicting file"
+        );
+        Ok(())
+    }
+}
+
+mod steps {
+    use crate::utils::four_commits;
+    use but_rebase::{Rebase, RebaseStep};
+    use but_testsupport::assure_stable_env;
+
+    fn pick(commit_id: gix::ObjectId) -> RebaseStep {
+        RebaseStep::Pick {
+            commit_id,
+            new_message: None,
+        }
+    }
+
+    fn has_file(repo: &gix::Repository, commit_id: gix::ObjectId, name: &str) -> bool {
+        repo.find_commit(commit_id)
+            .unwrap()
+            .tree()
+            .unwrap()
+            .lookup_entry_by_path(name)
+            .unwrap()
+            .is_some()
+    }
+
+    #[test]
+    fn drop_leaves_out_the_commit() -> anyhow::Result<()> {
+        assure_stable_env();
+        let (repo, commits) = four_commits()?;
+        let out = Rebase::new(&repo, commits.base, None)?
+            .steps([
+                pick(commits.a),
+                RebaseStep::Drop {
+                    commit_id: commits.b,
+                },
+                pick(commits.c),
+            ])?
+            .rebase()?;
+        assert!(has_file(&repo, out.top_commit, "a"));
+        assert!(!has_file(&repo, out.top_commit, "b"), "b was dropped");
+        assert!(has_file(&repo, out.top_commit, "c"));
+        assert_eq!(out.rewritten_commit(commits.b), None);
+        assert!(out.rewritten_commit(commits.c).is_some());
+        Ok(())
+    }
+
+    #[test]
+    fn edit_picks_the_commit_and_can_only_be_used_once() -> anyhow::Result<()> {
+        assure_stable_env();
+        let (repo, commits) = four_commits()?;
+        let out = Rebase::new(&repo, commits.base, None)?
+            .steps([
+                pick(commits.a),
+                RebaseStep::Edit {
+                    commit_id: commits.b,
+                },
+                pick(commits.c),
+            ])?
+            .rebase()?;
+        let edited = out
+            .rewritten_commit(commits.b)
+            .expect("edited commits are rewritten");
+        assert_eq!(repo.find_commit(edited)?.message_raw()?, "b\n");
+        assert!(has_file(&repo, out.top_commit, "b"));
+
+        let err = Rebase::new(&repo, commits.base, None)?
+            .steps([
+                RebaseStep::Edit {
+                    commit_id: commits.a,
+                },
+                RebaseStep::Edit {
+                    commit_id: commits.b,
+                },
+            ])
+            .unwrap_err();
+        assert_eq!(err.to_string(), "Only one commit can be edited per rebase");
+        Ok(())
+    }
+
+    #[test]
+    fn exec_runs_against_the_tree_of_the_preceding_commit() -> anyhow::Result<()> {
+        assure_stable_env();
+        let (repo, commits) = four_commits()?;
+        Rebase::new(&repo, commits.base, None)?
+            .steps([
+                RebaseStep::Exec {
+                    command: "test -f base && test ! -f a".into(),
+                },
+                pick(commits.a),
+                RebaseStep::Exec {
+                    command: "test -f a && test ! -f b".into(),
+                },
+                pick(commits.b),
+            ])?
+            .rebase()?;
+
+        let err = Rebase::new(&repo, commits.base, None)?
+            .steps([
+                pick(commits.a),
+                RebaseStep::Exec {
+                    command: "echo failing >&2 && test -f b".into(),
+                },
+                pick(commits.b),
+            ])?
+            .rebase()
+            .unwrap_err();
+        let err = err.to_string();
+        assert!(
+            err.starts_with("Command 'echo failing >&2 && test -f b' failed for commit"),
+            "{err}"
+        );
+        assert!(err.ends_with("\nfailing"), "stderr is included: {err}");
+        Ok(())
+    }
 }
 
 #[test]
@@ -0,0 +1,591 @@
+//! A dry-run of the workspace rules against the current uncommitted changes, explaining what each rule would do.
+use std::collections::HashMap;
+
+use but_hunk_assignment::HunkAssignment;
+use but_hunk_dependency::ui::HunkDependencies;
+use but_workspace::{HunkHeader, StackId, ui::StackEntry};
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::{
+    Action, Filter, ImplicitOperation, Operation, Trigger, WorkspaceRule,
+    handler::{filter_matches, is_applied_on_filesystem_change, target_stack_id},
+    implicit::{self, PlannedAction},
+};
+
+/// The result of evaluating all workspace rules against the current hunk assignments, without changing anything.
+#[derive(Serialize, Debug, Clone, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Evaluation {
+    /// One entry per uncommitted hunk, in the order of the assignments.
+    pub assignments: Vec<AssignmentEvaluation>,
+    /// Errors that would prevent a rule from being applied, like a target that doesn't exist anymore.
+    pub errors: Vec<RuleError>,
+}
+
+/// How the workspace rules apply to a single hunk assignment.
+#[derive(Serialize, Debug, Clone, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AssignmentEvaluation {
+    /// The path of the file the hunk belongs to.
+    pub path: String,
+    /// The hunk, or `None` if the assignment is for the whole file.
+    pub hunk_header: Option<HunkHeader>,
+    /// The stack the hunk is currently assigned to, if any.
+    pub stack_id: Option<StackId>,
+    /// The evaluation of each rule, in the order the rules are applied.
+    pub rules: Vec<RuleEvaluation>,
+}
+
+/// How a single rule applies to a hunk assignment.
+#[derive(Serialize, Debug, Clone, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleEvaluation {
+    /// The ID of the rule.
+    pub rule_id: String,
+    /// Why the rule isn't applied when files change, or `None` if it is.
+    pub skipped: Option<SkipReason>,
+    /// The outcome of each of the rule's filters for the hunk.
+    pub filters: Vec<FilterEvaluation>,
+    /// Whether the rule matched the hunk. This is the case if it has no filters, or if any of its filters matched.
+    pub matched: bool,
+    /// What would happen to the hunk, or `None` if the rule didn't match, is skipped, failed or wouldn't change anything.
+    pub action: Option<EvaluatedAction>,
+}
+
+/// The reason a rule isn't applied when files change.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum SkipReason {
+    /// The rule is disabled.
+    Disabled,
+    /// The rule is triggered by something other than file-system changes.
+    Trigger,
+    /// The action of the rule isn't applied automatically.
+    UnsupportedAction,
+}
+
+/// The outcome of a single filter for a hunk.
+#[derive(Serialize, Debug, Clone, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FilterEvaluation {
+    /// The filter that was evaluated.
+    pub filter: Filter,
+    /// Whether the filter matched.
+    pub outcome: FilterOutcome,
+}
+
+/// Whether a filter matched a hunk.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum FilterOutcome {
+    /// The filter matched the hunk.
+    Matched,
+    /// The filter rejected the hunk.
+    Rejected,
+    /// The filter isn't evaluated when files change.
+    NotEvaluated,
+}
+
+/// What applying a rule would do with a hunk.
+#[derive(Serialize, Debug, Clone, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase", tag = "type", content = "subject")]
+pub enum EvaluatedAction {
+    /// The hunk would be assigned to the stack, or to a new stack if `None`.
+    #[serde(rename_all = "camelCase")]
+    Assign { stack_id: Option<StackId> },
+    /// The hunk would be amended into the commit with the given change-id.
+    #[serde(rename_all = "camelCase")]
+    Amend {
+        change_id: String,
+        #[serde(with = "gitbutler_serde::object_id")]
+        #[schemars(with = "String")]
+        commit_id: gix::ObjectId,
+    },
+    /// The hunk would be committed to the branch, which is in the given stack or would be created if `None`.
+    #[serde(rename_all = "camelCase")]
+    NewCommit {
+        branch_name: String,
+        stack_id: Option<StackId>,
+    },
+    /// The hunk would be absorbed into the commit it depends on.
+    #[serde(rename_all = "camelCase")]
+    Absorb {
+        stack_id: StackId,
+        #[serde(with = "gitbutler_serde::object_id")]
+        #[schemars(with = "String")]
+        commit_id: gix::ObjectId,
+    },
+}
+
+/// An error that would prevent a rule from being applied.
+#[derive(Serialize, Debug, Clone, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleError {
+    /// The ID of the failing rule.
+    pub rule_id: String,
+    /// A description of what went wrong.
+    pub error: String,
+}
+
+/// Evaluate `rules` against `assignments` the way the rule handler would apply them when files change.
+/// `stacks_in_ws` are the stacks in the workspace, and `change_ids` maps workspace commits to their change-id.
+pub(crate) fn evaluate(
+    rules: &[WorkspaceRule],
+    assignments: &[HunkAssignment],
+    dependencies: Option<&HunkDependencies>,
+    stacks_in_ws: &[StackEntry],
+    change_ids: &HashMap<gix::ObjectId, String>,
+) -> Evaluation {
+    let mut errors = Vec::new();
+    let resolved = rules
+        .iter()
+        .map(|rule| match resolve(rule, stacks_in_ws, change_ids) {
+            Ok(resolved) => resolved,
+            Err(err) => {
+                errors.push(RuleError {
+                    rule_id: rule.id.clone(),
+                    error: format!("{err:#}"),
+                });
+                Resolved::Failed
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let assignments = assignments
+        .iter()
+        .map(|assignment| AssignmentEvaluation {
+            path: assignment.path.clone(),
+            hunk_header: assignment.hunk_header,
+            stack_id: assignment.stack_id,
+            rules: rules
+                .iter()
+                .zip(&resolved)
+                .map(|(rule, resolved)| evaluate_rule(rule, resolved, assignment, dependencies))
+                .collect(),
+        })
+        .collect();
+    Evaluation {
+        assignments,
+        errors,
+    }
+}
+
+/// What a rule resolves to in the current workspace, independently of the hunk it's applied to.
+enum Resolved {
+    /// The rule isn't applied when files change.
+    Skipped(SkipReason),
+    /// The rule can't be applied.
+    Failed,
+    /// Matching hunks are assigned to the stack, or to a new one if `None`.
+    Assign(Option<StackId>),
+    /// Matching hunks are amended into the commit.
+    Amend {
+        change_id: String,
+        commit_id: gix::ObjectId,
+    },
+    /// Matching hunks are committed to the branch, which is in the stack or created if `None`.
+    NewCommit {
+        branch_name: String,
+        stack_id: Option<StackId>,
+    },
+    /// Matching hunks are handled by the implicit operation.
+    Implicit(ImplicitOperation),
+}
+
+fn resolve(
+    rule: &WorkspaceRule,
+    stacks_in_ws: &[StackEntry],
+    change_ids: &HashMap<gix::ObjectId, String>,
+) -> anyhow::Result<Resolved> {
+    if !is_applied_on_filesystem_change(rule) {
+        let reason = if !rule.enabled {
+            SkipReason::Disabled
+        } else if rule.trigger != Trigger::FileSytemChange {
+            SkipReason::Trigger
+        } else {
+            SkipReason::UnsupportedAction
+        };
+        return Ok(Resolved::Skipped(reason));
+    }
+    Ok(match &rule.action {
+        Action::Explicit(Operation::Assign { target }) => {
+            Resolved::Assign(target_stack_id(target, stacks_in_ws)?)
+        }
+        Action::Explicit(Operation::Amend { change_id }) => {
+            let commit_id = change_ids
+                .iter()
+                .find_map(|(commit_id, id)| (id == change_id).then_some(*commit_id))
+                .ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "No commit with Change-Id {change_id} found in the current workspace"
+                    )
+                })?;
+            Resolved::Amend {
+                change_id: change_id.clone(),
+                commit_id,
+            }
+        }
+        Action::Explicit(Operation::NewCommit { branch_name, .. }) => Resolved::NewCommit {
+            branch_name: branch_name.clone(),
+            stack_id: stacks_in_ws
+                .iter()
+                .find(|s| s.heads.iter().any(|h| &h.name == branch_name))
+                .and_then(|s| s.id),
+        },
+        Action::Implicit(operation) => Resolved::Implicit(operation.clone()),
+    })
+}
+
+fn evaluate_rule(
+    rule: &WorkspaceRule,
+    resolved: &Resolved,
+    assignment: &HunkAssignment,
+    dependencies: Option<&HunkDependencies>,
+) -> RuleEvaluation {
+    let filters = rule
+        .filters
+        .iter()
+        .map(|filter| FilterEvaluation {
+            filter: filter.clone(),
+            outcome: match filter_matches(filter, assignment) {
+                Some(true) => FilterOutcome::Matched,
+                Some(false) => FilterOutcome::Rejected,
+                None => FilterOutcome::NotEvaluated,
+            },
+        })
+        .collect::<Vec<_>>();
+    let matched = filters.is_empty() || filters.iter().any(|f| f.outcome == FilterOutcome::Matched);
+
+    let action = match resolved {
+        _ if !matched => None,
+        Resolved::Skipped(_) | Resolved::Failed => None,
+        Resolved::Assign(stack_id) => (stack_id.is_none() || assignment.stack_id != *stack_id)
+            .then_some(EvaluatedAction::Assign {
+                stack_id: *stack_id,
+            }),
+        Resolved::Amend {
+            change_id,
+            commit_id,
+        } => Some(EvaluatedAction::Amend {
+            change_id: change_id.clone(),
+            commit_id: *commit_id,
+        }),
+        Resolved::NewCommit {
+            branch_name,
+            stack_id,
+        } => Some(EvaluatedAction::NewCommit {
+            branch_name: branch_name.clone(),
+            stack_id: *stack_id,
+        }),
+        Resolved::Implicit(operation) => {
+            implicit::plan(operation, std::slice::from_ref(assignment), dependencies)
+                .into_iter()
+                .next()
+                .map(|change| match change.action {
+                    PlannedAction::Assign { stack_id } => EvaluatedAction::Assign {
+                        stack_id: Some(stack_id),
+                    },
+                    PlannedAction::Absorb {
+                        stack_id,
+                        commit_id,
+                    } => EvaluatedAction::Absorb {
+                        stack_id,
+                        commit_id,
+                    },
+                })
+        }
+    };
+
+    RuleEvaluation {
+        rule_id: rule.id.clone(),
+        skipped: match resolved {
+            Resolved::Skipped(reason) => Some(*reason),
+            _ => None,
+        },
+        filters,
+        matched,
+        action,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use but_hunk_assignment::HunkAssignment;
+    use but_workspace::ui::{StackEntry, StackHeadInfo};
+
+    use super::{EvaluatedAction, FilterOutcome, SkipReason, evaluate};
+    use crate::{
+        Action, Filter, Operation, StackTarget, Trigger, WorkspaceRule,
+        testing::{assignment, commit, stack},
+    };
+
+    fn stack_entry(id: u128, branch_name: &str, order: usize) -> StackEntry {
+        StackEntry {
+            id: Some(stack(id)),
+            heads: vec![StackHeadInfo {
+                name: branch_name.into(),
+                tip: commit(1),
+                is_checked_out: false,
+            }],
+            tip: commit(1),
+            order: Some(order),
+            is_checked_out: false,
+        }
+    }
+
+    fn rule(id: &str, filters: Vec<Filter>, operation: Operation) -> WorkspaceRule {
+        WorkspaceRule {
+            id: id.into(),
+            created_at: chrono::NaiveDateTime::default(),
+            enabled: true,
+            trigger: Trigger::FileSytemChange,
+            filters,
+            action: Action::Explicit(operation),
+        }
+    }
+
+    fn path(regex: &str) -> Filter {
+        Filter::PathMatchesRegex(regex::Regex::new(regex).unwrap())
+    }
+
+    fn content(regex: &str) -> Filter {
+        Filter::ContentMatchesRegex(regex::Regex::new(regex).unwrap())
+    }
+
+    fn assign(target: StackTarget) -> Operation {
+        Operation::Assign { target }
+    }
+
+    #[test]
+    fn matching_and_rejecting_filters() {
+        let stacks = [stack_entry(1, "left", 0), stack_entry(2, "right", 1)];
+        let rules = [rule(
+            "r",
+            vec![path(r"\.md$"), content("TODO")],
+            assign(StackTarget::Rightmost),
+        )];
+        let assignments = [
+            assignment("src/lib.rs"),
+            HunkAssignment {
+                stack_id: Some(stack(1)),
+                ..assignment("README.md")
+            },
+        ];
+        let out = evaluate(&rules, &assignments, None, &stacks, &HashMap::new());
+        assert!(out.errors.is_empty());
+
+        let lib = &out.assignments[0].rules[0];
+        assert_eq!(
+            lib.filters.iter().map(|f| f.outcome).collect::<Vec<_>>(),
+            [FilterOutcome::Rejected, FilterOutcome::Matched]
+        );
+        assert!(lib.matched, "any matching filter is enough");
+        assert_eq!(
+            lib.action,
+            Some(EvaluatedAction::Assign {
+                stack_id: Some(stack(2))
+            })
+        );
+
+        let readme = &out.assignments[1].rules[0];
+        assert_eq!(
+            readme.filters.iter().map(|f| f.outcome).collect::<Vec<_>>(),
+            [FilterOutcome::Matched, FilterOutcome::Matched]
+        );
+        assert_eq!(
+            readme.action,
+            Some(EvaluatedAction::Assign {
+                stack_id: Some(stack(2))
+            })
+        );
+    }
+
+    #[test]
+    fn non_matching_filters_have_no_action() {
+        let stacks = [stack_entry(1, "left", 0)];
+        let rules = [rule(
+            "r",
+            vec![path(r"\.md$"), content("FIXME")],
+            assign(StackTarget::Leftmost),
+        )];
+        let out = evaluate(
+            &rules,
+            &[assignment("src/lib.rs")],
+            None,
+            &stacks,
+            &HashMap::new(),
+        );
+        let evaluation = &out.assignments[0].rules[0];
+        assert_eq!(
+            evaluation
+                .filters
+                .iter()
+                .map(|f| f.outcome)
+                .collect::<Vec<_>>(),
+            [FilterOutcome::Rejected, FilterOutcome::Rejected]
+        );
+        assert!(!evaluation.matched);
+        assert_eq!(evaluation.action, None);
+        assert_eq!(evaluation.skipped, None);
+    }
+
+    #[test]
+    fn filters_not_evaluated_on_file_changes_dont_match() {
+        let rules = [rule(
+            "r",
+            vec![Filter::ClaudeCodeSessionId("session".into())],
+            assign(StackTarget::Leftmost),
+        )];
+        let out = evaluate(
+            &rules,
+            &[assignment("a.rs")],
+            None,
+            &[stack_entry(1, "left", 0)],
+            &HashMap::new(),
+        );
+        let evaluation = &out.assignments[0].rules[0];
+        assert_eq!(evaluation.filters[0].outcome, FilterOutcome::NotEvaluated);
+        assert!(!evaluation.matched);
+        assert_eq!(evaluation.action, None);
+    }
+
+    #[test]
+    fn assigning_to_the_current_stack_changes_nothing() {
+        let rules = [rule("r", vec![], assign(StackTarget::Leftmost))];
+        let out = evaluate(
+            &rules,
+            &[HunkAssignment {
+                stack_id: Some(stack(1)),
+                ..assignment("a.rs")
+            }],
+            None,
+            &[stack_entry(1, "left", 0)],
+            &HashMap::new(),
+        );
+        let evaluation = &out.assignments[0].rules[0];
+        assert!(evaluation.matched, "rules without filters match everything");
+        assert_eq!(evaluation.action, None);
+    }
+
+    #[test]
+    fn skipped_rules() {
+        let mut disabled = rule("disabled", vec![], assign(StackTarget::Leftmost));
+        disabled.enabled = false;
+        let mut hook = rule("hook", vec![], assign(StackTarget::Leftmost));
+        hook.trigger = Trigger::ClaudeCodeHook;
+        let out = evaluate(
+            &[disabled, hook],
+            &[assignment("a.rs")],
+            None,
+            &[stack_entry(1, "left", 0)],
+            &HashMap::new(),
+        );
+        assert!(out.errors.is_empty());
+        let rules = &out.assignments[0].rules;
+        assert_eq!(rules[0].skipped, Some(SkipReason::Disabled));
+        assert_eq!(rules[1].skipped, Some(SkipReason::Trigger));
+        assert!(
+            rules.iter().all(|r| r.matched && r.action.is_none()),
+            "skipped rules still show whether they match, but do nothing"
+        );
+    }
+
+    #[test]
+    fn invalid_rules_are_reported_as_errors() {
+        let rules = [
+            rule(
+                "missing-stack",
+                vec![],
+                assign(StackTarget::StackId(stack(3).to_string())),
+            ),
+            rule(
+                "missing-change-id",
+                vec![],
+                Operation::Amend {
+                    change_id: "unknown".into(),
+                },
+            ),
+            rule(
+                "amend",
+                vec![],
+                Operation::Amend {
+                    change_id: "known".into(),
+                },
+            ),
+        ];
+        let change_ids = HashMap::from([(commit(2), "known".to_string())]);
+        let out = evaluate(
+            &rules,
+            &[assignment("a.rs")],
+            None,
+            &[stack_entry(1, "left", 0)],
+            &change_ids,
+        );
+        assert_eq!(
+            out.errors
+                .iter()
+                .map(|e| e.rule_id.as_str())
+                .collect::<Vec<_>>(),
+            ["missing-stack", "missing-change-id"]
+        );
+        assert!(out.errors[0].error.contains("is not in the workspace"));
+        assert!(
+            out.errors[1]
+                .error
+                .contains("No commit with Change-Id unknown")
+        );
+
+        let rules = &out.assignments[0].rules;
+        assert!(
+            rules[..2].iter().all(|r| r.matched && r.action.is_none()),
+            "failing rules do nothing"
+        );
+        assert_eq!(
+            rules[2].action,
+            Some(EvaluatedAction::Amend {
+                change_id: "known".into(),
+                commit_id: commit(2),
+            }),
+            "other rules are still evaluated"
+        );
+    }
+
+    #[test]
+    fn new_commit_resolves_the_stack_of_the_branch() {
+        let new_commit = |branch_name: &str| Operation::NewCommit {
+            branch_name: branch_name.into(),
+            message_template: None,
+            generate_message: false,
+        };
+        let rules = [
+            rule("existing", vec![], new_commit("right")),
+            rule("new", vec![], new_commit("other")),
+        ];
+        let out = evaluate(
+            &rules,
+            &[assignment("a.rs")],
+            None,
+            &[stack_entry(1, "left", 0), stack_entry(2, "right", 1)],
+            &HashMap::new(),
+        );
+        let rules = &out.assignments[0].rules;
+        assert_eq!(
+            rules[0].action,
+            Some(EvaluatedAction::NewCommit {
+                branch_name: "right".into(),
+                stack_id: Some(stack(2)),
+            })
+        );
+        assert_eq!(
+            rules[1].action,
+            Some(EvaluatedAction::NewCommit {
+                branch_name: "other".into(),
+                stack_id: None,
+            }),
+            "the branch would be created"
+        );
+    }
+}
@@ -1,76 +1,274 @@
+use anyhow::Context;
 use but_graph::VirtualBranchesTomlMetadata;
 use but_hunk_assignment::{HunkAssignment, assign, assignments_to_requests};
 use but_hunk_dependency::ui::HunkDependencies;
 use but_workspace::{DiffSpec, StackId, StacksFilter, commit_engine, ui::StackEntry};
 use gitbutler_command_context::CommandContext;
+use gitbutler_oplog::{OplogExt, SnapshotExt};
 use itertools::Itertools;
 use std::str::FromStr;
 
-use crate::{Filter, StackTarget};
+use crate::{
+    Filter, StackTarget,
+    evaluate::RuleError,
+    implicit::{PlannedAction, PlannedChange},
+};
+
+/// The outcome of applying the workspace rules.
+#[derive(Debug, Default)]
+pub struct Outcome {
+    /// The amount of hunk assignments that were changed.
+    pub updates: usize,
+    /// The rules that couldn't be applied, which doesn't prevent the remaining rules from being applied.
+    pub errors: Vec<RuleError>,
+}
+
+impl Outcome {
+    /// Return the value of `result`, or record that the rule with `rule_id` failed and return the default.
+    fn record<T: Default>(&mut self, rule_id: &str, result: anyhow::Result<T>) -> T {
+        result.unwrap_or_else(|err| {
+            self.errors.push(RuleError {
+                rule_id: rule_id.to_owned(),
+                error: format!("{err:#}"),
+            });
+            T::default()
+        })
+    }
+}
 
 pub fn process_workspace_rules(
     ctx: &mut CommandContext,
     assignments: &[HunkAssignment],
     dependencies: &Option<HunkDependencies>,
-) -> anyhow::Result<usize> {
-    let mut updates = 0;
+) -> anyhow::Result<Outcome> {
+    let mut outcome = Outcome::default();
     if assignments.is_empty() {
         // Dont create stacks if there are no changes to assign anywhere
-        return Ok(updates);
+        return Ok(outcome);
     }
     let rules = super::list_rules(ctx)?
         .into_iter()
-        .filter(|r| r.enabled)
-        .filter(|r| matches!(r.trigger, super::Trigger::FileSytemChange))
-        .filter(|r| {
-            matches!(
-                &r.action,
-                super::Action::Explicit(super::Operation::Assign { .. })
-            ) || matches!(
-                &r.action,
-                super::Action::Explicit(super::Operation::Amend { .. })
-            )
-        })
+        .filter(is_applied_on_filesystem_change)
         .collect_vec();
 
     if rules.is_empty() {
-        return Ok(updates);
+        return Ok(outcome);
     }
 
-    let repo = ctx.gix_repo_for_merging_non_persisting()?;
-    let stacks_in_ws = if ctx.app_settings().feature_flags.ws3 {
-        let meta = VirtualBranchesTomlMetadata::from_path(
-            ctx.project().gb_dir().join("virtual_branches.toml"),
-        )?;
-        but_workspace::stacks_v3(&repo, &meta, StacksFilter::InWorkspace, None)
-    } else {
-        but_workspace::stacks(ctx, &ctx.project().gb_dir(), &repo, StacksFilter::default())
-    }?;
+    let mut assignments = assignments.to_vec();
+    let mut dependencies = dependencies.clone();
+    let mut stacks_in_ws = stacks_in_workspace(ctx)?;
 
     for rule in rules {
+        let updates_before = outcome.updates;
+        let mut created_stack = false;
         match rule.action {
             super::Action::Explicit(super::Operation::Assign { target }) => {
-                if let Some(stack_id) = get_or_create_stack_id(ctx, target, &stacks_in_ws) {
-                    let assignments = matching(assignments, rule.filters.clone())
-                        .into_iter()
-                        .filter(|e| e.stack_id != Some(stack_id))
-                        .map(|mut e| {
-                            e.stack_id = Some(stack_id);
-                            e
-                        })
-                        .collect_vec();
-                    updates +=
-                        handle_assign(ctx, assignments, dependencies.as_ref()).unwrap_or_default();
-                }
+                created_stack = target_stack_id(&target, &stacks_in_ws)
+                    .is_ok_and(|stack_id| stack_id.is_none());
+                let stack_id = match get_or_create_stack_id(ctx, target, &stacks_in_ws) {
+                    Ok(stack_id) => stack_id,
+                    Err(err) => {
+                        outcome.record::<()>(&rule.id, Err(err));
+                        continue;
+                    }
+                };
+                let assignments = matching(&assignments, rule.filters.clone())
+                    .into_iter()
+                    .filter(|e| e.stack_id != Some(stack_id))
+                    .map(|mut e| {
+                        e.stack_id = Some(stack_id);
+                        e
+                    })
+                    .collect_vec();
+                let result = handle_assign(ctx, assignments, dependencies.as_ref());
+                let updates = outcome.record(&rule.id, result);
+                outcome.updates += updates;
             }
             super::Action::Explicit(super::Operation::Amend { change_id }) => {
-                let assignments = matching(assignments, rule.filters.clone());
-                handle_amend(ctx, assignments, change_id).unwrap_or_default();
+                let assignments = matching(&assignments, rule.filters.clone());
+                let len = assignments.len();
+                let result = handle_amend(ctx, assignments, change_id).map(|()| len);
+                let updates = outcome.record(&rule.id, result);
+                outcome.updates += updates;
+            }
+            super::Action::Explicit(super::Operation::NewCommit {
+                branch_name,
+                message_template,
+                generate_message,
+            }) => {
+                let assignments = matching(&assignments, rule.filters.clone());
+                created_stack = !assignments.is_empty()
+                    && !stacks_in_ws
+                        .iter()
+                        .any(|s| s.heads.iter().any(|h| h.name == branch_name));
+                let result = handle_new_commit(
+                    ctx,
+                    assignments,
+                    &branch_name,
+                    message_template.as_deref(),
+                    generate_message,
+                    &stacks_in_ws,
+                );
+                let updates = outcome.record(&rule.id, result);
+                outcome.updates += updates;
+            }
+            super::Action::Implicit(operation) => {
+                let assignments = matching(&assignments, rule.filters.clone());
+                let planned =
+                    crate::implicit::plan(&operation, &assignments, dependencies.as_ref());
+                tracing::debug!(rule = %rule.id, ?planned, "applying implicit rule");
+                let result = handle_planned_changes(
+                    ctx,
+                    &rule.id,
+                    &assignments,
+                    planned,
+                    dependencies.as_ref(),
+                    &mut outcome,
+                );
+                outcome.record(&rule.id, result);
             }
             _ => continue,
         };
+
+        // Later rules must not see hunks that were committed by this one, nor create a stack that already exists.
+        if outcome.updates > updates_before || created_stack {
+            let reloaded = crate::workspace_assignments(ctx)
+                .and_then(|(assignments, deps)| Ok((assignments, deps, stacks_in_workspace(ctx)?)));
+            match reloaded {
+                Ok((new_assignments, new_dependencies, new_stacks)) => {
+                    assignments = new_assignments;
+                    dependencies = Some(new_dependencies);
+                    stacks_in_ws = new_stacks;
+                }
+                Err(err) => {
+                    outcome
+                        .record::<()>(&rule.id, Err(err.context("Failed to reload the workspace")));
+                    break;
+                }
+            }
+        }
+    }
+    Ok(outcome)
+}
+
+/// Apply the `planned` changes of the rule with `rule_id` to the `assignments` they were planned for,
+/// assigning before absorbing.
+/// The amount of changed assignments is added to `outcome`, along with the commits that couldn't be absorbed into.
+fn handle_planned_changes(
+    ctx: &mut CommandContext,
+    rule_id: &str,
+    assignments: &[HunkAssignment],
+    planned: Vec<PlannedChange>,
+    deps: Option<&HunkDependencies>,
+    outcome: &mut Outcome,
+) -> anyhow::Result<()> {
+    let assignment_of = |change: &PlannedChange| {
+        assignments
+            .iter()
+            .find(|a| a.path == change.path && a.hunk_header == change.hunk_header)
+            .cloned()
+    };
+
+    let mut to_assign = Vec::new();
+    let mut to_absorb = Vec::<(gix::ObjectId, Vec<HunkAssignment>)>::new();
+    for change in &planned {
+        let Some(mut assignment) = assignment_of(change) else {
+            continue;
+        };
+        match change.action {
+            PlannedAction::Assign { stack_id } => {
+                assignment.stack_id = Some(stack_id);
+                to_assign.push(assignment);
+            }
+            PlannedAction::Absorb { commit_id, .. } => {
+                match to_absorb.iter_mut().find(|(id, _)| *id == commit_id) {
+                    Some((_, group)) => group.push(assignment),
+                    None => to_absorb.push((commit_id, vec![assignment])),
+                }
+            }
+        }
+    }
+
+    if !to_assign.is_empty() {
+        outcome.updates += handle_assign(ctx, to_assign, deps)?;
+    }
+    if to_absorb.is_empty() {
+        return Ok(());
+    }
+
+    // Amending rewrites commits, so they are found by their change-id which stays stable.
+    let change_ids = change_ids_by_commit(ctx)?;
+    for (commit_id, group) in to_absorb {
+        let Some(change_id) = change_ids.get(&commit_id) else {
+            outcome.record::<()>(
+                rule_id,
+                Err(anyhow::anyhow!(
+                    "Can't absorb into commit {commit_id} as it has no change-id"
+                )),
+            );
+            continue;
+        };
+        let len = group.len();
+        let result = handle_amend(ctx, group, change_id.clone())
+            .map(|()| len)
+            .with_context(|| format!("Can't absorb into commit {commit_id}"));
+        let updates = outcome.record(rule_id, result);
+        outcome.updates += updates;
+    }
+    Ok(())
+}
+
+/// Map all commits in the workspace that have a change-id to it.
+pub(crate) fn change_ids_by_commit(
+    ctx: &CommandContext,
+) -> anyhow::Result<std::collections::HashMap<gix::ObjectId, String>> {
+    let repo = but_core::open_repo_for_merging(ctx.project().worktree_path())?;
+    let meta = VirtualBranchesTomlMetadata::from_path(
+        ctx.project().gb_dir().join("virtual_branches.toml"),
+    )?;
+    let ref_info_options = but_workspace::ref_info::Options {
+        expensive_commit_info: true,
+        traversal: meta.graph_options(),
+    };
+    let info = but_workspace::head_info(&repo, &meta, ref_info_options)?;
+    Ok(info
+        .stacks
+        .into_iter()
+        .flat_map(|stack| stack.segments)
+        .flat_map(|segment| segment.commits)
+        .filter_map(|commit| Some((commit.id, commit.change_id?.to_string())))
+        .collect())
+}
+
+/// Returns `true` if `rule` is evaluated when files change, and its action is one the handler can apply.
+pub(crate) fn is_applied_on_filesystem_change(rule: &super::WorkspaceRule) -> bool {
+    rule.enabled
+        && matches!(rule.trigger, super::Trigger::FileSytemChange)
+        && matches!(
+            &rule.action,
+            super::Action::Explicit(
+                super::Operation::Assign { .. }
+                    | super::Operation::Amend { .. }
+                    | super::Operation::NewCommit { .. }
+            ) | super::Action::Implicit(
+                super::ImplicitOperation::AssignToAppropriateBranch
+                    | super::ImplicitOperation::AbsorbIntoDependentCommit
+            )
+        )
+}
+
+/// The stacks in the workspace.
+pub(crate) fn stacks_in_workspace(ctx: &CommandContext) -> anyhow::Result<Vec<StackEntry>> {
+    let repo = ctx.gix_repo_for_merging_non_persisting()?;
+    if ctx.app_settings().feature_flags.ws3 {
+        let meta = VirtualBranchesTomlMetadata::from_path(
+            ctx.project().gb_dir().join("virtual_branches.toml"),
+        )?;
+        but_workspace::stacks_v3(&repo, &meta, StacksFilter::InWorkspace, None)
+    } else {
+        but_workspace::stacks(ctx, &ctx.project().gb_dir(), &repo, StacksFilter::default())
     }
-    Ok(updates)
 }
 
 fn handle_amend(
@@ -127,11 +325,144 @@ fn handle_amend(
     Ok(())
 }
 
+/// Commit `assignments` to the branch named `branch_name`, creating a new stack with that branch if it doesn't exist.
+/// The commit is recorded in the oplog so it can be undone.
+/// Returns the amount of committed assignments.
+fn handle_new_commit(
+    ctx: &mut CommandContext,
+    assignments: Vec<HunkAssignment>,
+    branch_name: &str,
+    message_template: Option<&str>,
+    generate_message: bool,
+    stacks_in_ws: &[StackEntry],
+) -> anyhow::Result<usize> {
+    if assignments.is_empty() {
+        return Ok(0);
+    }
+    let stack_id = match stacks_in_ws
+        .iter()
+        .find(|s| s.heads.iter().any(|h| h.name == branch_name))
+        .and_then(|s| s.id)
+    {
+        Some(stack_id) => stack_id,
+        None => create_stack_with_name(ctx, branch_name)?,
+    };
+    let assignments = assignments
+        .into_iter()
+        .filter(|a| belongs_to_stack(a, stack_id))
+        .collect_vec();
+    if assignments.is_empty() {
+        return Ok(0);
+    }
+
+    let message = commit_message(
+        branch_name,
+        message_template,
+        &assignments,
+        generate_message.then_some(generate_commit_message),
+    );
+    let len = assignments.len();
+    let changes = but_workspace::flatten_diff_specs(
+        assignments.into_iter().map(DiffSpec::from).collect_vec(),
+    );
+
+    let mut guard = ctx.project().exclusive_worktree_access();
+    let snapshot_tree = ctx.prepare_snapshot(guard.read_permission());
+    let outcome = commit_engine::create_commit_simple(
+        ctx,
+        stack_id,
+        None,
+        changes,
+        message.clone(),
+        branch_name.to_owned(),
+        guard.write_permission(),
+    );
+    let _ = snapshot_tree.and_then(|snapshot_tree| {
+        ctx.snapshot_commit_creation(
+            snapshot_tree,
+            outcome.as_ref().err(),
+            message,
+            None,
+            guard.write_permission(),
+        )
+    });
+
+    let outcome = outcome?;
+    Ok(if outcome.new_commit.is_some() { len } else { 0 })
+}
+
+/// Returns `true` if `assignment` may be committed to the stack with `stack_id`, which is when
+/// it isn't assigned to another stack and doesn't depend on commits of another stack.
+fn belongs_to_stack(assignment: &HunkAssignment, stack_id: StackId) -> bool {
+    assignment.stack_id.is_none_or(|id| id == stack_id)
+        && assignment
+            .hunk_locks
+            .iter()
+            .flatten()
+            .all(|lock| lock.stack_id == stack_id)
+}
+
+/// The message for a commit of `assignments` to `branch_name`, rendered from `template` if set.
+/// Otherwise the message is produced by `generate` if set, falling back to a summary of the changed paths.
+fn commit_message(
+    branch_name: &str,
+    template: Option<&str>,
+    assignments: &[HunkAssignment],
+    generate: Option<fn(&str, &[HunkAssignment]) -> Option<String>>,
+) -> String {
+    let files = assignments
+        .iter()
+        .map(|a| a.path.as_str())
+        .unique()
+        .join(", ");
+    if let Some(template) = template {
+        return template
+            .replace("{branch}", branch_name)
+            .replace("{files}", &files);
+    }
+    generate
+        .and_then(|generate| generate(branch_name, assignments))
+        .filter(|message| !message.trim().is_empty())
+        .unwrap_or_else(|| format!("Update {files}"))
+}
+
+/// Generate a commit message from the diff of `assignments` with AI, or `None` if there are no credentials
+/// or generation fails.
+fn generate_commit_message(branch_name: &str, assignments: &[HunkAssignment]) -> Option<String> {
+    let diff = assignments
+        .iter()
+        .filter_map(|a| {
+            a.diff
+                .as_ref()
+                .map(|diff| format!("--- {}\n{}", a.path, diff))
+        })
+        .join("\n");
+    let openai = but_action::OpenAiProvider::with(None)?;
+    but_action::generate::commit_message_blocking(
+        &openai,
+        &format!("Changes matched by a workspace rule for branch '{branch_name}'"),
+        "",
+        &diff,
+    )
+    .ok()
+}
+
 fn get_or_create_stack_id(
     ctx: &CommandContext,
     target: StackTarget,
     stacks_in_ws: &[StackEntry],
-) -> Option<StackId> {
+) -> anyhow::Result<StackId> {
+    match target_stack_id(&target, stacks_in_ws)? {
+        Some(stack_id) => Ok(stack_id),
+        None => create_stack(ctx),
+    }
+}
+
+/// Resolve `target` to one of `stacks_in_ws`, or `None` if a new stack would have to be created for it.
+pub(crate) fn target_stack_id(
+    target: &StackTarget,
+    stacks_in_ws: &[StackEntry],
+) -> anyhow::Result<Option<StackId>> {
     let sorted_stack_ids = stacks_in_ws
         .iter()
         .sorted_by(|a, b| Ord::cmp(&a.order.unwrap_or_default(), &b.order.unwrap_or_default()))
@@ -139,30 +470,15 @@ fn get_or_create_stack_id(
         .collect_vec();
     match target {
         StackTarget::StackId(stack_id) => {
-            if let Ok(stack_id) = StackId::from_str(&stack_id) {
-                if sorted_stack_ids.iter().any(|e| e == &stack_id) {
-                    Option::Some(stack_id)
-                } else {
-                    Option::None
-                }
-            } else {
-                Option::None
-            }
-        }
-        StackTarget::Leftmost => {
-            if sorted_stack_ids.is_empty() {
-                create_stack(ctx).ok()
-            } else {
-                sorted_stack_ids.first().cloned()
-            }
-        }
-        StackTarget::Rightmost => {
-            if sorted_stack_ids.is_empty() {
-                create_stack(ctx).ok()
+            let stack_id = StackId::from_str(stack_id)?;
+            if sorted_stack_ids.contains(&stack_id) {
+                Ok(Some(stack_id))
             } else {
-                sorted_stack_ids.last().cloned()
+                anyhow::bail!("Stack {stack_id} is not in the workspace")
             }
         }
+        StackTarget::Leftmost => Ok(sorted_stack_ids.first().cloned()),
+        StackTarget::Rightmost => Ok(sorted_stack_ids.last().cloned()),
     }
 }
 
@@ -171,8 +487,12 @@ fn create_stack(ctx: &CommandContext) -> anyhow::Result<StackId> {
     let vb_state = &gitbutler_stack::VirtualBranchesHandle::new(ctx.project().gb_dir());
     let branch_name =
         gitbutler_stack::Stack::next_available_name(&ctx.gix_repo()?, vb_state, template, false)?;
+    create_stack_with_name(ctx, &branch_name)
+}
+
+fn create_stack_with_name(ctx: &CommandContext, branch_name: &str) -> anyhow::Result<StackId> {
     let create_req = gitbutler_branch::BranchCreateRequest {
-        name: Some(branch_name),
+        name: Some(branch_name.to_owned()),
         ownership: None,
         order: None,
         selected_for_changes: None,
@@ -191,43 +511,128 @@ fn handle_assign(
     deps: Option<&HunkDependencies>,
 ) -> anyhow::Result<usize> {
     let len = assignments.len();
-    if assign(ctx, assignments_to_requests(assignments), deps).is_ok() {
-        Ok(len)
-    } else {
-        Ok(0)
-    }
+    assign(ctx, assignments_to_requests(assignments), deps)?;
+    Ok(len)
 }
 
-fn matching(wt_assignments: &[HunkAssignment], filters: Vec<Filter>) -> Vec<HunkAssignment> {
+pub(crate) fn matching(
+    wt_assignments: &[HunkAssignment],
+    filters: Vec<Filter>,
+) -> Vec<HunkAssignment> {
     if filters.is_empty() {
         return wt_assignments.to_vec();
     }
     let mut assignments = Vec::new();
     for filter in filters {
-        match filter {
-            Filter::PathMatchesRegex(regex) => {
-                for change in wt_assignments.iter() {
-                    if regex.is_match(&change.path) {
-                        assignments.push(change.clone());
-                    }
-                }
+        for change in wt_assignments.iter() {
+            if filter_matches(&filter, change) == Some(true) {
+                assignments.push(change.clone());
             }
-            Filter::ContentMatchesRegex(regex) => {
-                for change in wt_assignments.iter() {
-                    if let Some(diff) = change.diff.clone() {
-                        let diff = diff.to_string();
-                        let matching_lines: Vec<&str> =
-                            diff.lines().filter(|line| line.starts_with('+')).collect();
-                        if matching_lines.iter().any(|line| regex.is_match(line)) {
-                            assignments.push(change.clone());
-                        }
-                    }
-                }
-            }
-            Filter::FileChangeType(_) => continue,
-            Filter::SemanticType(_) => continue,
-            Filter::ClaudeCodeSessionId(_) => continue,
         }
     }
     assignments
 }
+
+/// Returns whether `filter` matches `change`, or `None` if the filter isn't evaluated when files change.
+pub(crate) fn filter_matches(filter: &Filter, change: &HunkAssignment) -> Option<bool> {
+    match filter {
+        Filter::PathMatchesRegex(regex) => Some(regex.is_match(&change.path)),
+        Filter::ContentMatchesRegex(regex) => Some(change.diff.as_ref().is_some_and(|diff| {
+            diff.to_string()
+                .lines()
+                .filter(|line| line.starts_with('+'))
+                .any(|line| regex.is_match(line))
+        })),
+        Filter::FileChangeType(_) => None,
+        Filter::SemanticType(_) => None,
+        Filter::ClaudeCodeSessionId(_) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use but_hunk_assignment::HunkAssignment;
+
+    use but_hunk_dependency::ui::HunkLock;
+    use but_workspace::StackId;
+
+    use super::{belongs_to_stack, commit_message};
+
+    fn generated(_branch_name: &str, _assignments: &[HunkAssignment]) -> Option<String> {
+        Some("generated".into())
+    }
+
+    fn failed(_branch_name: &str, _assignments: &[HunkAssignment]) -> Option<String> {
+        None
+    }
+
+    fn not_called(_branch_name: &str, _assignments: &[HunkAssignment]) -> Option<String> {
+        panic!("the template takes precedence over generating a message")
+    }
+
+    #[test]
+    fn template_is_rendered_with_branch_and_unique_files() {
+        let assignments = [assignment("a.rs"), assignment("b.rs"), assignment("a.rs")];
+        assert_eq!(
+            commit_message(
+                "feat",
+                Some("{branch}: change {files}"),
+                &assignments,
+                Some(not_called)
+            ),
+            "feat: change a.rs, b.rs"
+        );
+    }
+
+    #[test]
+    fn without_template_or_generation_the_message_is_derived_from_paths() {
+        let assignments = [assignment("a.rs"), assignment("b.rs")];
+        assert_eq!(
+            commit_message("feat", None, &assignments, None),
+            "Update a.rs, b.rs"
+        );
+    }
+
+    #[test]
+    fn generated_message_is_used_if_enabled() {
+        let assignments = [assignment("a.rs")];
+        assert_eq!(
+            commit_message("feat", None, &assignments, Some(generated)),
+            "generated"
+        );
+        assert_eq!(
+            commit_message("feat", None, &assignments, Some(failed)),
+            "Update a.rs",
+            "failing to generate falls back to the paths"
+        );
+    }
+
+    #[test]
+    fn only_hunks_of_the_target_stack_belong_to_it() {
+        let target = stack(1);
+        let unassigned = assignment("a.rs");
+        assert!(belongs_to_stack(&unassigned, target));
+
+        let assigned_to_other = HunkAssignment {
+            stack_id: Some(stack(2)),
+            ..assignment("a.rs")
+        };
+        assert!(!belongs_to_stack(&assigned_to_other, target));
+
+        let locked_to_target = HunkAssignment {
+            stack_id: Some(target),
+            hunk_locks: Some(vec![lock(1, 1)]),
+            ..assignment("a.rs")
+        };
+        assert!(belongs_to_stack(&locked_to_target, target));
+
+        let locked_to_other = HunkAssignment {
+            hunk_locks: Some(vec![lock(1, 1), lock(2, 2)]),
+            ..assignment("a.rs")
+        };
+        assert!(
+            !belongs_to_stack(&locked_to_other, target),
+            "committing it to the target would separate it from the commit it depends on"
+        );
+    }
+}
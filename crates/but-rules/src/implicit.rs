@@ -0,0 +1,364 @@
+//! The offline heuristics behind [implicit operations](crate::ImplicitOperation), based on hunk dependencies.
+use but_hunk_assignment::HunkAssignment;
+use but_hunk_dependency::ui::{HunkDependencies, HunkLock};
+use but_workspace::{HunkHeader, StackId};
+use itertools::Itertools;
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::ImplicitOperation;
+
+/// A change that an implicit operation makes, or would make in a dry-run, to an uncommitted hunk.
+#[derive(Serialize, Debug, Clone, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PlannedChange {
+    /// The path of the file the hunk belongs to.
+    pub path: String,
+    /// The hunk, or `None` if the change applies to the whole file.
+    pub hunk_header: Option<HunkHeader>,
+    /// What happens to the hunk.
+    pub action: PlannedAction,
+}
+
+/// What happens to a hunk when an implicit operation is applied.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase", tag = "type", content = "subject")]
+pub enum PlannedAction {
+    /// The hunk is assigned to the stack.
+    #[serde(rename_all = "camelCase")]
+    Assign { stack_id: StackId },
+    /// The hunk is amended into the commit it depends on, which is in the given stack.
+    #[serde(rename_all = "camelCase")]
+    Absorb {
+        stack_id: StackId,
+        #[serde(with = "gitbutler_serde::object_id")]
+        #[schemars(with = "String")]
+        commit_id: gix::ObjectId,
+    },
+}
+
+/// Decide what `operation` does with each of `assignments`, without changing anything.
+///
+/// - [`ImplicitOperation::AbsorbIntoDependentCommit`] absorbs hunks that depend on exactly one commit into it.
+/// - [`ImplicitOperation::AssignToAppropriateBranch`] assigns hunks to the only stack they depend on. Hunks
+///   without dependencies go to the stack of the locked hunks they overlap or border in the same file.
+///
+/// Hunks that depend on more than one commit or stack are ambiguous and left alone, as are hunks that are
+/// already where they would be assigned to. [`ImplicitOperation::LLMPrompt`] isn't an offline heuristic and plans nothing.
+pub fn plan(
+    operation: &ImplicitOperation,
+    assignments: &[HunkAssignment],
+    dependencies: Option<&HunkDependencies>,
+) -> Vec<PlannedChange> {
+    let planned_change = |assignment: &HunkAssignment, action| PlannedChange {
+        path: assignment.path.clone(),
+        hunk_header: assignment.hunk_header,
+        action,
+    };
+    match operation {
+        ImplicitOperation::AbsorbIntoDependentCommit => assignments
+            .iter()
+            .filter_map(|assignment| {
+                let lock = single(locks_of(assignment, dependencies), |lock| lock.commit_id)?;
+                Some(planned_change(
+                    assignment,
+                    PlannedAction::Absorb {
+                        stack_id: lock.stack_id,
+                        commit_id: lock.commit_id,
+                    },
+                ))
+            })
+            .collect(),
+        ImplicitOperation::AssignToAppropriateBranch => assignments
+            .iter()
+            .filter_map(|assignment| {
+                let locks = locks_of(assignment, dependencies);
+                let stack_id = if locks.is_empty() {
+                    stack_of_adjacent_hunks(assignment, dependencies)?
+                } else {
+                    single(locks, |lock| lock.stack_id)?.stack_id
+                };
+                (assignment.stack_id != Some(stack_id))
+                    .then(|| planned_change(assignment, PlannedAction::Assign { stack_id }))
+            })
+            .collect(),
+        ImplicitOperation::LLMPrompt(_) => Vec::new(),
+    }
+}
+
+/// The locks of `assignment`, looked up in `dependencies` if the assignment doesn't carry them.
+fn locks_of(assignment: &HunkAssignment, dependencies: Option<&HunkDependencies>) -> Vec<HunkLock> {
+    if let Some(locks) = assignment.hunk_locks.as_ref().filter(|l| !l.is_empty()) {
+        return locks.clone();
+    }
+    let (Some(dependencies), Some(header)) = (dependencies, assignment.hunk_header) else {
+        return Vec::new();
+    };
+    dependencies
+        .diffs
+        .iter()
+        .find(|(path, hunk, _)| *path == assignment.path && HunkHeader::from(hunk) == header)
+        .map(|(_, _, locks)| locks.clone())
+        .unwrap_or_default()
+}
+
+/// Return the first of `locks` if all of them agree on the `key`, or `None` if there are none or they disagree.
+fn single<K: PartialEq>(locks: Vec<HunkLock>, key: impl Fn(&HunkLock) -> K) -> Option<HunkLock> {
+    let first = *locks.first()?;
+    locks
+        .iter()
+        .all(|lock| key(lock) == key(&first))
+        .then_some(first)
+}
+
+/// The stack owning the locked hunks of the same file that overlap or directly border `assignment`,
+/// if their locks are all in one stack.
+///
+/// Only touching hunks are considered as a change further away in the file tells nothing about where this one belongs.
+fn stack_of_adjacent_hunks(
+    assignment: &HunkAssignment,
+    dependencies: Option<&HunkDependencies>,
+) -> Option<StackId> {
+    let header = assignment.hunk_header?;
+    let range = header.new_start..=header.new_start + header.new_lines;
+    let stacks = dependencies?
+        .diffs
+        .iter()
+        .filter(|(path, hunk, _)| {
+            *path == assignment.path
+                && hunk.new_start <= *range.end()
+                && *range.start() <= hunk.new_start + hunk.new_lines
+        })
+        .flat_map(|(_, _, locks)| locks)
+        .map(|lock| lock.stack_id)
+        .unique()
+        .collect_vec();
+    match stacks.as_slice() {
+        [stack_id] => Some(*stack_id),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use but_core::unified_diff::DiffHunk;
+    use but_hunk_dependency::ui::{HunkDependencies, HunkLock};
+
+    use super::{PlannedAction, PlannedChange, plan};
+    use crate::{
+        ImplicitOperation,
+        testing::{commit, header, hunk, lock, stack},
+    };
+
+    const NOTHING: [PlannedChange; 0] = [];
+
+    fn dependencies(diffs: &[(&str, u32, &[HunkLock])]) -> HunkDependencies {
+        HunkDependencies {
+            diffs: diffs
+                .iter()
+                .map(|(path, start, locks)| {
+                    let hunk = DiffHunk {
+                        old_start: *start,
+                        old_lines: 1,
+                        new_start: *start,
+                        new_lines: 1,
+                        diff: Default::default(),
+                    };
+                    (path.to_string(), hunk, locks.to_vec())
+                })
+                .collect(),
+            errors: Vec::new(),
+        }
+    }
+
+    fn absorb(path: &str, start: u32, stack_id: u128, commit_id: u8) -> PlannedChange {
+        PlannedChange {
+            path: path.into(),
+            hunk_header: Some(header(start)),
+            action: PlannedAction::Absorb {
+                stack_id: stack(stack_id),
+                commit_id: commit(commit_id),
+            },
+        }
+    }
+
+    fn assign(path: &str, start: u32, stack_id: u128) -> PlannedChange {
+        PlannedChange {
+            path: path.into(),
+            hunk_header: Some(header(start)),
+            action: PlannedAction::Assign {
+                stack_id: stack(stack_id),
+            },
+        }
+    }
+
+    #[test]
+    fn single_lock() {
+        let assignments = [hunk("a", 1, &[lock(1, 1)])];
+        assert_eq!(
+            plan(
+                &ImplicitOperation::AbsorbIntoDependentCommit,
+                &assignments,
+                None
+            ),
+            [absorb("a", 1, 1, 1)]
+        );
+        assert_eq!(
+            plan(
+                &ImplicitOperation::AssignToAppropriateBranch,
+                &assignments,
+                None
+            ),
+            [assign("a", 1, 1)]
+        );
+    }
+
+    #[test]
+    fn locks_agreeing_on_the_commit_or_stack() {
+        let assignments = [hunk("a", 1, &[lock(1, 1), lock(1, 1)])];
+        assert_eq!(
+            plan(
+                &ImplicitOperation::AbsorbIntoDependentCommit,
+                &assignments,
+                None
+            ),
+            [absorb("a", 1, 1, 1)]
+        );
+
+        let assignments = [hunk("a", 1, &[lock(1, 1), lock(1, 2)])];
+        assert_eq!(
+            plan(
+                &ImplicitOperation::AssignToAppropriateBranch,
+                &assignments,
+                None
+            ),
+            [assign("a", 1, 1)],
+            "commits of the same stack agree on the stack"
+        );
+    }
+
+    #[test]
+    fn conflicting_locks_are_left_alone() {
+        let assignments = [hunk("a", 1, &[lock(1, 1), lock(1, 2)])];
+        assert_eq!(
+            plan(
+                &ImplicitOperation::AbsorbIntoDependentCommit,
+                &assignments,
+                None
+            ),
+            NOTHING,
+            "the hunk depends on two commits"
+        );
+
+        let assignments = [hunk("a", 1, &[lock(1, 1), lock(2, 2)])];
+        assert_eq!(
+            plan(
+                &ImplicitOperation::AssignToAppropriateBranch,
+                &assignments,
+                None
+            ),
+            NOTHING,
+            "the hunk depends on two stacks"
+        );
+    }
+
+    #[test]
+    fn no_locks() {
+        let assignments = [hunk("a", 1, &[])];
+        for operation in [
+            ImplicitOperation::AbsorbIntoDependentCommit,
+            ImplicitOperation::AssignToAppropriateBranch,
+        ] {
+            assert_eq!(plan(&operation, &assignments, None), NOTHING);
+            assert_eq!(
+                plan(&operation, &assignments, Some(&dependencies(&[]))),
+                NOTHING
+            );
+        }
+    }
+
+    #[test]
+    fn locks_are_looked_up_in_dependencies() {
+        let assignments = [hunk("a", 5, &[])];
+        let deps = dependencies(&[("a", 1, &[lock(2, 2)]), ("a", 5, &[lock(1, 1)])]);
+        assert_eq!(
+            plan(
+                &ImplicitOperation::AbsorbIntoDependentCommit,
+                &assignments,
+                Some(&deps)
+            ),
+            [absorb("a", 5, 1, 1)]
+        );
+    }
+
+    #[test]
+    fn hunks_already_in_their_stack_are_left_alone() {
+        let mut assignment = hunk("a", 1, &[lock(1, 1)]);
+        assignment.stack_id = Some(stack(1));
+        assert_eq!(
+            plan(
+                &ImplicitOperation::AssignToAppropriateBranch,
+                &[assignment],
+                None
+            ),
+            NOTHING
+        );
+    }
+
+    #[test]
+    fn stack_of_adjacent_hunks() {
+        let deps = dependencies(&[
+            ("a", 10, &[lock(1, 1)]),
+            ("a", 100, &[lock(2, 2)]),
+            ("b", 90, &[lock(3, 3)]),
+        ]);
+        let assignments = [
+            hunk("a", 11, &[]),
+            hunk("a", 50, &[]),
+            hunk("a", 90, &[]),
+            hunk("a", 99, &[]),
+        ];
+        assert_eq!(
+            plan(
+                &ImplicitOperation::AssignToAppropriateBranch,
+                &assignments,
+                Some(&deps)
+            ),
+            [assign("a", 11, 1), assign("a", 99, 2)],
+            "hunks that don't touch a locked hunk of the same file are left alone"
+        );
+        assert_eq!(
+            plan(
+                &ImplicitOperation::AbsorbIntoDependentCommit,
+                &assignments,
+                Some(&deps)
+            ),
+            NOTHING,
+            "hunks without dependencies aren't absorbed"
+        );
+
+        let deps = dependencies(&[("a", 10, &[lock(1, 1)]), ("a", 12, &[lock(2, 2)])]);
+        assert_eq!(
+            plan(
+                &ImplicitOperation::AssignToAppropriateBranch,
+                &[hunk("a", 11, &[])],
+                Some(&deps)
+            ),
+            NOTHING,
+            "the adjacent hunks are locked to more than one stack"
+        );
+    }
+
+    #[test]
+    fn llm_prompt_plans_nothing() {
+        let assignments = [hunk("a", 1, &[lock(1, 1)])];
+        assert_eq!(
+            plan(
+                &ImplicitOperation::LLMPrompt("prompt".into()),
+                &assignments,
+                None
+            ),
+            NOTHING
+        );
+    }
+}
@@ -1,11 +1,16 @@
 use but_hunk_dependency::ui::hunk_dependencies_for_workspace_changes_by_worktree_dir;
 use gitbutler_command_context::CommandContext;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 pub mod db;
+pub mod evaluate;
 pub mod handler;
+pub mod implicit;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct WorkspaceRule {
     /// A UUID unique identifier for the rule.
@@ -68,7 +73,7 @@ impl WorkspaceRule {
 }
 
 /// Represents the kinds of events in the app that can cause a rule to be evaluated.
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub enum Trigger {
     /// When a file is added, removed or modified in the Git worktree.
@@ -79,14 +84,16 @@ pub enum Trigger {
 
 /// A filter is a condition that determines what files or changes the rule applies to.
 /// Within a filter, multiple conditions are combined with AND logic (i.e. to match all conditions must be met)
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
 #[serde(rename_all = "camelCase", tag = "type", content = "subject")]
 pub enum Filter {
     /// Matches the file path (relative to the repository root).
     #[serde(with = "serde_regex")]
+    #[schemars(with = "String")]
     PathMatchesRegex(regex::Regex),
     /// Match the file content.
     #[serde(with = "serde_regex")]
+    #[schemars(with = "String")]
     ContentMatchesRegex(regex::Regex),
     /// Matches the file change operation type (e.g. addition, deletion, modification, rename)
     FileChangeType(TreeStatus),
@@ -98,7 +105,7 @@ pub enum Filter {
 
 /// Represents the type of change that occurred in the Git worktree.
 /// Matches the TreeStatus of the TreeChange
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub enum TreeStatus {
     /// Something was added or scheduled to be added.
@@ -113,7 +120,7 @@ pub enum TreeStatus {
 
 /// Represents a semantic type of change that was inferred for the change.
 /// Typically this means a heuristic or an LLM determinded that a change represents a refactor, a new feature, a bug fix, or documentation update.
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
 #[serde(rename_all = "camelCase", tag = "type", content = "subject")]
 pub enum SemanticType {
     /// A change that is a refactor, meaning it does not change the external behavior of the code but improves its structure.
@@ -131,7 +138,7 @@ pub enum SemanticType {
 /// Represents an action that can be taken based on the rule evaluation.
 /// An action can be either explicit, meaning the user defined something like "Assign in Lane A" or "Ammend into Commit X"
 /// or it is implicit, meaning the action was determined by heuristics or AI, such as "Assign to appropriate branch" or "Absorb in dependent commit".
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
 #[serde(rename_all = "camelCase", tag = "type", content = "subject")]
 pub enum Action {
     /// An action that has an explicit operation defined by the user.
@@ -141,19 +148,29 @@ pub enum Action {
 }
 
 /// Represents the operation that a user can configure to be performed in an explicit action.
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
 #[serde(rename_all = "camelCase", tag = "type", content = "subject")]
 pub enum Operation {
     /// Assign the matched changes to a specific stack ID.
     Assign { target: StackTarget },
     /// Amend the matched changes into a specific commit.
     Amend { change_id: String },
-    /// Create a new commit with the matched changes on a specific branch.
-    NewCommit { branch_name: String },
+    /// Create a new commit with the matched changes on a specific branch, which is created if it doesn't exist.
+    NewCommit {
+        branch_name: String,
+        /// The message of the commit, with `{branch}` replaced by the branch name and `{files}` by the changed paths.
+        /// If not set, the message is derived from the changed paths, or generated if `generate_message` is set.
+        #[serde(default)]
+        message_template: Option<String>,
+        /// Generate the message with AI if there is no `message_template`, which sends the diff of the changes
+        /// to the AI provider. The message is derived from the changed paths if this is unset or generation fails.
+        #[serde(default)]
+        generate_message: bool,
+    },
 }
 
 /// The target stack for a given operation. It's either specifying a specific stack ID, or alternaitvely the leftmost or rightmost stack in the workspace.
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
 #[serde(rename_all = "camelCase", tag = "type", content = "subject")]
 pub enum StackTarget {
     StackId(String),
@@ -162,7 +179,7 @@ pub enum StackTarget {
 }
 
 /// Represents the implicit operation that is determined by heuristics or AI.
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
 #[serde(rename_all = "camelCase", tag = "type", content = "subject")]
 pub enum ImplicitOperation {
     /// Assign the matched changes to the appropriate branch based on offline heuristics.
@@ -174,7 +191,7 @@ pub enum ImplicitOperation {
 }
 
 /// A request to create a new workspace rule.
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateRuleRequest {
     /// The trigger that causes the rule to be evaluated.
@@ -217,7 +234,7 @@ pub fn delete_rule(ctx: &mut CommandContext, id: &str) -> anyhow::Result<()> {
 }
 
 /// A request to update an existing workspace rule.
-#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct UpdateRuleRequest {
     /// The ID of the rule to update.
@@ -300,7 +317,58 @@ pub fn list_rules(ctx: &mut CommandContext) -> anyhow::Result<Vec<WorkspaceRule>
     Ok(rules)
 }
 
-pub fn process_rules(ctx: &mut CommandContext) -> anyhow::Result<()> {
+/// Applies the workspace rules to the current uncommitted changes, returning the rules that couldn't be applied
+/// along with the amount of changed assignments.
+pub fn process_rules(ctx: &mut CommandContext) -> anyhow::Result<handler::Outcome> {
+    let (assignments, dependencies) = workspace_assignments(ctx)?;
+    handler::process_workspace_rules(ctx, &assignments, &Some(dependencies))
+}
+
+/// Previews what the implicit operation of the rule with `id` would do with the current uncommitted changes,
+/// without changing anything. Rules with explicit actions plan nothing.
+pub fn preview_implicit_rule(
+    ctx: &mut CommandContext,
+    id: &str,
+) -> anyhow::Result<Vec<implicit::PlannedChange>> {
+    let rule = get_rule(ctx, id)?;
+    let Action::Implicit(operation) = &rule.action else {
+        return Ok(Vec::new());
+    };
+    let (assignments, dependencies) = workspace_assignments(ctx)?;
+    let assignments = handler::matching(&assignments, rule.filters.clone());
+    Ok(implicit::plan(operation, &assignments, Some(&dependencies)))
+}
+
+/// Evaluates all workspace rules against the current uncommitted changes without changing anything, explaining
+/// for each hunk which rules match it, which filters reject it and what would happen to it.
+pub fn evaluate_rules(ctx: &mut CommandContext) -> anyhow::Result<evaluate::Evaluation> {
+    let rules = list_rules(ctx)?;
+    let (assignments, dependencies) = workspace_assignments(ctx)?;
+    let stacks_in_ws = handler::stacks_in_workspace(ctx)?;
+    let needs_change_ids = rules
+        .iter()
+        .any(|r| matches!(r.action, Action::Explicit(Operation::Amend { .. })));
+    let change_ids = if needs_change_ids {
+        handler::change_ids_by_commit(ctx)?
+    } else {
+        Default::default()
+    };
+    Ok(evaluate::evaluate(
+        &rules,
+        &assignments,
+        Some(&dependencies),
+        &stacks_in_ws,
+        &change_ids,
+    ))
+}
+
+/// The assignments of all uncommitted changes along with their dependencies.
+pub(crate) fn workspace_assignments(
+    ctx: &mut CommandContext,
+) -> anyhow::Result<(
+    Vec<but_hunk_assignment::HunkAssignment>,
+    but_hunk_dependency::ui::HunkDependencies,
+)> {
     let wt_changes = but_core::diff::worktree_changes(&ctx.gix_repo()?)?;
 
     let dependencies = hunk_dependencies_for_workspace_changes_by_worktree_dir(
@@ -317,7 +385,5 @@ pub fn process_rules(ctx: &mut CommandContext) -> anyhow::Result<()> {
         Some(&dependencies),
     )
     .map_err(|e| anyhow::anyhow!("Failed to get assignments: {}", e))?;
-
-    handler::process_workspace_rules(ctx, &assignments, &Some(dependencies))?;
-    Ok(())
+    Ok((assignments, dependencies))
 }
@@ -0,0 +1,56 @@
+//! Constructors for the values that tests of rules, and of what applies them, are made of.
+use but_hunk_assignment::HunkAssignment;
+use but_hunk_dependency::ui::HunkLock;
+use but_workspace::{HunkHeader, StackId};
+
+/// The stack identified by `id`.
+pub fn stack(id: u128) -> StackId {
+    StackId::from_number_for_testing(id)
+}
+
+/// The commit whose hash consists of `id` only.
+pub fn commit(id: u8) -> gix::ObjectId {
+    gix::ObjectId::from_bytes_or_panic(&[id; 20])
+}
+
+/// A lock on the commit identified by `commit_id` in the stack identified by `stack_id`.
+pub fn lock(stack_id: u128, commit_id: u8) -> HunkLock {
+    HunkLock {
+        stack_id: stack(stack_id),
+        commit_id: commit(commit_id),
+    }
+}
+
+/// The header of a hunk that changes the line at `start`.
+pub fn header(start: u32) -> HunkHeader {
+    HunkHeader {
+        old_start: start,
+        old_lines: 1,
+        new_start: start,
+        new_lines: 1,
+    }
+}
+
+/// An unassigned change of the whole file at `path` that adds a `TODO`.
+pub fn assignment(path: &str) -> HunkAssignment {
+    HunkAssignment {
+        id: None,
+        hunk_header: None,
+        path: path.into(),
+        path_bytes: path.into(),
+        stack_id: None,
+        hunk_locks: None,
+        line_nums_added: None,
+        line_nums_removed: None,
+        diff: Some("@@ -1 +1 @@\n-old\n+new TODO\n".into()),
+    }
+}
+
+/// An unassigned hunk of `path` at [`header(start)`](header), which depends on `locks`.
+pub fn hunk(path: &str, start: u32, locks: &[HunkLock]) -> HunkAssignment {
+    HunkAssignment {
+        hunk_header: Some(header(start)),
+        hunk_locks: Some(locks.to_vec()),
+        ..assignment(path)
+    }
+}
@@ -0,0 +1,274 @@
+//! Token authentication for clients connecting to the server, and the commands they may run.
+use std::{collections::HashSet, net::IpAddr, sync::Arc};
+
+use axum::{
+    extract::{Request, State},
+    http::{StatusCode, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use gitbutler_secret::{Sensitive, secret};
+
+/// The secret handle of the token that grants access to all commands.
+const TOKEN_HANDLE: &str = "but-server-token";
+/// The secret handle of the token that only grants access to the commands in `BUTLER_ALLOWED_COMMANDS`.
+const RESTRICTED_TOKEN_HANDLE: &str = "but-server-restricted-token";
+
+/// What an authenticated client may do.
+#[derive(Debug, Clone)]
+pub(crate) enum Access {
+    /// All commands may be run.
+    Full,
+    /// Only the commands in the set may be run.
+    Restricted(Arc<HashSet<String>>),
+}
+
+impl Access {
+    pub(crate) fn allows(&self, command: &str) -> bool {
+        match self {
+            Access::Full => true,
+            Access::Restricted(commands) => commands.contains(command),
+        }
+    }
+}
+
+/// The tokens clients have to present, as `Authorization: Bearer <token>` header or as `token` query parameter.
+/// The latter is for websockets, which can't be opened with custom headers from a browser.
+pub(crate) struct Auth {
+    token: Sensitive<String>,
+    restricted: Option<(Sensitive<String>, Arc<HashSet<String>>)>,
+}
+
+impl Auth {
+    /// Read the configuration from the environment and return `None` if clients don't need to authenticate.
+    ///
+    /// Authentication is required if `required` is `true`, which is the case when listening on a non-loopback interface,
+    /// or if `BUTLER_AUTH` is set to `1`.
+    /// `BUTLER_ALLOWED_COMMANDS` is a comma-separated list of commands that may be run with the restricted token.
+    ///
+    /// Tokens are generated on first use and kept in the secret store, so they stay the same across restarts.
+    pub(crate) fn from_env(required: bool) -> anyhow::Result<Option<Self>> {
+        let required = required || std::env::var("BUTLER_AUTH").is_ok_and(|value| value == "1");
+        if !required {
+            return Ok(None);
+        }
+        let restricted = match std::env::var("BUTLER_ALLOWED_COMMANDS") {
+            Ok(commands) => {
+                let commands = commands
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|command| !command.is_empty())
+                    .map(ToOwned::to_owned)
+                    .collect();
+                Some((token(RESTRICTED_TOKEN_HANDLE)?, Arc::new(commands)))
+            }
+            Err(_) => None,
+        };
+        Ok(Some(Auth {
+            token: token(TOKEN_HANDLE)?,
+            restricted,
+        }))
+    }
+
+    fn access(&self, token: &str) -> Option<Access> {
+        if constant_time_eq(token, &self.token.0) {
+            return Some(Access::Full);
+        }
+        let (restricted_token, commands) = self.restricted.as_ref()?;
+        constant_time_eq(token, &restricted_token.0).then(|| Access::Restricted(commands.clone()))
+    }
+
+    /// Tell where to find the tokens, and print them if `BUTLER_PRINT_TOKENS` is set to `1`.
+    pub(crate) fn print_tokens(&self) {
+        let print = std::env::var("BUTLER_PRINT_TOKENS").is_ok_and(|value| value == "1");
+        let describe = |handle: &str, token: &Sensitive<String>| {
+            if print {
+                format!("token {}", token.0)
+            } else {
+                format!(
+                    "the token stored as '{}' in the system keychain",
+                    keychain_entry(handle)
+                )
+            }
+        };
+        println!("Authenticate with {}", describe(TOKEN_HANDLE, &self.token));
+        if let Some((token, commands)) = &self.restricted {
+            let mut commands = commands.iter().map(String::as_str).collect::<Vec<_>>();
+            commands.sort();
+            println!(
+                "Authenticate with {} to only run {}",
+                describe(RESTRICTED_TOKEN_HANDLE, token),
+                commands.join(", ")
+            );
+        }
+    }
+}
+
+/// Reject requests without a valid token, if `auth` is set, and make the [`Access`] of the client available to handlers.
+pub(crate) async fn authenticate(
+    State(auth): State<Option<Arc<Auth>>>,
+    mut req: Request,
+    next: Next,
+) -> Response {
+    let access = match auth.as_deref() {
+        None => Access::Full,
+        Some(auth) => match request_token(&req).and_then(|token| auth.access(token)) {
+            Some(access) => access,
+            None => return StatusCode::UNAUTHORIZED.into_response(),
+        },
+    };
+    req.extensions_mut().insert(access);
+    next.run(req).await
+}
+
+/// Return `true` if `host` can only be reached from this machine.
+pub(crate) fn is_loopback(host: &str) -> bool {
+    host == "localhost" || host.parse::<IpAddr>().is_ok_and(|ip| ip.is_loopback())
+}
+
+fn request_token(req: &Request) -> Option<&str> {
+    req.headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .or_else(|| {
+            req.uri()
+                .query()?
+                .split('&')
+                .find_map(|pair| pair.strip_prefix("token="))
+        })
+}
+
+/// The name of the system keychain entry of the token with `handle`, as stored by [`secret::persist()`].
+fn keychain_entry(handle: &str) -> String {
+    format!("gitbutler-{handle}")
+}
+
+fn token(handle: &str) -> anyhow::Result<Sensitive<String>> {
+    if let Some(token) = secret::retrieve(handle, secret::Namespace::Global)? {
+        return Ok(token);
+    }
+    let token = Sensitive(format!(
+        "{}{}",
+        uuid::Uuid::new_v4().simple(),
+        uuid::Uuid::new_v4().simple()
+    ));
+    secret::persist(handle, &token, secret::Namespace::Global)?;
+    Ok(token)
+}
+
+fn constant_time_eq(a: &str, b: &str) -> bool {
+    a.len() == b.len()
+        && a.bytes()
+            .zip(b.bytes())
+            .fold(0, |acc, (a, b)| acc | (a ^ b))
+            == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashSet, sync::Arc};
+
+    use axum::{body::Body, extract::Request, http::header};
+    use gitbutler_secret::Sensitive;
+
+    use super::{Access, Auth, constant_time_eq, is_loopback, request_token};
+
+    fn auth(restricted_commands: Option<&[&str]>) -> Auth {
+        Auth {
+            token: Sensitive("full-access".into()),
+            restricted: restricted_commands.map(|commands| {
+                (
+                    Sensitive("restricted".into()),
+                    Arc::new(commands.iter().map(|c| c.to_string()).collect()),
+                )
+            }),
+        }
+    }
+
+    #[test]
+    fn constant_time_eq_compares_content_and_length() {
+        assert!(constant_time_eq("", ""));
+        assert!(constant_time_eq("token", "token"));
+        assert!(!constant_time_eq("token", "tokeN"));
+        assert!(!constant_time_eq("token", "token2"));
+        assert!(!constant_time_eq("token", ""));
+    }
+
+    #[test]
+    fn is_loopback_only_accepts_local_hosts() {
+        assert!(is_loopback("localhost"));
+        assert!(is_loopback("127.0.0.1"));
+        assert!(is_loopback("127.1.2.3"));
+        assert!(is_loopback("::1"));
+        assert!(!is_loopback("0.0.0.0"));
+        assert!(!is_loopback("::"));
+        assert!(!is_loopback("192.168.1.1"));
+        assert!(!is_loopback("example.com"));
+    }
+
+    #[test]
+    fn request_token_from_header_or_query() {
+        let req = Request::builder()
+            .uri("/?token=from-query")
+            .header(header::AUTHORIZATION, "Bearer from-header")
+            .body(Body::empty())
+            .unwrap();
+        assert_eq!(
+            request_token(&req),
+            Some("from-header"),
+            "the header takes precedence"
+        );
+
+        let req = Request::builder()
+            .uri("/ws?project=a&token=from-query")
+            .body(Body::empty())
+            .unwrap();
+        assert_eq!(request_token(&req), Some("from-query"));
+
+        let req = Request::builder()
+            .uri("/?token=from-query")
+            .header(header::AUTHORIZATION, "Basic dXNlcjpwYXNz")
+            .body(Body::empty())
+            .unwrap();
+        assert_eq!(
+            request_token(&req),
+            Some("from-query"),
+            "other schemes are ignored"
+        );
+
+        let req = Request::builder()
+            .uri("/?mytoken=x")
+            .body(Body::empty())
+            .unwrap();
+        assert_eq!(request_token(&req), None);
+    }
+
+    #[test]
+    fn access_allows() {
+        assert!(Access::Full.allows("anything"));
+        let restricted = Access::Restricted(Arc::new(HashSet::from(["stacks".to_string()])));
+        assert!(restricted.allows("stacks"));
+        assert!(!restricted.allows("stack_details"));
+        assert!(!restricted.allows(""));
+    }
+
+    #[test]
+    fn auth_access_by_token() {
+        let auth = auth(Some(&["stacks"]));
+        assert!(matches!(auth.access("full-access"), Some(Access::Full)));
+        let Some(Access::Restricted(commands)) = auth.access("restricted") else {
+            panic!("the restricted token grants restricted access");
+        };
+        assert_eq!(*commands, HashSet::from(["stacks".to_string()]));
+        assert!(auth.access("wrong").is_none());
+        assert!(auth.access("").is_none());
+    }
+
+    #[test]
+    fn auth_access_without_restricted_token() {
+        let auth = auth(None);
+        assert!(matches!(auth.access("full-access"), Some(Access::Full)));
+        assert!(auth.access("restricted").is_none());
+    }
+}
@@ -0,0 +1,41 @@
+//! Turn changes into the events the frontend listens to, using the same names and payloads as the Tauri app.
+use but_broadcaster::FrontendEvent;
+use but_settings::AppSettings;
+use gitbutler_watcher::Change;
+
+/// Return the event that informs about a `change` in a watched project.
+pub(crate) fn from_change(change: Change) -> FrontendEvent {
+    match change {
+        Change::GitFetch(project_id) => FrontendEvent {
+            name: format!("project://{project_id}/git/fetch"),
+            payload: serde_json::json!({}),
+        },
+        Change::GitHead {
+            project_id,
+            head,
+            operating_mode,
+        } => FrontendEvent {
+            name: format!("project://{project_id}/git/head"),
+            payload: serde_json::json!({ "head": head, "operatingMode": operating_mode }),
+        },
+        Change::GitActivity(project_id) => FrontendEvent {
+            name: format!("project://{project_id}/git/activity"),
+            payload: serde_json::json!({}),
+        },
+        Change::WorktreeChanges {
+            project_id,
+            changes,
+        } => FrontendEvent {
+            name: format!("project://{project_id}/worktree_changes"),
+            payload: serde_json::json!(&changes),
+        },
+    }
+}
+
+/// Return the event that informs about updated app `settings`.
+pub(crate) fn from_settings(settings: AppSettings) -> FrontendEvent {
+    FrontendEvent {
+        name: "settings://update".to_string(),
+        payload: serde_json::json!(settings),
+    }
+}
@@ -1,24 +1,25 @@
-use std::sync::Arc;
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
 use axum::{
-    Json, Router,
+    Extension, Json, Router,
     extract::{
         WebSocketUpgrade,
         ws::{Message, WebSocket},
     },
+    http::StatusCode,
+    middleware,
     response::IntoResponse,
     routing::{any, get},
 };
 use but_api::{
     App, NoParams,
-    commands::{
-        askpass, cherry_apply, claude, cli, config, diff, forge, git, github, modes, open,
-        projects as iprojects, remotes, repo, rules, secret, settings, stack, undo, users,
-        virtual_branches, workspace, zip,
-    },
+    commands::{askpass, claude, github, settings, zip},
     error::ToError as _,
 };
-use but_broadcaster::Broadcaster;
+use but_broadcaster::{Broadcaster, Subscription};
 use but_settings::AppSettingsWithDiskSync;
 use futures_util::{SinkExt, StreamExt as _};
 use gitbutler_project::ProjectId;
@@ -28,8 +29,24 @@ use tokio::sync::Mutex;
 use tower::ServiceBuilder;
 use tower_http::cors::{Any, CorsLayer};
 
+mod auth;
+mod events;
 mod projects;
-use crate::projects::ActiveProjects;
+use crate::{
+    auth::{Access, Auth},
+    projects::ActiveProjects,
+};
+
+/// A message sent by a client over the websocket to select the events it receives.
+///
+/// Clients start out subscribed to all events, so to only receive some they unsubscribe from the subscription
+/// without topic and project ID.
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+enum ClientMessage {
+    Subscribe(Subscription),
+    Unsubscribe(Subscription),
+}
 
 #[derive(Serialize, Deserialize)]
 #[serde(tag = "type", content = "subject", rename_all = "camelCase")]
@@ -63,9 +80,19 @@ pub async fn run() {
     let extra = Extra {
         active_projects: Arc::new(Mutex::new(ActiveProjects::new())),
     };
-    // TODO: This needs to watch and send events accordingly (it's done in the Tauri main but not here)
-    let app_settings =
+    let mut app_settings =
         AppSettingsWithDiskSync::new(config_dir.clone()).expect("failed to create app settings");
+    app_settings
+        .watch_in_background({
+            let broadcaster = broadcaster.clone();
+            move |settings| {
+                broadcaster
+                    .blocking_lock()
+                    .send(events::from_settings(settings));
+                Ok(())
+            }
+        })
+        .expect("failed to watch app settings");
 
     let app = App {
         broadcaster: broadcaster.clone(),
@@ -76,6 +103,14 @@ pub async fn run() {
         claudes: Default::default(),
     };
 
+    let port = std::env::var("BUTLER_PORT").unwrap_or("6978".into());
+    let host = std::env::var("BUTLER_HOST").unwrap_or("127.0.0.1".into());
+    // Only the owner of the socket file can connect to it, while anyone reaching the port can connect via TCP.
+    let socket_path = std::env::var_os("BUTLER_SOCKET").map(PathBuf::from);
+    let auth = Auth::from_env(socket_path.is_none() && !auth::is_loopback(&host))
+        .expect("failed to load authentication tokens")
+        .map(Arc::new);
+
     // build our application with a single route
     let app = Router::new()
         .route(
@@ -83,9 +118,15 @@ pub async fn run() {
             get(|| async { "Hello, World!" }).post({
                 let app = app.clone();
                 let extra = extra.clone();
-                move |req| handle_command(req, app, extra, app_settings)
+                move |Extension(access): Extension<Access>, req| {
+                    handle_command(req, access, app, extra, app_settings)
+                }
             }),
         )
+        .route(
+            "/commands",
+            get(|| async { Json(but_api::registry::describe()) }),
+        )
         .route(
             "/ws",
             any({
@@ -93,16 +134,63 @@ pub async fn run() {
                 async move |req| handle_ws_request(req, broadcaster).await
             }),
         )
+        .layer(middleware::from_fn_with_state(
+            auth.clone(),
+            auth::authenticate,
+        ))
         .layer(ServiceBuilder::new().layer(cors));
 
-    let port = std::env::var("BUTLER_PORT").unwrap_or("6978".into());
-    let host = std::env::var("BUTLER_HOST").unwrap_or("127.0.0.1".into());
+    if let Some(auth) = &auth {
+        auth.print_tokens();
+    }
+    if let Some(socket_path) = socket_path {
+        serve_unix_socket(&socket_path, app).await;
+        return;
+    }
     let url = format!("{host}:{port}");
     let listener = tokio::net::TcpListener::bind(&url).await.unwrap();
     println!("Running at {url}");
     axum::serve(listener, app).await.unwrap();
 }
 
+#[cfg(unix)]
+async fn serve_unix_socket(socket_path: &Path, app: Router) {
+    use std::os::unix::fs::{FileTypeExt as _, PermissionsExt as _};
+
+    // A socket file left behind by a previous run would make binding fail, but anything else is left alone.
+    if let Ok(metadata) = std::fs::symlink_metadata(socket_path) {
+        assert!(
+            metadata.file_type().is_socket(),
+            "{} exists and isn't a socket",
+            socket_path.display()
+        );
+        std::fs::remove_file(socket_path).unwrap();
+    }
+    // Bind in a directory only we can access, so nobody can connect before the permissions are restricted,
+    // and move the socket into place after.
+    let parent = socket_path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    let private_dir = tempfile::Builder::new()
+        .prefix(".but-server-")
+        .permissions(std::fs::Permissions::from_mode(0o700))
+        .tempdir_in(parent)
+        .unwrap();
+    let private_socket_path = private_dir.path().join("socket");
+    let listener = tokio::net::UnixListener::bind(&private_socket_path).unwrap();
+    std::fs::set_permissions(&private_socket_path, std::fs::Permissions::from_mode(0o600)).unwrap();
+    std::fs::rename(&private_socket_path, socket_path).unwrap();
+    drop(private_dir);
+    println!("Running at {}", socket_path.display());
+    axum::serve(listener, app).await.unwrap();
+}
+
+#[cfg(not(unix))]
+async fn serve_unix_socket(_socket_path: &Path, _app: Router) {
+    panic!("BUTLER_SOCKET is only supported on Unix");
+}
+
 async fn handle_ws_request(
     ws: WebSocketUpgrade,
     broadcaster: Arc<Mutex<Broadcaster>>,
@@ -111,7 +199,7 @@ async fn handle_ws_request(
 }
 
 async fn handle_websocket(socket: WebSocket, broadcaster: Arc<Mutex<Broadcaster>>) {
-    let (send, mut recv) = tokio::sync::mpsc::unbounded_channel();
+    let (send, mut recv) = but_broadcaster::channel(but_broadcaster::DEFAULT_CAPACITY);
     let id = uuid::Uuid::new_v4();
     broadcaster.lock().await.register_sender(&id, send);
 
@@ -126,8 +214,16 @@ async fn handle_websocket(socket: WebSocket, broadcaster: Arc<Mutex<Broadcaster>
     });
 
     while let Some(Ok(msg)) = socket_recv.next().await {
-        #[expect(clippy::single_match)]
         match msg {
+            Message::Text(text) => match serde_json::from_str::<ClientMessage>(text.as_str()) {
+                Ok(ClientMessage::Subscribe(subscription)) => {
+                    broadcaster.lock().await.subscribe(&id, subscription);
+                }
+                Ok(ClientMessage::Unsubscribe(subscription)) => {
+                    broadcaster.lock().await.unsubscribe(&id, &subscription);
+                }
+                Err(err) => tracing::warn!("Ignoring invalid websocket message: {err}"),
+            },
             Message::Close(_) => {
                 thread.abort();
                 break;
@@ -141,57 +237,24 @@ async fn handle_websocket(socket: WebSocket, broadcaster: Arc<Mutex<Broadcaster>
 
 async fn handle_command(
     Json(request): Json<Request>,
+    access: Access,
     app: App,
     extra: Extra,
     app_settings_sync: AppSettingsWithDiskSync,
-) -> Json<serde_json::Value> {
+) -> axum::response::Response {
     let command: &str = &request.command;
+    if !access.allows(command) {
+        let err: but_api::error::Error =
+            anyhow::anyhow!("Command {command} is not allowed with this token").into();
+        return (
+            StatusCode::FORBIDDEN,
+            Json(json!(Response::Error(json!(err)))),
+        )
+            .into_response();
+    }
+    // Commands that need the state of the server are dispatched here, all others are found in the registry.
     let result = match command {
-        // General commands
-        "git_remote_branches" => git::git_remote_branches_cmd(request.params),
-        "git_test_push" => git::git_test_push_cmd(request.params),
-        "git_test_fetch" => git::git_test_fetch_cmd(request.params),
-        "git_index_size" => git::git_index_size_cmd(request.params),
-        "delete_all_data" => git::delete_all_data_cmd(request.params),
-        "git_set_global_config" => git::git_set_global_config_cmd(request.params),
-        "git_remove_global_config" => git::git_remove_global_config_cmd(request.params),
-        "git_get_global_config" => git::git_get_global_config_cmd(request.params),
-        // Diff commands
-        "tree_change_diffs" => diff::tree_change_diffs_cmd(request.params),
-        "commit_details" => diff::commit_details_cmd(request.params),
-        "changes_in_branch" => diff::changes_in_branch_cmd(request.params),
-        "changes_in_worktree" => diff::changes_in_worktree_cmd(request.params),
-        "assign_hunk" => diff::assign_hunk_cmd(request.params),
-        // Cherry apply commands
-        "cherry_apply_status" => cherry_apply::cherry_apply_status_cmd(request.params),
-        "cherry_apply" => cherry_apply::cherry_apply_cmd(request.params),
-        // Workspace commands
-        "stacks" => workspace::stacks_cmd(request.params),
-        "head_info" => workspace::head_info_cmd(request.params),
-        #[cfg(unix)]
-        "show_graph_svg" => workspace::show_graph_svg_cmd(request.params),
-        "stack_details" => workspace::stack_details_cmd(request.params),
-        "branch_details" => workspace::branch_details_cmd(request.params),
-        "create_commit_from_worktree_changes" => {
-            workspace::create_commit_from_worktree_changes_cmd(request.params)
-        }
-        "amend_commit_from_worktree_changes" => {
-            workspace::amend_commit_from_worktree_changes_cmd(request.params)
-        }
-        "discard_worktree_changes" => workspace::discard_worktree_changes_cmd(request.params),
-        "move_changes_between_commits" => {
-            workspace::move_changes_between_commits_cmd(request.params)
-        }
-        "split_branch" => workspace::split_branch_cmd(request.params),
-        "split_branch_into_dependent_branch" => {
-            workspace::split_branch_into_dependent_branch_cmd(request.params)
-        }
-        "uncommit_changes" => workspace::uncommit_changes_cmd(request.params),
-        "stash_into_branch" => workspace::stash_into_branch_cmd(request.params),
-        "canned_branch_name" => workspace::canned_branch_name_cmd(request.params),
-        "target_commits" => workspace::target_commits_cmd(request.params),
         // App settings
-        "get_app_settings" => settings::get_app_settings_cmd(request.params),
         "update_onboarding_complete" => {
             serde_json::from_value(request.params)
                 .to_error()
@@ -234,118 +297,22 @@ async fn handle_command(
             .and_then(|params| {
                 settings::update_reviews(&app_settings_sync, params).map(|r| json!(r))
             }),
-        // Secret management
-        "secret_get_global" => secret::secret_get_global_cmd(request.params),
-        "secret_set_global" => secret::secret_set_global_cmd(request.params),
-        // User management
-        "get_user" => users::get_user_cmd(request.params),
-        "set_user" => users::set_user_cmd(request.params),
-        "delete_user" => users::delete_user_cmd(request.params),
+        "update_forges" => serde_json::from_value(request.params)
+            .to_error()
+            .and_then(|params| {
+                settings::update_forges(&app_settings_sync, params).map(|r| json!(r))
+            }),
+        "update_oplog" => serde_json::from_value(request.params)
+            .to_error()
+            .and_then(|params| {
+                settings::update_oplog(&app_settings_sync, params).map(|r| json!(r))
+            }),
         // Project management
-        "update_project" => iprojects::update_project_cmd(request.params),
-        "add_project" => iprojects::add_project_cmd(request.params),
-        "get_project" => iprojects::get_project_cmd(request.params),
-        "delete_project" => iprojects::delete_project_cmd(request.params),
         "list_projects" => projects::list_projects(&extra).await,
         "set_project_active" => {
             projects::set_project_active(&app, &extra, app_settings_sync, request.params).await
         }
-        // Virtual branches commands
-        "normalize_branch_name" => virtual_branches::normalize_branch_name_cmd(request.params),
-        "create_virtual_branch" => virtual_branches::create_virtual_branch_cmd(request.params),
-        "delete_local_branch" => virtual_branches::delete_local_branch_cmd(request.params),
-        "create_virtual_branch_from_branch" => {
-            virtual_branches::create_virtual_branch_from_branch_cmd(request.params)
-        }
-        "integrate_upstream_commits" => {
-            virtual_branches::integrate_upstream_commits_cmd(request.params)
-        }
-        "get_initial_integration_steps_for_branch" => {
-            virtual_branches::get_initial_integration_steps_for_branch_cmd(request.params)
-        }
-        "integrate_branch_with_steps" => {
-            virtual_branches::integrate_branch_with_steps_cmd(request.params)
-        }
-        "get_base_branch_data" => virtual_branches::get_base_branch_data_cmd(request.params),
-        "set_base_branch" => virtual_branches::set_base_branch_cmd(request.params),
-        "push_base_branch" => virtual_branches::push_base_branch_cmd(request.params),
-        "update_stack_order" => virtual_branches::update_stack_order_cmd(request.params),
-        "unapply_stack" => virtual_branches::unapply_stack_cmd(request.params),
-        "can_apply_remote_branch" => virtual_branches::can_apply_remote_branch_cmd(request.params),
-        "list_commit_files" => virtual_branches::list_commit_files_cmd(request.params),
-        "amend_virtual_branch" => virtual_branches::amend_virtual_branch_cmd(request.params),
-        "undo_commit" => virtual_branches::undo_commit_cmd(request.params),
-        "insert_blank_commit" => virtual_branches::insert_blank_commit_cmd(request.params),
-        "reorder_stack" => virtual_branches::reorder_stack_cmd(request.params),
-        "find_git_branches" => virtual_branches::find_git_branches_cmd(request.params),
-        "list_branches" => virtual_branches::list_branches_cmd(request.params),
-        "get_branch_listing_details" => {
-            virtual_branches::get_branch_listing_details_cmd(request.params)
-        }
-        "squash_commits" => virtual_branches::squash_commits_cmd(request.params),
-        "fetch_from_remotes" => virtual_branches::fetch_from_remotes_cmd(request.params),
-        "move_commit" => virtual_branches::move_commit_cmd(request.params),
-        "move_branch" => virtual_branches::move_branch_cmd(request.params),
-        "update_commit_message" => virtual_branches::update_commit_message_cmd(request.params),
-        "find_commit" => virtual_branches::find_commit_cmd(request.params),
-        "upstream_integration_statuses" => {
-            virtual_branches::upstream_integration_statuses_cmd(request.params)
-        }
-        "integrate_upstream" => virtual_branches::integrate_upstream_cmd(request.params),
-        "resolve_upstream_integration" => {
-            virtual_branches::resolve_upstream_integration_cmd(request.params)
-        }
-        // Operating modes commands
-        "operating_mode" => modes::operating_mode_cmd(request.params),
-        "enter_edit_mode" => modes::enter_edit_mode_cmd(request.params),
-        "abort_edit_and_return_to_workspace" => {
-            modes::abort_edit_and_return_to_workspace_cmd(request.params)
-        }
-        "save_edit_and_return_to_workspace" => {
-            modes::save_edit_and_return_to_workspace_cmd(request.params)
-        }
-        "edit_initial_index_state" => modes::edit_initial_index_state_cmd(request.params),
-        "edit_changes_from_initial" => modes::edit_changes_from_initial_cmd(request.params),
-        // Repository commands
-        "git_get_local_config" => repo::git_get_local_config_cmd(request.params),
-        "git_set_local_config" => repo::git_set_local_config_cmd(request.params),
-        "check_signing_settings" => repo::check_signing_settings_cmd(request.params),
-        "git_clone_repository" => repo::git_clone_repository_cmd(request.params),
-        "get_uncommited_files" => repo::get_uncommited_files_cmd(request.params),
-        "get_commit_file" => repo::get_commit_file_cmd(request.params),
-        "get_workspace_file" => repo::get_workspace_file_cmd(request.params),
-        "pre_commit_hook" => repo::pre_commit_hook_cmd(request.params),
-        "pre_commit_hook_diffspecs" => repo::pre_commit_hook_diffspecs_cmd(request.params),
-        "post_commit_hook" => repo::post_commit_hook_cmd(request.params),
-        "message_hook" => repo::message_hook_cmd(request.params),
-        // Stack management commands
-        "create_branch" => stack::create_branch_cmd(request.params),
-        "remove_branch" => stack::remove_branch_cmd(request.params),
-        "update_branch_name" => stack::update_branch_name_cmd(request.params),
-        "update_branch_description" => stack::update_branch_description_cmd(request.params),
-        "update_branch_pr_number" => stack::update_branch_pr_number_cmd(request.params),
-        "push_stack" => stack::push_stack_cmd(request.params),
-        "push_stack_to_review" => stack::push_stack_to_review_cmd(request.params),
-        // Undo/Snapshot commands
-        "list_snapshots" => undo::list_snapshots_cmd(request.params),
-        "restore_snapshot" => undo::restore_snapshot_cmd(request.params),
-        "snapshot_diff" => undo::snapshot_diff_cmd(request.params),
         // "oplog_diff_worktrees" => undo::oplog_diff_worktrees(&ctx, request.params),
-        // Config management commands
-        "get_gb_config" => config::get_gb_config_cmd(request.params),
-        "set_gb_config" => config::set_gb_config_cmd(request.params),
-        "store_author_globally_if_unset" => {
-            config::store_author_globally_if_unset_cmd(request.params)
-        }
-        "get_author_info" => config::get_author_info_cmd(request.params),
-        // Remotes management commands
-        "list_remotes" => remotes::list_remotes_cmd(request.params),
-        "add_remote" => remotes::add_remote_cmd(request.params),
-        // Rules/Workspace rules commands
-        "create_workspace_rule" => rules::create_workspace_rule_cmd(request.params),
-        "delete_workspace_rule" => rules::delete_workspace_rule_cmd(request.params),
-        "update_workspace_rule" => rules::update_workspace_rule_cmd(request.params),
-        "list_workspace_rules" => rules::list_workspace_rules_cmd(request.params),
         "init_device_oauth" => {
             let result = github::init_device_oauth(NoParams {}).await;
             result.map(|r| json!(r))
@@ -360,14 +327,8 @@ async fn handle_command(
                 Err(e) => Err(e),
             }
         }
-        // Forge commands
-        "pr_templates" => forge::pr_templates_cmd(request.params),
-        "pr_template" => forge::pr_template_cmd(request.params),
         // // Menu commands (limited - no menu_item_set_enabled as it's Tauri-specific)
         // "get_editor_link_scheme" => menu::get_editor_link_scheme(&ctx, request.params),
-        // CLI commands
-        "install_cli" => cli::install_cli_cmd(request.params),
-        "cli_path" => cli::cli_path_cmd(request.params),
         // Askpass commands (async)
         "submit_prompt_response" => {
             let params = serde_json::from_value(request.params).to_error();
@@ -380,8 +341,6 @@ async fn handle_command(
             }
         }
         // Open/System commands (limited - no open_project_in_window as it's Tauri-specific)
-        "open_url" => open::open_url_cmd(request.params),
-        "show_in_finder" => open::show_in_finder_cmd(request.params),
 
         // TODO: Tauri-specific commands that cannot be ported to HTTP API:
         //
@@ -470,12 +429,6 @@ async fn handle_command(
                 Err(e) => Err(e),
             }
         }
-        "claude_list_permission_requests" => {
-            claude::claude_list_permission_requests_cmd(request.params)
-        }
-        "claude_update_permission_request" => {
-            claude::claude_update_permission_request_cmd(request.params)
-        }
         "claude_cancel_session" => {
             let params = serde_json::from_value(request.params).to_error();
             match params {
@@ -510,13 +463,6 @@ async fn handle_command(
                 Err(e) => Err(e),
             }
         }
-        "claude_get_prompt_templates" => claude::claude_get_prompt_templates_cmd(request.params),
-        "claude_write_prompt_templates" => {
-            claude::claude_write_prompt_templates_cmd(request.params)
-        }
-        "claude_get_prompt_templates_path" => {
-            claude::claude_get_prompt_templates_path_cmd(request.params)
-        }
         "claude_get_sub_agents" => {
             #[derive(Deserialize)]
             #[serde(rename_all = "camelCase")]
@@ -549,11 +495,15 @@ async fn handle_command(
             }
         }
 
-        _ => Err(anyhow::anyhow!("Command {} not found!", command).into()),
+        _ => match but_api::registry::find(command) {
+            Some(command) => (command.call)(request.params),
+            None => Err(anyhow::anyhow!("Command {} not found!", command).into()),
+        },
     };
 
     match result {
         Ok(value) => Json(json!(Response::Success(value))),
         Err(e) => Json(json!(Response::Error(json!(e)))),
     }
+    .into_response()
 }
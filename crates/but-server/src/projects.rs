@@ -1,10 +1,9 @@
 use crate::Extra;
 use anyhow::{Context as _, Result};
 use but_api::{App, error::ToError};
-use but_broadcaster::FrontendEvent;
 use but_settings::AppSettingsWithDiskSync;
 use gitbutler_project::{Project, ProjectId};
-use gitbutler_watcher::{Change, WatcherHandle};
+use gitbutler_watcher::WatcherHandle;
 use serde::Deserialize;
 use serde_json::json;
 use std::collections::HashMap;
@@ -38,37 +37,10 @@ impl ActiveProjects {
 
         let handler = gitbutler_watcher::Handler::new({
             let broadcaster = ctx.broadcaster.clone();
-
-            move |value| {
-                let frontend_event = match value {
-                    Change::GitFetch(project_id) => FrontendEvent {
-                        name: format!("project://{project_id}/git/fetch"),
-                        payload: serde_json::json!({}),
-                    },
-                    Change::GitHead {
-                        project_id,
-                        head,
-                        operating_mode,
-                    } => FrontendEvent {
-                        name: format!("project://{project_id}/git/head"),
-                        payload: serde_json::json!({ "head": head, "operatingMode": operating_mode }),
-                    },
-                    Change::GitActivity(project_id) => FrontendEvent {
-                        name: format!("project://{project_id}/git/activity"),
-                        payload: serde_json::json!({}),
-                    },
-                    Change::WorktreeChanges {
-                        project_id,
-                        changes,
-                    } => FrontendEvent {
-                        name: format!("project://{project_id}/worktree_changes"),
-                        payload: serde_json::json!(&changes),
-                    },
-                };
-
-                println!("Sending event");
-                broadcaster.blocking_lock().send(frontend_event);
-                println!("Sent event");
+            move |change| {
+                let event = crate::events::from_change(change);
+                tracing::trace!(event_name = event.name);
+                broadcaster.blocking_lock().send(event);
                 Ok(())
             }
         });
@@ -128,7 +100,7 @@ pub async fn set_project_active(
 
     // let is_exclusive = !active_projects.projects.contains(&params.id);
 
-    // TODO: Migrate DB, start watcher
+    // TODO: Migrate DB
 
     Ok(json!(ProjectInfo {
         is_exclusive: true,
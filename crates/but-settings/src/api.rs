@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use crate::AppSettingsWithDiskSync;
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
@@ -42,6 +44,23 @@ pub struct ReviewsUpdate {
     pub auto_fill_pr_description_from_commit: Option<bool>,
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+/// Update request for [`crate::app_settings::Forges`].
+pub struct ForgesUpdate {
+    pub hosts: Option<BTreeMap<String, String>>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+/// Update request for [`crate::app_settings::Oplog`].
+pub struct OplogUpdate {
+    pub auto_prune: Option<bool>,
+    pub keep_count: Option<usize>,
+    pub keep_days: Option<u64>,
+    pub keep_operations: Option<Vec<String>>,
+}
+
 #[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 /// Update request for [`crate::app_settings::Fetch`].
@@ -144,6 +163,31 @@ impl AppSettingsWithDiskSync {
         settings.save()
     }
 
+    pub fn update_forges(&self, update: ForgesUpdate) -> Result<()> {
+        let mut settings = self.get_mut_enforce_save()?;
+        if let Some(hosts) = update.hosts {
+            settings.forges.hosts = hosts;
+        }
+        settings.save()
+    }
+
+    pub fn update_oplog(&self, update: OplogUpdate) -> Result<()> {
+        let mut settings = self.get_mut_enforce_save()?;
+        if let Some(auto_prune) = update.auto_prune {
+            settings.oplog.auto_prune = auto_prune;
+        }
+        if let Some(keep_count) = update.keep_count {
+            settings.oplog.keep_count = keep_count;
+        }
+        if let Some(keep_days) = update.keep_days {
+            settings.oplog.keep_days = keep_days;
+        }
+        if let Some(keep_operations) = update.keep_operations {
+            settings.oplog.keep_operations = keep_operations;
+        }
+        settings.save()
+    }
+
     pub fn update_fetch(&self, update: FetchUpdate) -> Result<()> {
         let mut settings = self.get_mut_enforce_save()?;
         if let Some(auto_fetch_interval_minutes) = update.auto_fetch_interval_minutes {
@@ -1,6 +1,9 @@
+use std::collections::BTreeMap;
+
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct TelemetrySettings {
     /// Whether the anonymous metrics are enabled.
@@ -13,14 +16,14 @@ pub struct TelemetrySettings {
     pub app_distinct_id: Option<String>,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct GitHubOAuthAppSettings {
     /// Client ID for the GitHub OAuth application. Set this to use custom (non-GitButler) OAuth application.
     pub oauth_client_id: String,
 }
 
-#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct FeatureFlags {
     /// Enable the usage of V3 workspace APIs.
@@ -54,21 +57,21 @@ pub struct FeatureFlags {
     pub single_branch: bool,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ExtraCsp {
     /// Additional hosts that the application can connect to.
     pub hosts: Vec<String>,
 }
 
-#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Fetch {
     /// The frequency at which the app will automatically fetch. A negative value (e.g. -1) disables auto fetching.
     pub auto_fetch_interval_minutes: isize,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Claude {
     /// Path to the Claude Code executable. Defaults to "claude" if not set.
@@ -85,9 +88,30 @@ pub struct Claude {
     pub use_configured_model: bool,
 }
 
-#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Reviews {
     /// Whether to auto-fill PR title and description from the first commit when a branch has only one commit.
     pub auto_fill_pr_description_from_commit: bool,
 }
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Forges {
+    /// Maps the hosts of self-hosted forges, like `git.corp.example.com`, to the forge they run.
+    /// Valid forges are `github`, `gitlab`, `bitbucket` and `azure`.
+    pub hosts: BTreeMap<String, String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Oplog {
+    /// Whether snapshots that aren't retained are pruned automatically in the background, at most once a day.
+    pub auto_prune: bool,
+    /// The amount of most recent snapshots to always keep.
+    pub keep_count: usize,
+    /// Snapshots created within this amount of days are always kept.
+    pub keep_days: u64,
+    /// The names of operations whose snapshots are always kept, like `RestoreFromSnapshot`.
+    pub keep_operations: Vec<String>,
+}
@@ -1,7 +1,8 @@
 #![allow(deprecated)]
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct AppSettings {
     /// The amount of context lines to show in unified diffs, above and below the hunk.
@@ -22,6 +23,10 @@ pub struct AppSettings {
     pub claude: app_settings::Claude,
     /// Settings related to code reviews and pull requests.
     pub reviews: app_settings::Reviews,
+    /// Settings related to detecting the forge a repository is hosted on.
+    pub forges: app_settings::Forges,
+    /// Settings related to the retention of snapshots in the operations log.
+    pub oplog: app_settings::Oplog,
 }
 
 impl Default for AppSettings {
@@ -1463,12 +1463,12 @@ pub struct SplitCommitParameters {
     #[schemars(description = "
     <description>
         The definitions for each new commit shard.
-        Each shard specifies the commit message and the list of files to include in that shard.
+        Each shard specifies the commit message and the files and hunks to include in that shard.
     </description>
 
     <important_notes>
-        Each shard must have a unique set of files (no overlap).
-        All files in the source commit must be assigned to a shard.
+        Each shard must have a unique set of files and hunks (no overlap).
+        Files and hunks of the source commit that aren't assigned to a shard are kept in a remainder commit with the original commit message.
         The order of the shards determines the order of the resulting commits (first being the newest or 'child-most' commit and las being the oldest or 'parent-most').
     </important_notes>
     ")]
@@ -1483,12 +1483,13 @@ impl Tool for SplitCommit {
     fn description(&self) -> String {
         "
         <description>
-            Split a single commit into multiple new commits, each with its own message and file set.
+            Split a single commit into multiple new commits, each with its own message and set of files and hunks.
         </description>
 
         <important_notes>
             This tool allows you to break up a commit into several smaller commits, each defined by a shard.
-            Each shard must have a unique set of files, and all files in the source commit must be assigned to a shard.
+            Each shard must have a unique set of files and hunks.
+            Changes of the source commit that aren't assigned to a shard are kept in a remainder commit with the original commit message.
             The order of the shards determines the order of the resulting commits.
         </important_notes>
         ".to_string()
@@ -1527,7 +1528,7 @@ pub fn split_commit(
         .shards
         .into_iter()
         .map(Into::into)
-        .collect::<Vec<but_workspace::CommitFiles>>();
+        .collect::<Vec<but_workspace::CommitChanges>>();
 
     let outcome = but_workspace::split_commit(
         ctx,
@@ -1538,9 +1539,11 @@ pub fn split_commit(
     )?;
 
     let CommmitSplitOutcome {
-        new_commits,
+        mut new_commits,
+        remainder,
         move_changes_result,
     } = outcome;
+    new_commits.extend(remainder);
 
     // Emit a stack update for the frontend.
     let project_id = ctx.project().id;
@@ -1604,12 +1607,44 @@ pub struct CommitShard {
     <important_notes>
         The file paths should be files that exist in the the source commit.
         The file paths are unique to this commit shard, there can't be duplicates.
+        Use `hunks` instead to only include some of the changes of a file.
     </important_notes>
     ")]
     pub files: Vec<String>,
+    /// The list of individual hunks to be included in the commit.
+    #[schemars(description = "
+    <description>
+        The list of individual hunks to be included in the commit, for files whose changes should be split across shards.
+        Each entry identifies a hunk of the source commit's diff by its file path and hunk header.
+    </description>
+
+    <important_notes>
+        The hunks should be hunks of the diff of the source commit, exactly as shown in its hunk headers.
+        A file listed in `files` includes all of its hunks and shouldn't be listed here.
+        Each hunk is unique to this commit shard, there can't be duplicates.
+    </important_notes>
+    ")]
+    #[serde(default)]
+    pub hunks: Vec<ShardHunk>,
 }
 
-impl From<CommitShard> for but_workspace::CommitFiles {
+/// A hunk of the diff of a commit to be split.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ShardHunk {
+    /// The relative path to the file the hunk belongs to.
+    pub path: String,
+    /// The 1-based line number the hunk starts at in the old version of the file, the `a` in `@@ -a,b +c,d @@`.
+    pub old_start: u32,
+    /// The amount of lines of the hunk in the old version of the file, the `b` in `@@ -a,b +c,d @@`.
+    pub old_lines: u32,
+    /// The 1-based line number the hunk starts at in the new version of the file, the `c` in `@@ -a,b +c,d @@`.
+    pub new_start: u32,
+    /// The amount of lines of the hunk in the new version of the file, the `d` in `@@ -a,b +c,d @@`.
+    pub new_lines: u32,
+}
+
+impl From<CommitShard> for but_workspace::CommitChanges {
     fn from(value: CommitShard) -> Self {
         let message = format!(
             "{}\n\n{}",
@@ -1617,10 +1652,28 @@ impl From<CommitShard> for but_workspace::CommitFiles {
             value.message_body.trim()
         );
 
-        but_workspace::CommitFiles {
-            message,
-            files: value.files,
+        let mut piece = but_workspace::CommitChanges::from_files(message, value.files);
+        for hunk in value.hunks {
+            let header = but_workspace::HunkHeader {
+                old_start: hunk.old_start,
+                old_lines: hunk.old_lines,
+                new_start: hunk.new_start,
+                new_lines: hunk.new_lines,
+            };
+            match piece
+                .changes
+                .iter_mut()
+                .find(|spec| spec.path == hunk.path && !spec.hunk_headers.is_empty())
+            {
+                Some(spec) => spec.hunk_headers.push(header),
+                None => piece.changes.push(but_workspace::DiffSpec {
+                    previous_path: None,
+                    path: hunk.path.into(),
+                    hunk_headers: vec![header],
+                }),
+            }
         }
+        piece
     }
 }
 
@@ -2,7 +2,7 @@ use anyhow::Context;
 use std::borrow::Cow;
 
 /// For use in [`Anchor`].
-#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
 pub enum Position {
     /// The new dependent branch will appear above its anchor.
     Above,
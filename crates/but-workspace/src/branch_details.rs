@@ -289,6 +289,7 @@ fn upstream_commits(
                 message: commit.message().unwrap_or_default().into(),
                 created_at: i128::from(commit.time().seconds()) * 1000,
                 author,
+                signature: None,
             }
         })
         .collect())
@@ -391,6 +392,7 @@ fn local_commits_gix(
             state: CommitState::LocalAndRemote(info.id),
             created_at: i128::from(commit.committer.time.seconds) * 1000,
             author,
+            signature: None,
         });
     }
     Ok(out)
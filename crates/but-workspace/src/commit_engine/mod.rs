@@ -135,7 +135,7 @@ pub struct CreateCommitOutcome {
 }
 
 /// Provide a description of why a [`DiffSpec`] was rejected for application to the tree of a commit.
-#[derive(Default, Debug, Copy, Clone, PartialEq, serde::Serialize)]
+#[derive(Default, Debug, Copy, Clone, PartialEq, serde::Serialize, schemars::JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub enum RejectionReason {
     /// All changes were applied, but they didn't end up effectively change the tree to something differing from the target tree.
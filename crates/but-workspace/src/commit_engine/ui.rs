@@ -1,17 +1,19 @@
 #![allow(missing_docs)]
 use crate::commit_engine::RejectionReason;
 use gitbutler_serde::BStringForFrontend;
+use schemars::JsonSchema;
 use serde::Serialize;
 
 /// The JSON serializable type of [super::CreateCommitOutcome].
 // TODO(ST): this type should contain mappings from old to new commits so that the UI knows what state to update, maybe.
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateCommitOutcome {
     /// Paths that contained at least one rejected hunk, for instance, a change that didn't apply, along with the reason for the rejection.
     pub paths_to_rejected_changes: Vec<(RejectionReason, BStringForFrontend)>,
     /// The newly created commit, if there was one. It maybe that a couple of paths were rejected, but the commit was created anyway.
     #[serde(with = "gitbutler_serde::object_id_opt")]
+    #[schemars(with = "Option<String>")]
     pub new_commit: Option<gix::ObjectId>,
 }
 
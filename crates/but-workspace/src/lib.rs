@@ -30,6 +30,7 @@ use but_core::TreeChange;
 use gitbutler_command_context::CommandContext;
 use gitbutler_oxidize::OidExt;
 use gitbutler_stack::VirtualBranchesHandle;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 mod integrated;
@@ -46,7 +47,7 @@ pub use tree_manipulation::{
     move_between_commits::move_changes_between_commits,
     remove_changes_from_commit_in_stack::remove_changes_from_commit_in_stack,
     split_branch::{split_branch, split_into_dependent_branch},
-    split_commit::{CommitFiles, CommmitSplitOutcome, split_commit},
+    split_commit::{CommitChanges, CommmitSplitOutcome, split_commit},
 };
 pub mod head;
 pub use head::{
@@ -78,7 +79,7 @@ mod stacks;
 // TODO: _v3 versions are specifically for the UI, so import them into `ui` instead.
 pub use stacks::{
     local_and_remote_commits, stack_branches, stack_details, stack_details_v3, stack_heads_info,
-    stacks, stacks_v3,
+    stacks, stacks_v3, verify_signatures,
 };
 
 mod branch_details;
@@ -86,16 +87,18 @@ pub use branch_details::{branch_details, branch_details_v3};
 use but_graph::SegmentIndex;
 
 /// A change that should be used to create a new commit or alter an existing one, along with enough information to know where to find it.
-#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct DiffSpec {
     /// The previous location of the entry, the source of a rename if there was one.
     #[serde(rename = "previousPathBytes")]
+    #[schemars(with = "Option<Vec<u8>>")]
     pub previous_path: Option<BString>,
     /// The worktree-relative path to the worktree file with the content to commit.
     ///
     /// If `hunks` is empty, this means the current content of the file should be committed.
     #[serde(rename = "pathBytes")]
+    #[schemars(with = "Vec<u8>")]
     pub path: BString,
     /// If one or more hunks are specified, match them with actual changes currently in the worktree.
     /// Failure to match them will lead to the change being dropped.
@@ -125,7 +128,7 @@ impl From<TreeChange> for DiffSpec {
 }
 
 /// The header of a hunk that represents a change to a file.
-#[derive(Clone, Copy, PartialEq, Eq, Ord, PartialOrd, Serialize, Deserialize)]
+#[derive(Clone, Copy, PartialEq, Eq, Ord, PartialOrd, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct HunkHeader {
     /// The 1-based line number at which the previous version of the file started.
@@ -244,7 +247,7 @@ pub struct WorkspaceCommit<'repo> {
 pub use gitbutler_stack::StackId;
 
 /// A filter for the list of stacks.
-#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, JsonSchema)]
 pub enum StacksFilter {
     /// Show all stacks
     All,
@@ -523,6 +523,25 @@ pub fn stack_details_v3(
     })
 }
 
+/// Check the signatures of all local commits in `branch_details`, so the UI can show which are signed and which
+/// lost their signature, or have an invalid one, after being rewritten.
+///
+/// This isn't done by [`stack_details()`] as it runs `gpg` or `ssh-keygen` for each signed commit,
+/// but results are cached by commit id.
+pub fn verify_signatures(repo: &gix::Repository, branch_details: &mut [ui::BranchDetails]) {
+    for commit in branch_details
+        .iter_mut()
+        .flat_map(|branch| branch.commits.iter_mut())
+    {
+        let commit_id = commit.id;
+        commit.signature = but_rebase::commit::verify_signature_cached(repo, commit_id)
+            .inspect_err(|err| {
+                tracing::warn!("Failed to check the signature of commit {commit_id}: {err:#}")
+            })
+            .ok();
+    }
+}
+
 impl ui::BranchDetails {
     fn from_segment(
         Segment {
@@ -653,6 +672,7 @@ impl From<&LocalCommit> for ui::Commit {
             author: author
                 .to_ref(&mut gix::date::parse::TimeBuf::default())
                 .into(),
+            signature: None,
         }
     }
 }
@@ -845,6 +865,7 @@ pub fn local_and_remote_commits(
             state,
             created_at,
             author: commit.author().into(),
+            signature: None,
         };
         local_and_remote.push(api_commit);
     }
@@ -6,7 +6,7 @@ use gitbutler_stack::{StackId, VirtualBranchesHandle};
 use gix::ObjectId;
 
 use crate::{
-    DiffSpec,
+    DiffSpec, HunkHeader,
     stack_ext::StackExt,
     tree_manipulation::utils::{
         ChangesSource, create_tree_without_diff, rebase_mapping_with_overrides,
@@ -74,10 +74,18 @@ pub fn remove_changes_from_commit(
     changes: impl IntoIterator<Item = DiffSpec>,
     context_lines: u32,
 ) -> Result<ObjectId> {
-    let repository = ctx.gix_repo()?;
+    remove_changes(&ctx.gix_repo()?, source_commit_id, changes, context_lines)
+}
 
+/// Like [`remove_changes_from_commit()`], but writes the new commit to `repository`.
+fn remove_changes(
+    repository: &gix::Repository,
+    source_commit_id: gix::ObjectId,
+    changes: impl IntoIterator<Item = DiffSpec>,
+    context_lines: u32,
+) -> Result<ObjectId> {
     let (source_tree_without_changes, rejected_specs) = create_tree_without_diff(
-        &repository,
+        repository,
         ChangesSource::Commit {
             id: source_commit_id,
         },
@@ -90,7 +98,7 @@ pub fn remove_changes_from_commit(
     }
 
     let rewritten_source_commit =
-        replace_commit_tree(&repository, source_commit_id, source_tree_without_changes)?;
+        replace_commit_tree(repository, source_commit_id, source_tree_without_changes)?;
     Ok(rewritten_source_commit)
 }
 
@@ -125,6 +133,132 @@ pub fn keep_only_file_changes_in_commit(
     remove_changes_from_commit(ctx, source_commit_id, diff_specs, context_lines).map(Some)
 }
 
+/// Keeps only the specified `changes_to_keep` in a commit, removing all others.
+///
+/// Each of `changes_to_keep` either keeps the whole file if it has no hunk headers, or only the listed
+/// hunks of the commit's diff with `context_lines` of context.
+/// Returns `None` if nothing of the commit would be kept.
+pub fn keep_only_changes_in_commit(
+    ctx: &CommandContext,
+    source_commit_id: gix::ObjectId,
+    changes_to_keep: &[DiffSpec],
+    context_lines: u32,
+) -> Result<Option<gix::ObjectId>> {
+    keep_only_changes(
+        &ctx.gix_repo()?,
+        source_commit_id,
+        changes_to_keep,
+        context_lines,
+    )
+}
+
+/// Like [`keep_only_changes_in_commit()`], but writes the new commit to `repository`.
+pub(crate) fn keep_only_changes(
+    repository: &gix::Repository,
+    source_commit_id: gix::ObjectId,
+    changes_to_keep: &[DiffSpec],
+    context_lines: u32,
+) -> Result<Option<gix::ObjectId>> {
+    let changes_to_remove =
+        unclaimed_changes_in_commit(repository, source_commit_id, changes_to_keep, context_lines)?;
+    let commit_changes =
+        but_core::diff::ui::commit_changes_by_worktree_dir(repository, source_commit_id)?;
+    let removes_everything = changes_to_remove.len() == commit_changes.changes.len()
+        && changes_to_remove
+            .iter()
+            .all(|change| change.hunk_headers.is_empty());
+    if removes_everything {
+        return Ok(None);
+    }
+    remove_changes(
+        repository,
+        source_commit_id,
+        changes_to_remove,
+        context_lines,
+    )
+    .map(Some)
+}
+
+/// Return the changes of the commit with `source_commit_id` that aren't part of `claimed`.
+///
+/// Files that aren't claimed at all are returned as a whole, files of which only some hunks are claimed
+/// are returned with the remaining hunks, and files that are fully claimed are omitted.
+/// Claimed hunks must match the hunks of the commit's diff with `context_lines` of context exactly.
+pub(crate) fn unclaimed_changes_in_commit(
+    repository: &gix::Repository,
+    source_commit_id: gix::ObjectId,
+    claimed: &[DiffSpec],
+    context_lines: u32,
+) -> Result<Vec<DiffSpec>> {
+    let commit_changes =
+        but_core::diff::ui::commit_changes_by_worktree_dir(repository, source_commit_id)?;
+    let commit_changes: Vec<TreeChange> =
+        commit_changes.changes.into_iter().map(Into::into).collect();
+
+    if let Some(unknown) = claimed
+        .iter()
+        .find(|spec| !commit_changes.iter().any(|change| change.path == spec.path))
+    {
+        bail!(
+            "'{}' isn't changed in commit {source_commit_id}",
+            unknown.path
+        );
+    }
+
+    let mut unclaimed = Vec::new();
+    for change in &commit_changes {
+        let claims: Vec<&DiffSpec> = claimed
+            .iter()
+            .filter(|spec| spec.path == change.path)
+            .collect();
+        if claims.is_empty() {
+            unclaimed.push(DiffSpec::from(change));
+            continue;
+        }
+        if claims.iter().any(|spec| spec.hunk_headers.is_empty()) {
+            continue;
+        }
+
+        let Some(but_core::UnifiedDiff::Patch { hunks, .. }) =
+            change.unified_diff(repository, context_lines)?
+        else {
+            bail!(
+                "Cannot select hunks of '{}' as it has no textual diff - use the whole file instead",
+                change.path
+            );
+        };
+        let commit_hunks: Vec<HunkHeader> = hunks.into_iter().map(Into::into).collect();
+        let claimed_hunks: Vec<HunkHeader> = claims
+            .iter()
+            .flat_map(|spec| spec.hunk_headers.iter().copied())
+            .collect();
+        if let Some(unknown) = claimed_hunks
+            .iter()
+            .find(|hunk| !commit_hunks.contains(hunk))
+        {
+            bail!(
+                "Hunk -{},{} +{},{} isn't a hunk of '{}' in commit {source_commit_id}",
+                unknown.old_start,
+                unknown.old_lines,
+                unknown.new_start,
+                unknown.new_lines,
+                change.path
+            );
+        }
+        let remaining_hunks: Vec<HunkHeader> = commit_hunks
+            .into_iter()
+            .filter(|hunk| !claimed_hunks.contains(hunk))
+            .collect();
+        if !remaining_hunks.is_empty() {
+            unclaimed.push(DiffSpec {
+                hunk_headers: remaining_hunks,
+                ..DiffSpec::from(change)
+            });
+        }
+    }
+    Ok(unclaimed)
+}
+
 pub fn remove_file_changes_from_commit(
     ctx: &CommandContext,
     source_commit_id: gix::ObjectId,
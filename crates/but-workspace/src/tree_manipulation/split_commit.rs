@@ -1,39 +1,47 @@
-use anyhow::Result;
+use anyhow::{Result, bail};
 use but_rebase::Rebase;
 use gitbutler_command_context::CommandContext;
 use gitbutler_stack::{StackId, VirtualBranchesHandle};
+use schemars::JsonSchema;
+use serde::Deserialize;
 
 use crate::{
-    MoveChangesResult,
+    DiffSpec, MoveChangesResult,
     stack_ext::StackExt,
     tree_manipulation::{
-        remove_changes_from_commit_in_stack::keep_only_file_changes_in_commit,
+        remove_changes_from_commit_in_stack::{keep_only_changes, unclaimed_changes_in_commit},
         utils::replace_pick_with_multiple_commits,
     },
 };
 
-/// Splits a commit into multiple commits based on the specified file changes.
+/// Splits a commit into multiple commits based on the specified changes.
 ///
 /// This function creates new commits for each specified piece of the original commit.
-/// The new commits will contain only the specified files, effectively splitting the original commit.
+/// The new commits will contain only the specified files or hunks, effectively splitting the original commit.
+/// Everything that no piece claims goes into a remainder commit which keeps the original message.
 /// In steps:
-/// 1. Create new commits for each specified piece of the original commit.
-/// 2. Replace the original commit in the stack with the new commits.
+/// 1. Create new commits for each specified piece of the original commit, and one for the remainder.
+/// 2. Replace the original commit in the stack with the new commits, with the remainder picked last.
 /// 3. Update the stack to reflect the new commits.
+///
+/// Hunks are matched against the commit's diff with `context_lines` of context, and each file or hunk
+/// may only be claimed by a single piece.
 pub fn split_commit(
     ctx: &mut CommandContext,
     stack_id: StackId,
     source_commit_id: gix::ObjectId,
-    pieces: &[CommitFiles],
+    pieces: &[CommitChanges],
     context_lines: u32,
 ) -> Result<CommmitSplitOutcome> {
     let repository = ctx.gix_repo()?;
     let vb_state = VirtualBranchesHandle::new(ctx.project().gb_dir());
 
     let source_stack = vb_state.get_stack_in_workspace(stack_id)?;
+    ensure_disjoint(pieces)?;
 
     let mut steps = source_stack.as_rebase_steps(ctx, &repository)?;
-    let commit_pieces = new_commits(ctx, source_commit_id, pieces, context_lines)?;
+    let (commit_pieces, remainder) =
+        commits_for_pieces(&repository, source_commit_id, pieces, context_lines)?;
     replace_pick_with_multiple_commits(&mut steps, source_commit_id, &commit_pieces)?;
     let base = source_stack.merge_base(ctx)?;
     let mut rebase = Rebase::new(&repository, base, None)?;
@@ -54,43 +62,111 @@ pub fn split_commit(
     let mut source_stack = source_stack;
     source_stack.set_heads_from_rebase_output(ctx, result.references)?;
 
-    let new_commits = commit_pieces.iter().map(|(id, _)| *id).collect::<Vec<_>>();
+    let new_commits = commit_pieces
+        .iter()
+        .filter(|(id, _)| Some(*id) != remainder)
+        .map(|(id, _)| *id)
+        .collect::<Vec<_>>();
 
     Ok(CommmitSplitOutcome {
         new_commits,
+        remainder,
         move_changes_result: MoveChangesResult {
             replaced_commits: commit_mapping,
         },
     })
 }
 
-fn new_commits(
-    ctx: &CommandContext,
+/// Create a commit with the changes of each of `pieces` of the commit with `source_commit_id`, along with its message,
+/// and one with all changes that no piece claims, which is also returned on its own and keeps the original message.
+fn commits_for_pieces(
+    repository: &gix::Repository,
     source_commit_id: gix::ObjectId,
-    pieces: &[CommitFiles],
+    pieces: &[CommitChanges],
     context_lines: u32,
-) -> Result<Vec<(gix::ObjectId, Option<String>)>> {
-    let mut new_commits = Vec::new();
+) -> Result<(Vec<(gix::ObjectId, Option<String>)>, Option<gix::ObjectId>)> {
+    let mut commits = Vec::new();
     for piece in pieces {
-        if let Some(rewritten_commit) = keep_only_file_changes_in_commit(
-            ctx,
-            source_commit_id,
-            &piece.files,
-            context_lines,
-            false,
-        )? {
-            new_commits.push((rewritten_commit, Some(piece.message.clone())));
+        if let Some(rewritten_commit) =
+            keep_only_changes(repository, source_commit_id, &piece.changes, context_lines)?
+        {
+            commits.push((rewritten_commit, Some(piece.message.clone())));
+        }
+    }
+
+    let claimed = pieces
+        .iter()
+        .flat_map(|piece| piece.changes.iter().cloned())
+        .collect::<Vec<_>>();
+    let unclaimed =
+        unclaimed_changes_in_commit(repository, source_commit_id, &claimed, context_lines)?;
+    let remainder = if unclaimed.is_empty() {
+        None
+    } else {
+        keep_only_changes(repository, source_commit_id, &unclaimed, context_lines)?
+    };
+    if let Some(remainder) = remainder {
+        commits.push((remainder, None));
+    }
+    Ok((commits, remainder))
+}
+
+/// Fail if a file or hunk is claimed by more than one of `pieces`.
+fn ensure_disjoint(pieces: &[CommitChanges]) -> Result<()> {
+    let specs = pieces
+        .iter()
+        .enumerate()
+        .flat_map(|(idx, piece)| piece.changes.iter().map(move |spec| (idx, spec)))
+        .collect::<Vec<_>>();
+    for (pos, (idx, spec)) in specs.iter().enumerate() {
+        for (other_idx, other) in &specs[pos + 1..] {
+            if other.path != spec.path {
+                continue;
+            }
+            let overlaps = spec.hunk_headers.is_empty()
+                || other.hunk_headers.is_empty()
+                || spec
+                    .hunk_headers
+                    .iter()
+                    .any(|hunk| other.hunk_headers.contains(hunk));
+            if overlaps && idx != other_idx {
+                bail!(
+                    "'{}' is claimed by more than one piece of the split",
+                    spec.path
+                );
+            }
         }
     }
-    Ok(new_commits)
+    Ok(())
 }
 
-/// Represents the files to be included in a new commit when splitting an existing commit.
-pub struct CommitFiles {
+/// Represents the changes to be included in a new commit when splitting an existing commit.
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CommitChanges {
     /// The message for the new commit.
     pub message: String,
-    /// A subset of the files in the commit that should be included in the new commit.
-    pub files: Vec<String>,
+    /// A subset of the changes in the commit that should be included in the new commit.
+    ///
+    /// A change without hunk headers includes the whole file, otherwise only the listed hunks of the file are included.
+    pub changes: Vec<DiffSpec>,
+}
+
+impl CommitChanges {
+    /// Create a piece that includes the whole of each of `files`.
+    pub fn from_files(message: String, files: impl IntoIterator<Item = String>) -> Self {
+        CommitChanges {
+            message,
+            changes: files
+                .into_iter()
+                .map(|path| DiffSpec {
+                    previous_path: None,
+                    path: path.into(),
+                    hunk_headers: vec![],
+                })
+                .collect(),
+        }
+    }
 }
 
 /// Represents the outcome of splitting a commit, including the newly created commits
@@ -98,10 +174,13 @@ pub struct CommitFiles {
 ///
 /// # Fields
 /// - `new_commits`: A vector containing the object IDs of the new commits that were created as a result of the split.
+/// - `remainder`: The commit with all changes that no piece claimed, if there were any.
 /// - `move_changes_result`: The result of the operation that moved changes between commits during the split process.
 pub struct CommmitSplitOutcome {
     /// The new commits created
     pub new_commits: Vec<gix::ObjectId>,
+    /// The commit with the unclaimed changes, which keeps the message of the original commit
+    pub remainder: Option<gix::ObjectId>,
     /// The moved changes outcome
     pub move_changes_result: MoveChangesResult,
 }
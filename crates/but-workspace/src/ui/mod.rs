@@ -1,6 +1,7 @@
 use anyhow::Context;
 use bstr::{BStr, BString, ByteSlice};
 use gix::refs::transaction::{Change, PreviousValue, RefEdit, RefLog};
+use schemars::JsonSchema;
 use serde::Serialize;
 
 /// Utilities for diffing, with workspace integration.
@@ -16,7 +17,7 @@ mod author {
     use serde::Serialize;
 
     /// Represents the author of a commit.
-    #[derive(Serialize, Hash, Clone, PartialEq, Eq)]
+    #[derive(Serialize, Hash, Clone, PartialEq, Eq, schemars::JsonSchema)]
     #[serde(rename_all = "camelCase")]
     pub struct Author {
         /// The name from the git commit signature
@@ -70,14 +71,16 @@ pub use author::Author;
 use gitbutler_stack::{Stack, StackId};
 
 /// The information about the branch inside a stack
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct StackHeadInfo {
     /// The name of the branch.
     #[serde(with = "gitbutler_serde::bstring_lossy")]
+    #[schemars(with = "String")]
     pub name: BString,
     /// The tip of the branch.
     #[serde(with = "gitbutler_serde::object_id")]
+    #[schemars(with = "String")]
     pub tip: gix::ObjectId,
     /// If `true`, then this head is checked directly so `HEAD` points to it, and this is only ever `true` for a single head.
     /// This is `false` if the worktree is checked out.
@@ -107,7 +110,7 @@ impl StackHeadInfo {
 
 /// Represents a lightweight version of a [`Stack`] for listing.
 /// NOTE: this is a UI type mostly because it's still modeled after the legacy stack with StackId, something that doesn't exist anymore.
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct StackEntry {
     /// The ID of the stack.
@@ -118,6 +121,7 @@ pub struct StackEntry {
     pub heads: Vec<StackHeadInfo>,
     /// The tip of the top-most branch, i.e., the most recent commit that would become the parent of new commits of the topmost stack branch.
     #[serde(with = "gitbutler_serde::object_id")]
+    #[schemars(with = "String")]
     pub tip: gix::ObjectId,
     /// The zero-based index for sorting stacks.
     pub order: Option<usize>,
@@ -127,7 +131,7 @@ pub struct StackEntry {
 
 /// **Temporary type to help transitioning to the optional version of stack-entry** and ultimately, to [`crate::RefInfo`].
 /// WARNING: for use by parts in the code that can rely on having a non-optional `stack_id`. The goal is to have none of these.
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct StackEntryNoOpt {
     /// The ID of the stack.
@@ -138,6 +142,7 @@ pub struct StackEntryNoOpt {
     pub heads: Vec<StackHeadInfo>,
     /// The tip of the top-most branch, i.e., the most recent commit that would become the parent of new commits of the topmost stack branch.
     #[serde(with = "gitbutler_serde::object_id")]
+    #[schemars(with = "String")]
     pub tip: gix::ObjectId,
     /// The zero-based index for sorting stacks.
     pub order: Option<usize>,
@@ -227,7 +232,7 @@ impl StackEntryNoOpt {
 }
 
 /// Represents the state a commit could be in.
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, JsonSchema)]
 #[serde(tag = "type", content = "subject")]
 pub enum CommitState {
     /// The commit is only local
@@ -240,6 +245,7 @@ pub enum CommitState {
     /// This variant carries the remote commit id.
     /// The `remote_commit_id` may be the same as the `id` or it may be different if the local commit has been rebased or updated in another way.
     #[serde(with = "gitbutler_serde::object_id")]
+    #[schemars(with = "String")]
     LocalAndRemote(gix::ObjectId),
     /// The commit is considered integrated.
     /// This should happen when this commit or the contents of this commit is already part of the base.
@@ -263,17 +269,20 @@ impl CommitState {
 }
 
 /// Commit that is a part of a [`StackBranch`](gitbutler_stack::StackBranch) and, as such, containing state derived in relation to the specific branch.
-#[derive(Clone, Serialize)]
+#[derive(Clone, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Commit {
     /// The OID of the commit.
     #[serde(with = "gitbutler_serde::object_id")]
+    #[schemars(with = "String")]
     pub id: gix::ObjectId,
     /// The parent OIDs of the commit.
     #[serde(with = "gitbutler_serde::object_id_vec")]
+    #[schemars(with = "Vec<String>")]
     pub parent_ids: Vec<gix::ObjectId>,
     /// The message of the commit.
     #[serde(with = "gitbutler_serde::bstring_lossy")]
+    #[schemars(with = "String")]
     pub message: BString,
     /// Whether the commit is in a conflicted state.
     /// The Conflicted state of a commit is a GitButler concept.
@@ -288,6 +297,9 @@ pub struct Commit {
     pub created_at: i128,
     /// The author of the commit.
     pub author: Author,
+    /// Whether the commit is signed, and if its signature matches, or `None` if this wasn't checked.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signature: Option<but_rebase::commit::SignatureStatus>,
 }
 
 impl TryFrom<gix::Commit<'_>> for Commit {
@@ -301,6 +313,7 @@ impl TryFrom<gix::Commit<'_>> for Commit {
             state: CommitState::LocalAndRemote(commit.id),
             created_at: i128::from(commit.time()?.seconds) * 1000,
             author: commit.author()?.into(),
+            signature: None,
         })
     }
 }
@@ -319,14 +332,16 @@ impl std::fmt::Debug for Commit {
 
 /// Commit that is only at the remote.
 /// Unlike the `Commit` struct, there is no knowledge of GitButler concepts like conflicted state etc.
-#[derive(Clone, Serialize)]
+#[derive(Clone, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct UpstreamCommit {
     /// The OID of the commit.
     #[serde(with = "gitbutler_serde::object_id")]
+    #[schemars(with = "String")]
     pub id: gix::ObjectId,
     /// The message of the commit.
     #[serde(with = "gitbutler_serde::bstring_lossy")]
+    #[schemars(with = "String")]
     pub message: BString,
     /// Commit creation time in Epoch milliseconds.
     pub created_at: i128,
@@ -346,7 +361,7 @@ impl std::fmt::Debug for UpstreamCommit {
 }
 
 /// Represents the pushable status for the current stack.
-#[derive(Debug, Clone, PartialEq, Eq, Copy, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Copy, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub enum PushStatus {
     /// Can push, but there are no changes to be pushed
@@ -362,14 +377,16 @@ pub enum PushStatus {
 }
 
 /// Information about the current state of a branch.
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct BranchDetails {
     /// The name of the branch.
     #[serde(with = "gitbutler_serde::bstring_lossy")]
+    #[schemars(with = "String")]
     pub name: BString,
     /// Upstream reference, e.g. `refs/remotes/origin/base-branch-improvements`
     #[serde(with = "gitbutler_serde::bstring_opt_lossy")]
+    #[schemars(with = "Option<String>")]
     pub remote_tracking_branch: Option<BString>,
     /// Description of the branch.
     /// Can include arbitrary utf8 data, eg. markdown etc.
@@ -381,11 +398,13 @@ pub struct BranchDetails {
     /// This is the last commit in the branch, aka the tip of the branch.
     /// If this is the only branch in the stack or the top-most branch, this is the tip of the stack.
     #[serde(with = "gitbutler_serde::object_id")]
+    #[schemars(with = "String")]
     pub tip: gix::ObjectId,
     /// This is the base commit from the perspective of this branch.
     /// If the branch is part of a stack and is on top of another branch, this is the head of the branch below it.
     /// If this branch is at the bottom of the stack, this is the merge base of the stack.
     #[serde(with = "gitbutler_serde::object_id")]
+    #[schemars(with = "String")]
     pub base_commit: gix::ObjectId,
     /// The pushable status for the branch.
     pub push_status: PushStatus,
@@ -404,7 +423,7 @@ pub struct BranchDetails {
 }
 
 /// Information about the current state of a stack
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct StackDetails {
     /// This is the name of the top-most branch, provided by the API for convenience
@@ -418,14 +437,16 @@ pub struct StackDetails {
 }
 
 /// Represents a branch in a [`Stack`]. It contains commits derived from the local pseudo branch and it's respective remote
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Branch {
     /// The name of the branch.
     #[serde(with = "gitbutler_serde::bstring_lossy")]
+    #[schemars(with = "String")]
     pub name: BString,
     /// Upstream reference, e.g. `refs/remotes/origin/base-branch-improvements`
     #[serde(with = "gitbutler_serde::bstring_opt_lossy")]
+    #[schemars(with = "Option<String>")]
     pub remote_tracking_branch: Option<BString>,
     /// Description of the branch.
     /// Can include arbitrary utf8 data, eg. markdown etc.
@@ -442,10 +463,12 @@ pub struct Branch {
     /// This is the last commit in the branch, aka the tip of the branch.
     /// If this is the only branch in the stack or the top-most branch, this is the tip of the stack.
     #[serde(with = "gitbutler_serde::object_id")]
+    #[schemars(with = "String")]
     pub tip: gix::ObjectId,
     /// This is the base commit from the perspective of this branch.
     /// If the branch is part of a stack and is on top of another branch, this is the head of the branch below it.
     /// If this branch is at the bottom of the stack, this is the merge base of the stack.
     #[serde(with = "gitbutler_serde::object_id")]
+    #[schemars(with = "String")]
     pub base_commit: gix::ObjectId,
 }
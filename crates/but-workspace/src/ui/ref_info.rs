@@ -8,10 +8,11 @@ use but_core::ref_metadata::StackId;
 use gix::refs::Category;
 
 /// A reference in `refs/heads`.
-#[derive(serde::Serialize, Debug, Clone)]
+#[derive(serde::Serialize, Debug, Clone, schemars::JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct BranchReference {
     /// The full ref name, like `refs/heads/feat`, for usage with the backend.
+    #[schemars(with = "Vec<u8>")]
     pub full_name_bytes: BString,
     /// The short version of `full_name_bytes` for display.
     pub display_name: String,
@@ -27,10 +28,11 @@ impl From<gix::refs::FullName> for BranchReference {
 }
 
 /// A reference in `refs/remotes`.
-#[derive(serde::Serialize, Debug, Clone)]
+#[derive(serde::Serialize, Debug, Clone, schemars::JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct RemoteTrackingReference {
     /// The full ref name, like `refs/remotes/origin/on-remote`, for usage with the backend.
+    #[schemars(with = "Vec<u8>")]
     pub full_name_bytes: BString,
     /// The short version of `full_name_bytes` for display, like `on-remote`, without the remote name.
     pub display_name: String,
@@ -82,7 +84,7 @@ impl RemoteTrackingReference {
 }
 
 /// Information about the target reference, the one we want to integrate with.
-#[derive(serde::Serialize, Debug, Clone)]
+#[derive(serde::Serialize, Debug, Clone, schemars::JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Target {
     /// The remote tracking branch of the traget to integrate with, like `refs/remotes/origin/main`.
@@ -112,7 +114,7 @@ pub(crate) mod inner {
 
     /// The UI-clone of [`crate::RefInfo`].
     /// TODO: should also include base-branch data, see `get_base_branch_data()`.
-    #[derive(serde::Serialize, Debug, Clone)]
+    #[derive(serde::Serialize, Debug, Clone, schemars::JsonSchema)]
     #[serde(rename_all = "camelCase")]
     pub struct RefInfo {
         /// The name of the ref that points to a workspace commit,
@@ -197,7 +199,7 @@ impl inner::RefInfo {
 }
 
 /// The UI-clone of [`branch::Stack`].
-#[derive(serde::Serialize, Debug, Clone)]
+#[derive(serde::Serialize, Debug, Clone, schemars::JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Stack {
     /// If the stack belongs to a managed workspace, the `id` will be set and persist.
@@ -208,6 +210,7 @@ pub struct Stack {
     /// Otherwise, it's the merge-base of all stacks in the current workspace.
     /// It is `None` if this is a stack derived from a branch without relation to any other branch.
     #[serde(with = "gitbutler_serde::object_id_opt")]
+    #[schemars(with = "Option<String>")]
     pub base: Option<gix::ObjectId>,
     /// The branch-name denoted segments of the stack from its tip to the point of reference, typically a merge-base.
     /// This array is never empty.
@@ -228,7 +231,7 @@ impl Stack {
 }
 
 /// A segment of a commit graph, representing a set of commits exclusively.
-#[derive(serde::Serialize, Debug, Clone)]
+#[derive(serde::Serialize, Debug, Clone, schemars::JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Segment {
     /// The unambiguous or disambiguated name of the branch at the tip of the segment, i.e. at the first commit.
@@ -278,6 +281,7 @@ pub struct Segment {
     /// It is `None` if the stack segment contains the first commit in the history, an orphan without ancestry,
     /// or if the history traversal was stopped early.
     #[serde(with = "gitbutler_serde::object_id_opt")]
+    #[schemars(with = "Option<String>")]
     pub base: Option<gix::ObjectId>,
 }
 
@@ -0,0 +1,44 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+CLI=${1:?The first argument is the GitButler CLI}
+
+git init remote
+(cd remote
+  git config user.name "Author"
+  git config user.email "author@example.com"
+
+  seq 10 >file
+  echo "other" >other
+  git add . && git commit -m "init"
+)
+
+CHANGE_ID=0
+function commit_stack() {
+  local stack="${1:?}"
+  local message="${2:?}"
+  ((CHANGE_ID += 1))
+  CHANGE_ID=$CHANGE_ID $CLI branch commit "$stack" -m "$message"
+}
+
+export GITBUTLER_CLI_DATA_DIR=../user/gitbutler/app-data
+
+# A stack whose bottom branch has a commit that changes two lines far apart in `file`, which are two hunks
+# without context lines, and the only line of `other`. The top branch has a commit on top of it.
+git clone remote two-branches
+(cd two-branches
+  git config user.name "Author"
+  git config user.email "author@example.com"
+
+  $CLI project add --switch-to-workspace "$(git rev-parse --symbolic-full-name @{u})"
+
+  $CLI branch create --set-default bottom
+  echo "before" >before
+  commit_stack "bottom" "add before"
+  { echo 1; echo two; seq 3 8; echo nine; echo 10; } >file
+  echo "changed" >other
+  commit_stack "bottom" "change both files"
+
+  $CLI branch series bottom -s "top"
+  echo "after" >after
+  commit_stack "bottom" "add after"
+)
@@ -1,2 +1,3 @@
 mod file;
 mod hunk;
+mod split_commit;
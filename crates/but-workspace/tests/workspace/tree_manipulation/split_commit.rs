@@ -0,0 +1,253 @@
+use but_testsupport::gix_testtools::tempfile::TempDir;
+use but_workspace::{CommitChanges, DiffSpec, StackId, split_commit};
+use gitbutler_command_context::CommandContext;
+use gitbutler_stack::VirtualBranchesHandle;
+
+use crate::utils::{CONTEXT_LINES, hunk_header};
+
+#[test]
+fn partial_hunk_split_with_remainder() -> anyhow::Result<()> {
+    let (mut ctx, _tmp, stack_id) = two_branches()?;
+    let source = head_of(&ctx, stack_id, "bottom")?;
+    let top_before = head_of(&ctx, stack_id, "top")?;
+
+    let pieces = [piece("first hunk", vec![spec("file", &[("-2,1", "+2,1")])])];
+    let outcome = split_commit(&mut ctx, stack_id, source, &pieces, CONTEXT_LINES)?;
+    assert_eq!(outcome.new_commits.len(), 1);
+    assert!(
+        outcome.remainder.is_some(),
+        "the second hunk and `other` aren't claimed"
+    );
+
+    let repo = ctx.gix_repo()?;
+    let bottom = head_of(&ctx, stack_id, "bottom")?;
+    assert_eq!(
+        messages(&repo, bottom)?,
+        ["change both files", "first hunk", "add before"],
+        "the pieces replace the commit, with the remainder picked last so it keeps the original message"
+    );
+    let piece_id = parent_of(&repo, bottom)?;
+    assert_eq!(
+        file_in_commit(&repo, piece_id, "file")?,
+        "1\ntwo\n3\n4\n5\n6\n7\n8\n9\n10\n",
+        "only the claimed hunk is applied"
+    );
+    assert_eq!(file_in_commit(&repo, piece_id, "other")?, "other\n");
+    assert_eq!(
+        file_in_commit(&repo, bottom, "file")?,
+        "1\ntwo\n3\n4\n5\n6\n7\n8\nnine\n10\n",
+        "the remainder adds the unclaimed hunk"
+    );
+    assert_eq!(file_in_commit(&repo, bottom, "other")?, "changed\n");
+
+    let top = head_of(&ctx, stack_id, "top")?;
+    assert_ne!(top, top_before, "the commits above are rebased");
+    assert_eq!(
+        messages(&repo, top)?,
+        ["add after", "change both files", "first hunk", "add before"],
+        "the head of each branch is updated"
+    );
+    assert_eq!(file_in_commit(&repo, top, "after")?, "after\n");
+    Ok(())
+}
+
+#[test]
+fn fully_claimed_commit_has_no_remainder() -> anyhow::Result<()> {
+    let (mut ctx, _tmp, stack_id) = two_branches()?;
+    let source = head_of(&ctx, stack_id, "bottom")?;
+
+    let pieces = [
+        piece(
+            "both hunks",
+            vec![spec("file", &[("-2,1", "+2,1"), ("-9,1", "+9,1")])],
+        ),
+        piece("other", vec![spec("other", &[])]),
+    ];
+    let outcome = split_commit(&mut ctx, stack_id, source, &pieces, CONTEXT_LINES)?;
+    assert_eq!(outcome.remainder, None);
+    assert_eq!(outcome.new_commits.len(), 2);
+
+    let repo = ctx.gix_repo()?;
+    let bottom = head_of(&ctx, stack_id, "bottom")?;
+    assert_eq!(
+        messages(&repo, bottom)?,
+        ["other", "both hunks", "add before"],
+        "the pieces are picked in order"
+    );
+    let both_hunks = parent_of(&repo, bottom)?;
+    assert_eq!(
+        file_in_commit(&repo, both_hunks, "file")?,
+        "1\ntwo\n3\n4\n5\n6\n7\n8\nnine\n10\n"
+    );
+    assert_eq!(file_in_commit(&repo, both_hunks, "other")?, "other\n");
+    assert_eq!(file_in_commit(&repo, bottom, "other")?, "changed\n");
+    assert_eq!(
+        messages(&repo, head_of(&ctx, stack_id, "top")?)?,
+        ["add after", "other", "both hunks", "add before"]
+    );
+    Ok(())
+}
+
+#[test]
+fn invalid_claims_are_rejected() -> anyhow::Result<()> {
+    let (mut ctx, _tmp, stack_id) = two_branches()?;
+    let source = head_of(&ctx, stack_id, "bottom")?;
+    let top = head_of(&ctx, stack_id, "top")?;
+
+    let err = split_commit(
+        &mut ctx,
+        stack_id,
+        source,
+        &[piece("a", vec![spec("file", &[("-3,1", "+3,1")])])],
+        CONTEXT_LINES,
+    )
+    .unwrap_err();
+    assert!(
+        err.to_string()
+            .starts_with("Hunk -3,1 +3,1 isn't a hunk of 'file'"),
+        "{err}"
+    );
+
+    let err = split_commit(
+        &mut ctx,
+        stack_id,
+        source,
+        &[piece("a", vec![spec("unchanged", &[])])],
+        CONTEXT_LINES,
+    )
+    .unwrap_err();
+    assert!(
+        err.to_string()
+            .starts_with("'unchanged' isn't changed in commit"),
+        "{err}"
+    );
+
+    let first_hunk = || spec("file", &[("-2,1", "+2,1")]);
+    let second_hunk = || spec("file", &[("-9,1", "+9,1")]);
+    for (a, b) in [
+        (first_hunk(), first_hunk()),
+        (spec("file", &[]), second_hunk()),
+        (second_hunk(), spec("file", &[])),
+    ] {
+        let err = split_commit(
+            &mut ctx,
+            stack_id,
+            source,
+            &[piece("a", vec![a]), piece("b", vec![b])],
+            CONTEXT_LINES,
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "'file' is claimed by more than one piece of the split"
+        );
+    }
+
+    assert_eq!(
+        (
+            head_of(&ctx, stack_id, "bottom")?,
+            head_of(&ctx, stack_id, "top")?
+        ),
+        (source, top),
+        "the stack is left unchanged"
+    );
+    Ok(())
+}
+
+#[test]
+fn different_hunks_of_a_file_can_go_to_different_pieces() -> anyhow::Result<()> {
+    let (mut ctx, _tmp, stack_id) = two_branches()?;
+    let source = head_of(&ctx, stack_id, "bottom")?;
+
+    let pieces = [
+        piece("first hunk", vec![spec("file", &[("-2,1", "+2,1")])]),
+        piece(
+            "second hunk and other",
+            vec![spec("file", &[("-9,1", "+9,1")]), spec("other", &[])],
+        ),
+    ];
+    split_commit(&mut ctx, stack_id, source, &pieces, CONTEXT_LINES)?;
+    let repo = ctx.gix_repo()?;
+    assert_eq!(
+        messages(&repo, head_of(&ctx, stack_id, "bottom")?)?,
+        ["second hunk and other", "first hunk", "add before"]
+    );
+    Ok(())
+}
+
+/// The project with a stack of the branches `bottom` and `top`, and the id of that stack.
+/// The commit at the head of `bottom` changes two hunks of `file` and all of `other`.
+fn two_branches() -> anyhow::Result<(CommandContext, TempDir, StackId)> {
+    let (ctx, tmp) = gitbutler_testsupport::writable::fixture("split-commit.sh", "two-branches")?;
+    let stack_id = VirtualBranchesHandle::new(ctx.project().gb_dir())
+        .list_stacks_in_workspace()?
+        .first()
+        .expect("the fixture has one stack")
+        .id;
+    Ok((ctx, tmp, stack_id))
+}
+
+fn head_of(ctx: &CommandContext, stack_id: StackId, branch: &str) -> anyhow::Result<gix::ObjectId> {
+    let stack =
+        VirtualBranchesHandle::new(ctx.project().gb_dir()).get_stack_in_workspace(stack_id)?;
+    stack
+        .branches()
+        .into_iter()
+        .find(|head| head.name() == branch)
+        .expect("the branch is in the stack")
+        .head_oid(&ctx.gix_repo()?)
+}
+
+/// The titles of the commit with `id` and its ancestors, up to the base of the workspace.
+fn messages(repo: &gix::Repository, id: gix::ObjectId) -> anyhow::Result<Vec<String>> {
+    let mut messages = Vec::new();
+    let mut commit = repo.find_commit(id)?;
+    loop {
+        let title = commit.message()?.title.to_string();
+        if title == "init" {
+            return Ok(messages);
+        }
+        messages.push(title);
+        commit = repo.find_commit(parent_of(repo, commit.id)?)?;
+    }
+}
+
+fn parent_of(repo: &gix::Repository, id: gix::ObjectId) -> anyhow::Result<gix::ObjectId> {
+    Ok(repo
+        .find_commit(id)?
+        .parent_ids()
+        .next()
+        .expect("the commit has a parent")
+        .detach())
+}
+
+fn file_in_commit(
+    repo: &gix::Repository,
+    commit_id: gix::ObjectId,
+    path: &str,
+) -> anyhow::Result<String> {
+    let entry = repo
+        .find_commit(commit_id)?
+        .tree()?
+        .lookup_entry_by_path(path)?
+        .expect("the file is in the commit");
+    Ok(String::from_utf8(entry.object()?.detach().data)?)
+}
+
+fn spec(path: &str, hunks: &[(&str, &str)]) -> DiffSpec {
+    DiffSpec {
+        previous_path: None,
+        path: path.into(),
+        hunk_headers: hunks
+            .iter()
+            .map(|(old, new)| hunk_header(old, new))
+            .collect(),
+    }
+}
+
+fn piece(message: &str, changes: Vec<DiffSpec>) -> CommitChanges {
+    CommitChanges {
+        message: message.into(),
+        changes,
+    }
+}
@@ -0,0 +1,294 @@
+use std::collections::HashMap;
+
+use bstr::ByteSlice;
+use but_hunk_assignment::HunkAssignment;
+use but_settings::AppSettings;
+use but_workspace::{DiffSpec, HunkHeader, StackId};
+use colored::Colorize;
+use gitbutler_command_context::CommandContext;
+use gitbutler_oplog::entry::OperationKind;
+use gitbutler_project::Project;
+use serde::Serialize;
+
+/// What `but absorb` does with a single uncommitted hunk.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PlannedHunk {
+    path: String,
+    hunk_header: Option<HunkHeader>,
+    outcome: Outcome,
+    #[serde(skip)]
+    assignment: HunkAssignment,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase", tag = "type", content = "subject")]
+enum Outcome {
+    /// The hunk is amended into the most recent commit that touched its lines.
+    #[serde(rename_all = "camelCase")]
+    Amend {
+        stack_id: StackId,
+        #[serde(with = "gitbutler_serde::object_id")]
+        commit_id: gix::ObjectId,
+        branch_name: String,
+        commit_title: String,
+        /// The position of the commit in its stack, counted from the top, which stays stable when amending.
+        #[serde(skip)]
+        position: usize,
+    },
+    /// The lines of the hunk were touched by commits in more than one stack.
+    Ambiguous,
+    /// No commit in the workspace touched the lines of the hunk.
+    NoDependency,
+    /// The lines of the hunk were only touched by commits that aren't in the workspace.
+    OutsideWorkspace,
+}
+
+/// A commit in the workspace, as needed to find the most recent commit a hunk depends on.
+struct WorkspaceCommit {
+    stack_id: StackId,
+    position: usize,
+    branch_name: String,
+    title: String,
+}
+
+pub(crate) fn handle(project: &Project, json: bool, dry_run: bool) -> anyhow::Result<()> {
+    let mut ctx = CommandContext::open(project, AppSettings::load_from_default_path_creating()?)?;
+    let planned = plan(&mut ctx, project)?;
+
+    // Amending a commit changes its id and the ids of all commits above it, as well as the headers of the
+    // remaining hunks in the same file. Hence each target is identified by its stable position in the stack,
+    // and the hunks for it are planned again right before amending.
+    let mut targets = Vec::<(StackId, usize)>::new();
+    for hunk in &planned {
+        if let Outcome::Amend {
+            stack_id, position, ..
+        } = hunk.outcome
+            && !targets.contains(&(stack_id, position))
+        {
+            targets.push((stack_id, position));
+        }
+    }
+    if dry_run || targets.is_empty() {
+        if json {
+            println!("{}", serde_json::to_string_pretty(&planned)?);
+        } else {
+            print_plan(&planned);
+        }
+        return Ok(());
+    }
+
+    crate::rub::create_snapshot(&mut ctx, project, OperationKind::AmendCommit);
+    let mut absorbed = Vec::new();
+    for (target_stack_id, target_position) in targets {
+        let planned = plan(&mut ctx, project)?;
+        let hunks = planned
+            .into_iter()
+            .filter(|hunk| {
+                matches!(hunk.outcome, Outcome::Amend { stack_id, position, .. }
+                    if stack_id == target_stack_id && position == target_position)
+            })
+            .collect::<Vec<_>>();
+        let Some(Outcome::Amend { commit_id, .. }) = hunks.first().map(|h| h.outcome.clone())
+        else {
+            continue;
+        };
+        let diff_specs: Vec<DiffSpec> = hunks.iter().map(|h| h.assignment.clone().into()).collect();
+        let mut guard = project.exclusive_worktree_access();
+        let outcome = crate::rub::amend_diff_specs(
+            &mut ctx,
+            diff_specs,
+            Some(target_stack_id),
+            commit_id,
+            guard.write_permission(),
+        )?;
+        drop(guard);
+        if !outcome.rejected_specs.is_empty() {
+            tracing::warn!(
+                "Couldn't absorb {} hunk(s) into {commit_id}",
+                outcome.rejected_specs.len()
+            );
+        }
+        if let Some(new_commit) = outcome.new_commit {
+            let rejected = |hunk: &PlannedHunk| {
+                outcome.rejected_specs.iter().any(|(_, spec)| {
+                    spec.path == hunk.path.as_str()
+                        && (spec.hunk_headers.is_empty()
+                            || hunk
+                                .hunk_header
+                                .is_some_and(|h| spec.hunk_headers.contains(&h)))
+                })
+            };
+            absorbed.extend(
+                hunks
+                    .into_iter()
+                    .filter(|hunk| !rejected(hunk))
+                    .map(|hunk| (hunk, new_commit)),
+            );
+        }
+    }
+
+    if json {
+        let absorbed = absorbed
+            .iter()
+            .map(|(hunk, new_commit)| {
+                serde_json::json!({
+                    "path": hunk.path,
+                    "hunkHeader": hunk.hunk_header,
+                    "commitId": new_commit.to_string(),
+                })
+            })
+            .collect::<Vec<_>>();
+        println!("{}", serde_json::to_string_pretty(&absorbed)?);
+        return Ok(());
+    }
+    if absorbed.is_empty() {
+        println!("{}", "Nothing was absorbed.".dimmed());
+    }
+    for (hunk, new_commit) in &absorbed {
+        println!(
+            "Absorbed {} → {}",
+            describe_hunk(&hunk.path, hunk.hunk_header),
+            short(&new_commit.to_string()).blue()
+        );
+    }
+    Ok(())
+}
+
+/// Decide for each uncommitted hunk whether it's amended into the most recent workspace commit that touched its lines.
+/// Hunks that depend on more than one stack, on no commit at all, or only on commits outside the workspace are left alone.
+fn plan(ctx: &mut CommandContext, project: &Project) -> anyhow::Result<Vec<PlannedHunk>> {
+    let changes =
+        but_core::diff::ui::worktree_changes_by_worktree_dir(project.path.clone())?.changes;
+    let (assignments, assignments_error) =
+        but_hunk_assignment::assignments_with_fallback(ctx, false, Some(changes), None)?;
+    // Without reconciled assignments the hunks have no locks, and all of them would seem to have no dependency.
+    if let Some(err) = assignments_error {
+        return Err(err.context("Couldn't determine the commits the uncommitted changes depend on"));
+    }
+    let commits = workspace_commits(project)?;
+
+    Ok(assignments
+        .into_iter()
+        .map(|assignment| {
+            let locks = assignment.hunk_locks.clone().unwrap_or_default();
+            let outcome = if locks.is_empty() {
+                Outcome::NoDependency
+            } else if locks.iter().any(|lock| lock.stack_id != locks[0].stack_id) {
+                Outcome::Ambiguous
+            } else {
+                locks
+                    .iter()
+                    .filter_map(|lock| Some((lock.commit_id, commits.get(&lock.commit_id)?)))
+                    .min_by_key(|(_, commit)| commit.position)
+                    .map_or(Outcome::OutsideWorkspace, |(commit_id, commit)| {
+                        Outcome::Amend {
+                            stack_id: commit.stack_id,
+                            commit_id,
+                            branch_name: commit.branch_name.clone(),
+                            commit_title: commit.title.clone(),
+                            position: commit.position,
+                        }
+                    })
+            };
+            PlannedHunk {
+                path: assignment.path.clone(),
+                hunk_header: assignment.hunk_header,
+                outcome,
+                assignment,
+            }
+        })
+        .collect())
+}
+
+/// All commits in applied stacks, with their position counted from the top of their stack.
+fn workspace_commits(project: &Project) -> anyhow::Result<HashMap<gix::ObjectId, WorkspaceCommit>> {
+    let mut commits = HashMap::new();
+    for stack_entry in but_api::workspace::stacks(project.id, None)? {
+        let Some(stack_id) = stack_entry.id else {
+            continue;
+        };
+        let stack_details = but_api::workspace::stack_details(project.id, Some(stack_id))?;
+        let stack_commits = stack_details.branch_details.iter().flat_map(|branch| {
+            branch
+                .commits
+                .iter()
+                .map(move |commit| (branch.name.to_string(), commit))
+        });
+        for (position, (branch_name, commit)) in stack_commits.enumerate() {
+            commits.insert(
+                commit.id,
+                WorkspaceCommit {
+                    stack_id,
+                    position,
+                    branch_name,
+                    title: commit
+                        .message
+                        .lines()
+                        .next()
+                        .unwrap_or_default()
+                        .to_string(),
+                },
+            );
+        }
+    }
+    Ok(commits)
+}
+
+fn print_plan(planned: &[PlannedHunk]) {
+    if planned.is_empty() {
+        println!("{}", "No uncommitted changes to absorb.".dimmed());
+        return;
+    }
+    let hunks = planned
+        .iter()
+        .map(|hunk| describe_hunk(&hunk.path, hunk.hunk_header))
+        .collect::<Vec<_>>();
+    let width = hunks.iter().map(|h| h.len()).max().unwrap_or_default();
+    println!(
+        "{}  {}",
+        format!("{:width$}", "HUNK").bold(),
+        "TARGET".bold()
+    );
+    for (hunk, description) in planned.iter().zip(&hunks) {
+        let target = match &hunk.outcome {
+            Outcome::Amend {
+                commit_id,
+                branch_name,
+                commit_title,
+                ..
+            } => format!(
+                "{} {} {}",
+                short(&commit_id.to_string()).blue(),
+                commit_title,
+                format!("[{branch_name}]").green()
+            ),
+            Outcome::Ambiguous => "left unassigned: depends on more than one stack"
+                .dimmed()
+                .to_string(),
+            Outcome::NoDependency => "left unassigned: no commit touched these lines"
+                .dimmed()
+                .to_string(),
+            Outcome::OutsideWorkspace => {
+                "left unassigned: depends on commits outside the workspace"
+                    .dimmed()
+                    .to_string()
+            }
+        };
+        println!("{description:width$}  {target}");
+    }
+}
+
+fn describe_hunk(path: &str, hunk_header: Option<HunkHeader>) -> String {
+    match hunk_header {
+        Some(h) => format!(
+            "{path} @@ -{},{} +{},{} @@",
+            h.old_start, h.old_lines, h.new_start, h.new_lines
+        ),
+        None => path.to_string(),
+    }
+}
+
+fn short(commit_id: &str) -> &str {
+    &commit_id[..7.min(commit_id.len())]
+}
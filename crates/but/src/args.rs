@@ -17,7 +17,11 @@ pub struct Args {
 #[derive(Debug, clap::Subcommand)]
 pub enum Subcommands {
     /// Show commits on active branches in your workspace.
-    Log,
+    Log {
+        /// Check the signatures of all commits, which is done by default if GitButler signs commits.
+        #[clap(long = "verify-signatures", default_value_t = false)]
+        verify_signatures: bool,
+    },
     /// Overview of the uncommitted changes in the repository.
     #[clap(alias = "st")]
     Status {
@@ -43,12 +47,12 @@ pub enum Subcommands {
         long_about = "Combines two entities together to perform an operation.
 
 Non-exhaustive list of operations:
-      │Source     │Target
-──────┼───────────┼──────
-Amend │File,Branch│Commit
-Squash│Commit     │Commit
-Assign│File,Branch│Branch
-Move  │Commit     │Branch
+      │Source          │Target
+──────┼────────────────┼──────
+Amend │File,Hunk,Branch│Commit
+Squash│Commit          │Commit
+Assign│File,Hunk,Branch│Branch
+Move  │Commit          │Branch
 
 For examples see `but rub --help`."
     )]
@@ -78,6 +82,8 @@ For examples see `but rub --help`."
     },
     /// Removes all marks from the workspace
     Unmark,
+    /// Commands for inspecting workspace rules.
+    Rules(crate::rules::Platform),
     /// Commit changes to a stack.
     Commit {
         /// Commit message
@@ -100,11 +106,42 @@ For examples see `but rub --help`."
         /// Commit ID to edit the message for
         commit: String,
     },
+    /// Split a commit into several commits, each with a selection of its files or hunks.
+    ///
+    /// Without `--piece`, lists the files and hunks of the commit that can be selected.
+    /// Changes that no piece selects are kept in a commit with the original message.
+    Split {
+        /// Commit ID to split
+        commit: String,
+        /// Comma-separated files or hunks for a new commit, like `a.txt,b.txt#2`. Can be repeated, once per new commit.
+        #[clap(long = "piece", short = 'p', value_name = "SELECTION")]
+        pieces: Vec<String>,
+        /// The commit message for each piece, in the order of the pieces.
+        #[clap(long = "message", short = 'm')]
+        messages: Vec<String>,
+    },
+    /// Amend uncommitted hunks into the most recent commits that touched the same lines.
+    ///
+    /// Hunks whose lines were touched by commits in more than one stack, or by no commit at all, are left alone.
+    Absorb {
+        /// Only show which commit each hunk would be amended into, without changing anything.
+        #[clap(long = "dry-run", short = 'n')]
+        dry_run: bool,
+    },
+    /// Reorder, squash, drop, edit and test the commits of a stack, and move them between its branches, by editing a todo list.
+    ///
+    /// Opens `$EDITOR` with the commits and branches of the stack, oldest first, like `git rebase -i`.
+    Reorder {
+        /// Name of any branch in the stack to rebase
+        branch_name: String,
+    },
     /// Show operation history (last 20 entries).
     Oplog {
         /// Start from this oplog SHA instead of the head
         #[clap(long)]
         since: Option<String>,
+        #[clap(subcommand)]
+        cmd: Option<crate::oplog::Subcommands>,
     },
     /// Restore to a specific oplog snapshot.
     Restore {
@@ -116,6 +153,30 @@ For examples see `but rub --help`."
     },
     /// Undo the last operation by reverting to the previous snapshot.
     Undo,
+    /// Redo the last undone operation by restoring the state from before the undo.
+    ///
+    /// Only undos and restores that weren't followed by other operations can be redone.
+    Redo,
+    /// Push all branches of a stack to the remote, in dependency order.
+    Push {
+        /// Branch CLI ID or name up to which the stack is pushed. Defaults to the top of the only stack.
+        branch: Option<String>,
+        /// Fill in the description of pushed branches from the forge's review template.
+        #[clap(long, short = 'r')]
+        review: bool,
+        /// Add a reviewer to the pushed changes. Can be repeated. Gerrit only.
+        #[clap(long = "reviewer", value_name = "USER")]
+        reviewers: Vec<String>,
+        /// Add a hashtag to the pushed changes. Can be repeated. Gerrit only.
+        #[clap(long = "hashtag", value_name = "TAG")]
+        hashtags: Vec<String>,
+    },
+    /// Push a stack and prepare its branches for review.
+    /// Equivalent to `but push --review`.
+    Pr {
+        /// Branch CLI ID or name up to which the stack is pushed. Defaults to the top of the only stack.
+        branch: Option<String>,
+    },
     /// Starts up the MCP server.
     Mcp {
         /// Starts the internal MCP server which has more granular tools.
@@ -157,15 +218,37 @@ pub enum CommandName {
     New,
     #[clap(alias = "describe")]
     Describe,
+    #[clap(alias = "split")]
+    Split,
+    #[clap(alias = "absorb")]
+    Absorb,
+    #[clap(alias = "reorder")]
+    Reorder,
     #[clap(alias = "oplog")]
     Oplog,
     #[clap(alias = "restore")]
     Restore,
     #[clap(alias = "undo")]
     Undo,
+    #[clap(alias = "redo")]
+    Redo,
+    #[clap(alias = "push")]
+    Push,
+    #[clap(alias = "pr")]
+    Pr,
+    OplogPrune,
+    OplogDiff,
     BaseCheck,
     BaseUpdate,
     BranchNew,
+    BranchList,
+    BranchShow,
+    BranchDelete,
+    BranchRename,
+    BranchApply,
+    BranchUnapply,
+    BranchMove,
+    RulesTest,
     #[clap(
         alias = "claude-pre-tool",
         alias = "claudepretool",
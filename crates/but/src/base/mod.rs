@@ -1,9 +1,13 @@
+use but_workspace::StackId;
 use colored::Colorize;
 use gitbutler_branch_actions::upstream_integration::{
+    BaseBranchResolution, BaseBranchResolutionApproach,
     BranchStatus::{Conflicted, Empty, Integrated, SaflyUpdatable},
-    Resolution, ResolutionApproach,
+    Resolution, ResolutionApproach, StackStatus,
     StackStatuses::{UpToDate, UpdatesRequired},
+    TreeStatus,
 };
+use gitbutler_oxidize::ObjectIdExt;
 use gitbutler_project::Project;
 
 #[derive(Debug, clap::Parser)]
@@ -16,7 +20,9 @@ pub enum Subcommands {
     /// Fetches remotes from the remote and checks the mergeability of the branches in the workspace.
     Check,
     /// Updates the worspace (with all applied branches) to include the latest changes from the base branch.
-    Update,
+    ///
+    /// Stacks are rebased, or deleted if all their branches were integrated, unless chosen otherwise.
+    Update(UpdateChoices),
 }
 
 pub fn handle(cmd: &Subcommands, project: &Project, json: bool) -> anyhow::Result<()> {
@@ -67,96 +73,312 @@ pub fn handle(cmd: &Subcommands, project: &Project, json: bool) -> anyhow::Resul
                             "\n❗️ There are uncommitted changes in the worktree that may conflict with the updates."
                         );
                     }
-                    if !statuses.is_empty() {
-                        println!("\n{}", "Active Branch Status".bold());
-                        for (_id, status) in statuses {
-                            for bs in status.branch_statuses {
-                                let status_icon = match bs.status {
-                                    SaflyUpdatable => "✅".to_string(),
-                                    Integrated => "🔄".to_string(),
-                                    Conflicted { rebasable } => {
-                                        if rebasable {
-                                            "⚠️".to_string()
-                                        } else {
-                                            "❗️".to_string()
-                                        }
-                                    }
-                                    Empty => "✅".to_string(),
-                                };
-                                let status_text = match bs.status {
-                                    SaflyUpdatable => "Updatable".green(),
-                                    Integrated => "Integrated".blue(),
-                                    Conflicted { rebasable } => {
-                                        if rebasable {
-                                            "Conflicted (Rebasable)".yellow()
-                                        } else {
-                                            "Conflicted (Not Rebasable)".red()
-                                        }
-                                    }
-                                    Empty => "Nothing to do".normal(),
-                                };
-                                println!("\n{} {} ({})", status_icon, bs.name, status_text);
-                            }
-                        }
-                    }
+                    print_statuses(&statuses);
                 }
             }
             println!("\nRun `but base update` to update your branches");
             Ok(())
         }
-        Subcommands::Update => {
-            let status =
-                but_api::virtual_branches::upstream_integration_statuses(project.id, None)?;
-            let resolutions = match status {
-                UpToDate => {
-                    println!("✅ Everything is up to date");
-                    None
-                }
-                UpdatesRequired {
-                    worktree_conflicts,
-                    statuses,
-                } => {
-                    if !worktree_conflicts.is_empty() {
-                        println!(
-                            "❗️ There are uncommitted changes in the worktree that may conflict with
-                            the updates. Please commit or stash them and try again."
-                        );
-                        None
-                    } else {
-                        println!("🔄 Updating branches...");
-                        let mut resolutions = vec![];
-                        for (maybe_stack_id, status) in statuses {
-                            let Some(stack_id) = maybe_stack_id else {
-                                println!("No stack ID, assuming we're on single-branch mode...",);
-                                continue;
-                            };
-                            let approach = if status
-                                .branch_statuses
-                                .iter()
-                                .all(|s| s.status == gitbutler_branch_actions::upstream_integration::BranchStatus::Integrated)
-                            && status.tree_status != gitbutler_branch_actions::upstream_integration::TreeStatus::Conflicted
-                            {
-                                    ResolutionApproach::Delete
-                                } else {
-                                    ResolutionApproach::Rebase
-                                };
-                            let resolution = Resolution {
-                                stack_id,
-                                approach,
-                                delete_integrated_branches: true,
-                                force_integrated_branches: vec![],
-                            };
-                            resolutions.push(resolution);
+        Subcommands::Update(choices) => update(project, json, choices),
+    }
+}
+
+/// The choices for resolving the update of the workspace, given on the command-line.
+#[derive(Debug, clap::Args)]
+pub struct UpdateChoices {
+    /// Rebase the stack containing this branch onto the new base. Can be repeated.
+    #[clap(long = "rebase", value_name = "BRANCH")]
+    rebase: Vec<String>,
+    /// Merge the new base into the stack containing this branch. Can be repeated.
+    #[clap(long = "merge", value_name = "BRANCH")]
+    merge: Vec<String>,
+    /// Unapply the stack containing this branch instead of updating it. Can be repeated.
+    #[clap(long = "unapply", value_name = "BRANCH")]
+    unapply: Vec<String>,
+    /// Delete the stack containing this branch instead of updating it. Can be repeated.
+    #[clap(long = "delete", value_name = "BRANCH")]
+    delete: Vec<String>,
+    /// Ask how to update each stack that wasn't chosen by a flag.
+    #[clap(long, short = 'i')]
+    interactive: bool,
+    /// Keep branches that were integrated upstream instead of archiving them.
+    #[clap(long)]
+    keep_integrated: bool,
+    /// How to resolve a base branch that diverged from its upstream.
+    #[clap(long, value_enum)]
+    base: Option<BaseApproach>,
+}
+
+/// How a diverged base branch is brought up to date with its upstream.
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+pub enum BaseApproach {
+    /// Rebase local base branch commits onto the upstream.
+    Rebase,
+    /// Merge the upstream into the local base branch.
+    Merge,
+    /// Discard local base branch commits.
+    HardReset,
+}
+
+impl From<BaseApproach> for BaseBranchResolutionApproach {
+    fn from(value: BaseApproach) -> Self {
+        match value {
+            BaseApproach::Rebase => BaseBranchResolutionApproach::Rebase,
+            BaseApproach::Merge => BaseBranchResolutionApproach::Merge,
+            BaseApproach::HardReset => BaseBranchResolutionApproach::HardReset,
+        }
+    }
+}
+
+fn update(project: &Project, json: bool, choices: &UpdateChoices) -> anyhow::Result<()> {
+    if json && choices.interactive {
+        anyhow::bail!("Interactive choices can't be combined with JSON output");
+    }
+    let (target_commit_id, base_branch_resolution) = match choices.base {
+        Some(approach) => {
+            let target_commit_id = but_api::virtual_branches::resolve_upstream_integration(
+                project.id,
+                approach.into(),
+            )?;
+            let resolution = BaseBranchResolution::new(
+                gix::ObjectId::from_hex(target_commit_id.as_bytes())?.to_git2(),
+                approach.into(),
+            );
+            (Some(target_commit_id), Some(resolution))
+        }
+        None => (None, None),
+    };
+
+    let status =
+        but_api::virtual_branches::upstream_integration_statuses(project.id, target_commit_id)?;
+    let statuses = match status {
+        UpToDate => {
+            if json {
+                println!("{}", serde_json::to_string_pretty(&status)?);
+            } else {
+                println!("✅ Everything is up to date");
+            }
+            return Ok(());
+        }
+        UpdatesRequired {
+            worktree_conflicts,
+            statuses,
+        } => {
+            if !worktree_conflicts.is_empty() {
+                anyhow::bail!(
+                    "There are uncommitted changes in the worktree that may conflict with the updates. Please commit or stash them and try again."
+                );
+            }
+            statuses
+        }
+    };
+
+    ensure_chosen_branches_exist(choices, &statuses)?;
+    if !json {
+        print_statuses(&statuses);
+        println!();
+    }
+
+    let mut resolutions = vec![];
+    for (maybe_stack_id, status) in &statuses {
+        let Some(stack_id) = maybe_stack_id else {
+            if !json {
+                println!("No stack ID, assuming we're on single-branch mode...");
+            }
+            continue;
+        };
+        let approach = match chosen_approach(choices, status)? {
+            Some(approach) => approach,
+            None if choices.interactive => prompt_approach(status)?,
+            None => default_approach(status),
+        };
+        resolutions.push(Resolution {
+            stack_id: *stack_id,
+            approach,
+            delete_integrated_branches: !choices.keep_integrated,
+            force_integrated_branches: vec![],
+        });
+    }
+
+    if !json {
+        println!("🔄 Updating branches...");
+    }
+    let outcome = but_api::virtual_branches::integrate_upstream(
+        project.id,
+        resolutions,
+        base_branch_resolution,
+    )?;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&outcome)?);
+        return Ok(());
+    }
+
+    println!("✅ Updated the workspace");
+    if !outcome.archived_branches.is_empty() {
+        println!("\n{}", "Archived integrated branches".bold());
+        for name in &outcome.archived_branches {
+            println!("  {}", name.blue());
+        }
+    }
+    if !outcome.review_ids_to_close.is_empty() {
+        println!("\n{}", "Reviews to close".bold());
+        for review_id in &outcome.review_ids_to_close {
+            println!("  {review_id}");
+        }
+    }
+    Ok(())
+}
+
+/// The approach chosen by flags for the stack with `status`, which is identified by any of its branches.
+fn chosen_approach(
+    choices: &UpdateChoices,
+    status: &StackStatus,
+) -> anyhow::Result<Option<ResolutionApproach>> {
+    let chosen = [
+        (&choices.rebase, ResolutionApproach::Rebase),
+        (&choices.merge, ResolutionApproach::Merge),
+        (&choices.unapply, ResolutionApproach::Unapply),
+        (&choices.delete, ResolutionApproach::Delete),
+    ]
+    .into_iter()
+    .filter(|(names, _)| {
+        status
+            .branch_statuses
+            .iter()
+            .any(|bs| names.contains(&bs.name))
+    })
+    .map(|(_, approach)| approach)
+    .collect::<Vec<_>>();
+    match chosen.as_slice() {
+        [] => Ok(None),
+        [approach] => Ok(Some(*approach)),
+        _ => anyhow::bail!(
+            "Conflicting choices for the stack with {}",
+            stack_name(status)
+        ),
+    }
+}
+
+/// Fail if a branch given by flags isn't part of any stack that needs to be updated, as it would be ignored otherwise.
+fn ensure_chosen_branches_exist(
+    choices: &UpdateChoices,
+    statuses: &[(Option<StackId>, StackStatus)],
+) -> anyhow::Result<()> {
+    let unknown = [
+        &choices.rebase,
+        &choices.merge,
+        &choices.unapply,
+        &choices.delete,
+    ]
+    .into_iter()
+    .flatten()
+    .find(|name| {
+        !statuses
+            .iter()
+            .any(|(_, status)| status.branch_statuses.iter().any(|bs| &&bs.name == name))
+    });
+    match unknown {
+        Some(name) => {
+            anyhow::bail!("No stack that needs to be updated contains the branch '{name}'")
+        }
+        None => Ok(()),
+    }
+}
+
+/// Delete stacks that were fully integrated, and rebase all others.
+fn default_approach(status: &StackStatus) -> ResolutionApproach {
+    if status
+        .branch_statuses
+        .iter()
+        .all(|s| s.status == Integrated)
+        && status.tree_status != TreeStatus::Conflicted
+    {
+        ResolutionApproach::Delete
+    } else {
+        ResolutionApproach::Rebase
+    }
+}
+
+fn prompt_approach(status: &StackStatus) -> anyhow::Result<ResolutionApproach> {
+    use std::io::{self, Write};
+
+    let default = default_approach(status);
+    loop {
+        print!(
+            "How should {} be updated? [r]ebase, [m]erge, [u]napply, [d]elete (default: {}): ",
+            stack_name(status).bold(),
+            approach_name(default)
+        );
+        io::stdout().flush()?;
+
+        let mut input = String::new();
+        io::stdin().read_line(&mut input)?;
+        match input.trim().to_lowercase().as_str() {
+            "" => return Ok(default),
+            "r" | "rebase" => return Ok(ResolutionApproach::Rebase),
+            "m" | "merge" => return Ok(ResolutionApproach::Merge),
+            "u" | "unapply" => return Ok(ResolutionApproach::Unapply),
+            "d" | "delete" => return Ok(ResolutionApproach::Delete),
+            _ => println!("{}", "Please choose one of r, m, u or d.".yellow()),
+        }
+    }
+}
+
+fn approach_name(approach: ResolutionApproach) -> &'static str {
+    match approach {
+        ResolutionApproach::Rebase => "rebase",
+        ResolutionApproach::Merge => "merge",
+        ResolutionApproach::Unapply => "unapply",
+        ResolutionApproach::Delete => "delete",
+    }
+}
+
+/// The stack is named after its top-most branch.
+fn stack_name(status: &StackStatus) -> String {
+    status
+        .branch_statuses
+        .first()
+        .map(|bs| bs.name.clone())
+        .unwrap_or_default()
+}
+
+fn print_statuses(statuses: &[(Option<StackId>, StackStatus)]) {
+    if !statuses.is_empty() {
+        println!("\n{}", "Active Branch Status".bold());
+        for (_id, status) in statuses {
+            let tree_status = match status.tree_status {
+                TreeStatus::SaflyUpdatable => "updatable".green(),
+                TreeStatus::Conflicted => "conflicted".red(),
+                TreeStatus::Empty => "nothing to do".normal(),
+            };
+            println!("\n{} ({})", stack_name(status).bold(), tree_status);
+            for bs in &status.branch_statuses {
+                let status_icon = match &bs.status {
+                    SaflyUpdatable => "✅".to_string(),
+                    Integrated => "🔄".to_string(),
+                    Conflicted { rebasable } => {
+                        if *rebasable {
+                            "⚠️".to_string()
+                        } else {
+                            "❗️".to_string()
                         }
-                        Some(resolutions)
                     }
-                }
-            };
-
-            if let Some(resolutions) = resolutions {
-                but_api::virtual_branches::integrate_upstream(project.id, resolutions, None)?;
+                    Empty => "✅".to_string(),
+                };
+                let status_text = match &bs.status {
+                    SaflyUpdatable => "Updatable".green(),
+                    Integrated => "Integrated".blue(),
+                    Conflicted { rebasable } => {
+                        if *rebasable {
+                            "Conflicted (Rebasable)".yellow()
+                        } else {
+                            "Conflicted (Not Rebasable)".red()
+                        }
+                    }
+                    Empty => "Nothing to do".normal(),
+                };
+                println!("  {} {} ({})", status_icon, bs.name, status_text);
             }
-            Ok(())
         }
     }
 }
@@ -1,6 +1,14 @@
+use std::str::FromStr;
+
+use bstr::ByteSlice;
 use but_settings::AppSettings;
+use but_workspace::{StackId, ui::BranchDetails};
+use colored::Colorize;
+use gitbutler_branch_actions::BranchListingFilter;
 use gitbutler_command_context::CommandContext;
 use gitbutler_project::Project;
+use gitbutler_reference::Refname;
+use serde::Serialize;
 
 #[derive(Debug, clap::Parser)]
 pub struct Platform {
@@ -18,9 +26,53 @@ pub enum Subcommands {
         #[clap(long, short = 'a')]
         anchor: Option<String>,
     },
+    /// Lists local branches, marking the ones that are applied in the workspace
+    #[clap(alias = "ls")]
+    List {
+        /// Also list branches that only exist on a remote
+        #[clap(long, short = 'a')]
+        all: bool,
+    },
+    /// Shows the commits of a branch in the workspace
+    Show {
+        /// Name of the branch
+        branch_name: String,
+    },
+    /// Deletes a branch, removing it from the workspace if it is applied
+    Delete {
+        /// Name of the branch
+        branch_name: String,
+    },
+    /// Renames a branch in the workspace
+    Rename {
+        /// Name of the branch
+        branch_name: String,
+        /// The new name of the branch
+        new_name: String,
+    },
+    /// Applies a local or remote branch to the workspace
+    Apply {
+        /// Name of the branch, like `feature` or `origin/feature`
+        branch_name: String,
+        /// Only check whether the branch would merge cleanly with the applied stacks, listing conflicting paths
+        #[clap(long)]
+        check: bool,
+    },
+    /// Unapplies the stack containing the branch from the workspace
+    Unapply {
+        /// Name of the branch
+        branch_name: String,
+    },
+    /// Moves a branch on top of another branch, making it a dependent branch in that stack
+    Move {
+        /// Name of the branch to move
+        branch_name: String,
+        /// Name of the branch to move it onto
+        onto: String,
+    },
 }
 
-pub fn handle(cmd: &Subcommands, project: &Project, _json: bool) -> anyhow::Result<()> {
+pub fn handle(cmd: &Subcommands, project: &Project, json: bool) -> anyhow::Result<()> {
     match cmd {
         Subcommands::New {
             branch_name,
@@ -92,5 +144,348 @@ pub fn handle(cmd: &Subcommands, project: &Project, _json: bool) -> anyhow::Resu
             println!("Created branch {branch_name}");
             Ok(())
         }
+        Subcommands::List { all } => list(project, json, *all),
+        Subcommands::Show { branch_name } => show(project, json, branch_name),
+        Subcommands::Delete { branch_name } => delete(project, json, branch_name),
+        Subcommands::Rename {
+            branch_name,
+            new_name,
+        } => rename(project, json, branch_name, new_name),
+        Subcommands::Apply {
+            branch_name,
+            check: false,
+        } => apply(project, json, branch_name),
+        Subcommands::Apply {
+            branch_name,
+            check: true,
+        } => check_apply(project, json, branch_name),
+        Subcommands::Unapply { branch_name } => unapply(project, json, branch_name),
+        Subcommands::Move { branch_name, onto } => move_onto(project, json, branch_name, onto),
+    }
+}
+
+fn list(project: &Project, json: bool, all: bool) -> anyhow::Result<()> {
+    let filter = BranchListingFilter {
+        local: (!all).then_some(true),
+        applied: None,
+    };
+    let branches = but_api::virtual_branches::list_branches(project.id, Some(filter))?;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&branches)?);
+        return Ok(());
+    }
+
+    if branches.is_empty() {
+        println!("{}", "No branches.".dimmed());
+    }
+    for branch in branches {
+        let applied = branch.stack.as_ref().is_some_and(|s| s.in_workspace);
+        let marker = if applied {
+            "●".green()
+        } else {
+            "○".dimmed()
+        };
+        let name = if applied {
+            branch.name.to_str_lossy().green().bold()
+        } else {
+            branch.name.to_str_lossy().normal()
+        };
+        let remotes = branch
+            .remotes
+            .iter()
+            .map(|remote| remote.as_bstr().to_str_lossy().into_owned())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let remotes = if remotes.is_empty() {
+            "".normal()
+        } else {
+            format!("({remotes})").dimmed()
+        };
+        let dependents = branch
+            .stack
+            .as_ref()
+            .map(|stack| {
+                stack
+                    .branches
+                    .iter()
+                    .filter(|b| b.as_str() != branch.name.to_str_lossy())
+                    .map(String::as_str)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            })
+            .filter(|dependents| !dependents.is_empty())
+            .map(|dependents| format!("stacked with {dependents}").dimmed())
+            .unwrap_or_default();
+        println!("{marker} {name} {remotes} {dependents}");
     }
+    Ok(())
+}
+
+fn show(project: &Project, json: bool, branch_name: &str) -> anyhow::Result<()> {
+    let stack_id = stack_id_of(project, branch_name)?;
+    let details = but_api::workspace::stack_details(project.id, Some(stack_id))?;
+    let branch = details
+        .branch_details
+        .into_iter()
+        .find(|b| b.name == branch_name)
+        .ok_or_else(|| anyhow::anyhow!("Branch '{branch_name}' not found in its stack"))?;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&branch)?);
+        return Ok(());
+    }
+    print_branch(&branch);
+    Ok(())
+}
+
+fn print_branch(branch: &BranchDetails) {
+    let id = crate::id::CliId::branch(&branch.name.to_str_lossy())
+        .to_string()
+        .underline()
+        .blue();
+    println!("{id} [{}]", branch.name.to_string().green().bold());
+    if let Some(remote) = &branch.remote_tracking_branch {
+        println!("  {} {}", "upstream:".dimmed(), remote);
+    }
+    if let Some(description) = &branch.description {
+        println!("  {}", description.dimmed());
+    }
+    for commit in &branch.upstream_commits {
+        let sha = commit.id.to_string();
+        println!(
+            "  {} {}{} {}",
+            "●".yellow(),
+            sha[..2].blue().underline(),
+            sha[2..7].dimmed(),
+            first_line(&commit.message.to_str_lossy())
+        );
+    }
+    if branch.commits.is_empty() {
+        println!("  {}", "(no commits)".dimmed().italic());
+    }
+    for commit in &branch.commits {
+        let sha = commit.id.to_string();
+        let conflicted = if commit.has_conflicts {
+            "{conflicted}".red()
+        } else {
+            "".normal()
+        };
+        println!(
+            "  ● {}{} {} {}",
+            sha[..2].blue().underline(),
+            sha[2..7].dimmed(),
+            first_line(&commit.message.to_str_lossy()),
+            conflicted
+        );
+    }
+}
+
+fn first_line(message: &str) -> String {
+    message
+        .lines()
+        .next()
+        .unwrap_or_default()
+        .chars()
+        .take(72)
+        .collect()
+}
+
+/// The `--json` output of `but branch delete`.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Deleted {
+    /// The name of the deleted branch.
+    deleted: String,
+}
+
+/// The `--json` output of `but branch rename`.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Renamed {
+    /// The previous name of the branch.
+    renamed: String,
+    /// The normalized name the branch was renamed to.
+    new_name: String,
+}
+
+/// The `--json` output of `but branch unapply`.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Unapplied {
+    /// The stack that was unapplied along with the branch.
+    unapplied: StackId,
+}
+
+fn delete(project: &Project, json: bool, branch_name: &str) -> anyhow::Result<()> {
+    match find_stack_id(project, branch_name)? {
+        Some(stack_id) => {
+            but_api::stack::remove_branch(project.id, stack_id, branch_name.to_owned())?;
+        }
+        None => {
+            let refname = Refname::from_str(&format!("refs/heads/{branch_name}"))?;
+            but_api::virtual_branches::delete_local_branch(
+                project.id,
+                refname,
+                branch_name.to_owned(),
+            )?;
+        }
+    }
+    if json {
+        let deleted = Deleted {
+            deleted: branch_name.to_owned(),
+        };
+        println!("{}", serde_json::to_string_pretty(&deleted)?);
+    } else {
+        println!("Deleted branch {}", branch_name.bold());
+    }
+    Ok(())
+}
+
+fn rename(project: &Project, json: bool, branch_name: &str, new_name: &str) -> anyhow::Result<()> {
+    let stack_id = stack_id_of(project, branch_name)?;
+    let new_name = but_api::virtual_branches::normalize_branch_name(new_name.to_owned())?;
+    but_api::stack::update_branch_name(
+        project.id,
+        stack_id,
+        branch_name.to_owned(),
+        new_name.clone(),
+    )?;
+    if json {
+        let renamed = Renamed {
+            renamed: branch_name.to_owned(),
+            new_name,
+        };
+        println!("{}", serde_json::to_string_pretty(&renamed)?);
+    } else {
+        println!(
+            "Renamed branch {} → {}",
+            branch_name.bold(),
+            new_name.green().bold()
+        );
+    }
+    Ok(())
+}
+
+fn apply(project: &Project, json: bool, branch_name: &str) -> anyhow::Result<()> {
+    let outcome = but_api::virtual_branches::create_virtual_branch_from_branch(
+        project.id,
+        branch_refname(project, branch_name)?,
+        None,
+        None,
+    )?;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&outcome)?);
+        return Ok(());
+    }
+    println!("Applied branch {}", branch_name.green().bold());
+    for stack_id in outcome.unapplied_stacks {
+        println!(
+            "  {} {}",
+            "Unapplied conflicting stack".yellow(),
+            stack_id.to_string().dimmed()
+        );
+    }
+    Ok(())
+}
+
+fn check_apply(project: &Project, json: bool, branch_name: &str) -> anyhow::Result<()> {
+    let preview = but_api::virtual_branches::preview_apply_branch(
+        project.id,
+        branch_refname(project, branch_name)?,
+    )?;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&preview)?);
+        return Ok(());
+    }
+    if preview.conflicts.is_empty() {
+        println!(
+            "Branch {} can be applied without conflicts",
+            branch_name.green().bold()
+        );
+        return Ok(());
+    }
+    println!(
+        "Branch {} conflicts with the workspace in {} path(s):",
+        branch_name.red().bold(),
+        preview.conflicts.len()
+    );
+    for conflict in &preview.conflicts {
+        let stacks = conflict
+            .stacks
+            .iter()
+            .map(|stack| stack.name.as_str())
+            .collect::<Vec<_>>();
+        println!(
+            "  {} {}",
+            conflict.path.to_str_lossy().yellow(),
+            format!("[{}]", stacks.join(", ")).dimmed()
+        );
+    }
+    Ok(())
+}
+
+/// Resolve `branch_name` to the full name of a local branch, or a remote tracking branch if there is no local one.
+fn branch_refname(project: &Project, branch_name: &str) -> anyhow::Result<Refname> {
+    let ctx = CommandContext::open(project, AppSettings::load_from_default_path_creating()?)?;
+    let repo = ctx.gix_repo()?;
+    let full_name = [
+        format!("refs/heads/{branch_name}"),
+        format!("refs/remotes/{branch_name}"),
+    ]
+    .into_iter()
+    .find(|name| matches!(repo.try_find_reference(name.as_str()), Ok(Some(_))))
+    .ok_or_else(|| anyhow::anyhow!("Could not find a local or remote branch '{branch_name}'"))?;
+    Ok(Refname::from_str(&full_name)?)
+}
+
+fn unapply(project: &Project, json: bool, branch_name: &str) -> anyhow::Result<()> {
+    let stack_id = stack_id_of(project, branch_name)?;
+    but_api::virtual_branches::unapply_stack(project.id, stack_id)?;
+    if json {
+        let unapplied = Unapplied {
+            unapplied: stack_id,
+        };
+        println!("{}", serde_json::to_string_pretty(&unapplied)?);
+    } else {
+        println!("Unapplied the stack of branch {}", branch_name.bold());
+    }
+    Ok(())
+}
+
+fn move_onto(project: &Project, json: bool, branch_name: &str, onto: &str) -> anyhow::Result<()> {
+    let source_stack_id = stack_id_of(project, branch_name)?;
+    let target_stack_id = stack_id_of(project, onto)?;
+    if source_stack_id == target_stack_id {
+        anyhow::bail!("'{branch_name}' and '{onto}' are already in the same stack");
+    }
+    let outcome = but_api::virtual_branches::move_branch(
+        project.id,
+        target_stack_id,
+        onto.to_owned(),
+        source_stack_id,
+        branch_name.to_owned(),
+    )?;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&outcome)?);
+    } else {
+        println!(
+            "Moved branch {} onto {}",
+            branch_name.bold(),
+            onto.green().bold()
+        );
+    }
+    Ok(())
+}
+
+/// The ID of the stack in the workspace that contains the branch named `branch_name`.
+fn stack_id_of(project: &Project, branch_name: &str) -> anyhow::Result<StackId> {
+    find_stack_id(project, branch_name)?
+        .ok_or_else(|| anyhow::anyhow!("Branch '{branch_name}' is not in the workspace"))
+}
+
+/// Like [`stack_id_of()`], but return `None` if no stack in the workspace contains the branch.
+fn find_stack_id(project: &Project, branch_name: &str) -> anyhow::Result<Option<StackId>> {
+    Ok(but_api::workspace::stacks(project.id, None)?
+        .into_iter()
+        .find(|stack| stack.heads.iter().any(|head| head.name == branch_name))
+        .and_then(|stack| stack.id))
 }
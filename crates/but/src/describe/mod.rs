@@ -204,7 +204,7 @@ fn get_commit_message_from_editor(
     Ok(message)
 }
 
-fn get_editor_command() -> Result<String> {
+pub(crate) fn get_editor_command() -> Result<String> {
     // Try $EDITOR first
     if let Ok(editor) = std::env::var("EDITOR") {
         return Ok(editor);
@@ -1,7 +1,7 @@
 use std::fmt::Display;
 
 use but_hunk_assignment::HunkAssignment;
-use but_workspace::StackId;
+use but_workspace::{HunkHeader, StackId};
 use gitbutler_command_context::CommandContext;
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
@@ -10,6 +10,11 @@ pub enum CliId {
         path: String,
         assignment: Option<StackId>,
     },
+    Hunk {
+        path: String,
+        hunk_header: HunkHeader,
+        assignment: Option<StackId>,
+    },
     CommittedFile {
         path: String,
         commit_oid: gix::ObjectId,
@@ -27,6 +32,7 @@ impl CliId {
     pub fn kind(&self) -> &'static str {
         match self {
             CliId::UncommittedFile { .. } => "an uncommitted file",
+            CliId::Hunk { .. } => "an uncommitted hunk",
             CliId::CommittedFile { .. } => "a committed file",
             CliId::Branch { .. } => "a branch",
             CliId::Commit { .. } => "a commit",
@@ -54,6 +60,15 @@ impl CliId {
         }
     }
 
+    /// The ID of the hunk of `assignment`, or `None` if the assignment is for a whole file without hunks.
+    pub fn hunk_from_assignment(assignment: &HunkAssignment) -> Option<Self> {
+        Some(CliId::Hunk {
+            path: assignment.path.clone(),
+            hunk_header: assignment.hunk_header?,
+            assignment: assignment.stack_id,
+        })
+    }
+
     pub fn committed_file(path: &str, commit_oid: gix::ObjectId) -> Self {
         CliId::CommittedFile {
             path: path.to_string(),
@@ -139,6 +154,10 @@ impl CliId {
                 .into_iter()
                 .filter(|id| id.matches_prefix(s))
                 .for_each(|id| cli_matches.push(id));
+            crate::status::all_hunks(ctx)?
+                .into_iter()
+                .filter(|id| id.matches_prefix(s))
+                .for_each(|id| cli_matches.push(id));
             crate::status::all_committed_files(ctx)?
                 .into_iter()
                 .filter(|id| id.matches_prefix(s))
@@ -203,6 +222,22 @@ impl Display for CliId {
                     write!(f, "{}", hash(path))
                 }
             }
+            CliId::Hunk {
+                path, hunk_header, ..
+            } => {
+                // The assignment isn't part of the ID so it stays the same when the hunk is moved.
+                let value = hash_with_len(
+                    &format!(
+                        "{path}@-{},{}+{},{}",
+                        hunk_header.old_start,
+                        hunk_header.old_lines,
+                        hunk_header.new_start,
+                        hunk_header.new_lines
+                    ),
+                    3,
+                );
+                write!(f, "{value}")
+            }
             CliId::CommittedFile { path, commit_oid } => {
                 let value = hash(&format!("{commit_oid}{path}"));
                 write!(f, "{value}")
@@ -224,6 +259,12 @@ impl Display for CliId {
 }
 
 pub(crate) fn hash(input: &str) -> String {
+    hash_with_len(input, 2)
+}
+
+/// Like [`hash()`], but with `len` characters, which must be at least 2.
+/// Hunks use longer IDs than other entities as there are many more of them.
+fn hash_with_len(input: &str, len: usize) -> String {
     let mut hash = 0u64;
     for byte in input.bytes() {
         hash = hash.wrapping_mul(31).wrapping_add(byte as u64);
@@ -234,9 +275,106 @@ pub(crate) fn hash(input: &str) -> String {
     let first_char = first_chars.chars().nth((hash % 20) as usize).unwrap();
     hash /= 20;
 
-    // Second character: 0-9,a-z (36 options)
-    let second_chars = "0123456789abcdefghijklmnopqrstuvwxyz";
-    let second_char = second_chars.chars().nth((hash % 36) as usize).unwrap();
+    // Following characters: 0-9,a-z (36 options)
+    let other_chars = "0123456789abcdefghijklmnopqrstuvwxyz";
+    let mut out = String::from(first_char);
+    for _ in 1..len {
+        out.push(other_chars.chars().nth((hash % 36) as usize).unwrap());
+        hash /= 36;
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use but_hunk_assignment::HunkAssignment;
+    use but_rules::testing::{assignment, header, hunk, stack};
+
+    use super::CliId;
+    use crate::rub::assign::hunk_assignment_requests;
+
+    /// Resolve `id` among the hunks of `assignments` the way the user would refer to them.
+    fn resolve(assignments: &[HunkAssignment], id: &str) -> Vec<CliId> {
+        assignments
+            .iter()
+            .filter_map(CliId::hunk_from_assignment)
+            .filter(|candidate| candidate.matches_prefix(id))
+            .collect()
+    }
+
+    #[test]
+    fn hunk_ids_round_trip() {
+        let assignments = [
+            hunk("file", 1, &[]),
+            hunk("file", 10, &[]),
+            hunk("other", 1, &[]),
+        ];
+        let ids = assignments
+            .iter()
+            .map(|a| CliId::hunk_from_assignment(a).expect("assignments have hunks"))
+            .collect::<Vec<_>>();
+        for id in &ids {
+            let s = id.to_string();
+            assert_eq!(s.len(), 3, "hunks have longer IDs than files");
+            assert!(id.matches(&s));
+            assert_eq!(resolve(&assignments, &s), [id.clone()]);
+        }
+        assert_ne!(ids[0].to_string(), ids[1].to_string());
+        assert_ne!(ids[0].to_string(), ids[2].to_string());
+    }
 
-    format!("{first_char}{second_char}")
+    #[test]
+    fn hunk_id_ignores_the_assignment() {
+        let unassigned = hunk("file", 1, &[]);
+        let assigned = HunkAssignment {
+            stack_id: Some(stack(1)),
+            ..unassigned.clone()
+        };
+        let before = CliId::hunk_from_assignment(&unassigned).unwrap();
+        let after = CliId::hunk_from_assignment(&assigned).unwrap();
+        assert_ne!(before, after);
+        assert_eq!(
+            before.to_string(),
+            after.to_string(),
+            "the ID stays the same when the hunk is moved to a stack"
+        );
+    }
+
+    #[test]
+    fn whole_files_have_no_hunk_id() {
+        assert_eq!(CliId::hunk_from_assignment(&assignment("file")), None);
+    }
+
+    #[test]
+    fn rub_hunk_onto_stack() {
+        let assignments = vec![
+            hunk("file", 1, &[]),
+            hunk("file", 10, &[]),
+            hunk("other", 10, &[]),
+        ];
+        let id = CliId::hunk_from_assignment(&assignments[1])
+            .unwrap()
+            .to_string();
+        let [
+            CliId::Hunk {
+                path, hunk_header, ..
+            },
+        ] = resolve(&assignments, &id).as_slice()
+        else {
+            panic!("the ID resolves to exactly one hunk");
+        };
+
+        let stack_id = stack(1);
+        let reqs =
+            hunk_assignment_requests(assignments.clone(), path, *hunk_header, Some(stack_id));
+        assert_eq!(reqs.len(), 1, "only the selected hunk is assigned");
+        assert_eq!(reqs[0].path_bytes, "file");
+        assert_eq!(reqs[0].hunk_header, Some(header(10)));
+        assert_eq!(reqs[0].stack_id, Some(stack_id));
+
+        assert!(
+            hunk_assignment_requests(assignments, path, header(5), Some(stack_id)).is_empty(),
+            "hunks that don't exist anymore can't be assigned"
+        );
+    }
 }
@@ -1,26 +1,46 @@
+use std::collections::BTreeMap;
+
+use but_core::RepositoryExt;
 use but_graph::VirtualBranchesTomlMetadata;
+use but_rebase::commit::SignatureStatus;
 use but_settings::AppSettings;
 use but_workspace::{
     StackId, StacksFilter,
-    ui::{StackDetails, StackEntry},
+    ui::{BranchDetails, CommitState, PushStatus, StackDetails, StackEntry},
 };
-use colored::Colorize;
+use colored::{ColoredString, Colorize};
 use gitbutler_command_context::CommandContext;
 use gitbutler_project::Project;
+use serde::Serialize;
 
 use crate::id::CliId;
 
-pub(crate) fn commit_graph(project: &Project, json: bool) -> anyhow::Result<()> {
+/// Print the stacks in the workspace with their branches and commits.
+///
+/// Signatures are only checked if GitButler signs commits or `verify_signatures` is set, as checking them
+/// runs `gpg` or `ssh-keygen` for each signed commit.
+pub(crate) fn commit_graph(
+    project: &Project,
+    json: bool,
+    verify_signatures: bool,
+) -> anyhow::Result<()> {
     let ctx = &mut CommandContext::open(project, AppSettings::load_from_default_path_creating()?)?;
     but_rules::process_rules(ctx).ok(); // TODO: this is doing double work (dependencies can be reused)
-    let stacks = stacks(ctx)?
+    let mut stacks = stacks(ctx)?
         .iter()
         .filter_map(|s| s.id.map(|id| stack_details(ctx, id).map(|d| (id, d))))
         .filter_map(Result::ok)
         .collect::<Vec<_>>();
+    let repo = ctx.gix_repo()?;
+    let signing_enabled = repo.git_settings()?.gitbutler_sign_commits.unwrap_or(false);
+    if signing_enabled || verify_signatures {
+        for (_, stack) in &mut stacks {
+            but_workspace::verify_signatures(&repo, &mut stack.branch_details);
+        }
+    }
 
     if json {
-        return output_json(stacks.into_iter().map(|(_, stack)| stack).collect());
+        return output_json(stacks.iter().map(|(_, stack)| stack));
     }
 
     let mut nesting = 0;
@@ -53,16 +73,37 @@ pub(crate) fn commit_graph(project: &Project, json: bool) -> anyhow::Result<()>
                 .to_string()
                 .underline()
                 .blue();
+            let review_str = match (branch.pr_number, &branch.review_id) {
+                (Some(pr_number), _) => format!("#{pr_number}").magenta(),
+                (None, Some(review_id)) => format!("review {review_id}").magenta(),
+                (None, None) => "".normal(),
+            };
             println!(
-                "{}{}{} [{}] {} {}",
+                "{}{}{} [{}] {} {} {} {}",
                 "│ ".repeat(nesting),
                 extra_space,
                 line,
                 branch.name.to_string().green().bold(),
                 id,
+                upstream_str(UpstreamState::of(branch)),
+                review_str,
                 mark.clone().unwrap_or_default()
             );
             mark = None; // show this on the first branch in the stack
+            for line in branch
+                .description
+                .as_deref()
+                .unwrap_or_default()
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+            {
+                println!(
+                    "{}{}│ {}",
+                    "│ ".repeat(nesting),
+                    extra_space,
+                    line.italic().dimmed()
+                );
+            }
             for (j, commit) in branch.upstream_commits.iter().enumerate() {
                 let time_string = chrono::DateTime::from_timestamp_millis(commit.created_at as i64)
                     .ok_or(anyhow::anyhow!("Could not parse timestamp"))?
@@ -102,26 +143,38 @@ pub(crate) fn commit_graph(project: &Project, json: bool) -> anyhow::Result<()>
                     None
                 };
                 let state_str = match commit.state {
-                    but_workspace::ui::CommitState::LocalOnly => "{local}".normal(),
-                    but_workspace::ui::CommitState::LocalAndRemote(_) => "{pushed}".cyan(),
-                    but_workspace::ui::CommitState::Integrated => "{integrated}".purple(),
+                    CommitState::LocalOnly => "{unpushed}".normal(),
+                    CommitState::LocalAndRemote(remote_id) if remote_id == commit.id => {
+                        "{pushed}".cyan()
+                    }
+                    CommitState::LocalAndRemote(_) => "{pushed, modified locally}".yellow(),
+                    CommitState::Integrated => "{integrated}".purple(),
                 };
                 let conflicted_str = if commit.has_conflicts {
                     "{conflicted}".red()
                 } else {
                     "".normal()
                 };
+                // Unsigned commits are only worth pointing out if they are expected to be signed.
+                let signature_str = match commit.signature {
+                    Some(SignatureStatus::Valid) => "{signed}".green(),
+                    Some(SignatureStatus::Unverified) => "{signed, unverified}".yellow(),
+                    Some(SignatureStatus::Invalid) => "{invalid signature}".red(),
+                    Some(SignatureStatus::Unsigned) if signing_enabled => "{unsigned}".yellow(),
+                    Some(SignatureStatus::Unsigned) | None => "".normal(),
+                };
                 let time_string = chrono::DateTime::from_timestamp_millis(commit.created_at as i64)
                     .ok_or(anyhow::anyhow!("Could not parse timestamp"))?
                     .format("%Y-%m-%d %H:%M:%S")
                     .to_string();
                 println!(
-                    "{}● {}{} {} {} {} {} {}",
+                    "{}● {}{} {} {} {} {} {} {}",
                     "│ ".repeat(nesting),
                     &commit.id.to_string()[..2].blue().underline(),
                     &commit.id.to_string()[2..7].blue(),
                     state_str,
                     conflicted_str,
+                    signature_str,
                     commit.author.name,
                     time_string.dimmed(),
                     mark.clone().unwrap_or_default()
@@ -212,7 +265,89 @@ pub(crate) fn stack_details(
     }
 }
 
-fn output_json(stacks: Vec<but_workspace::ui::StackDetails>) -> anyhow::Result<()> {
+/// How a branch relates to its remote tracking branch.
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase", tag = "type", content = "subject")]
+enum UpstreamState {
+    /// The branch was never pushed.
+    NoUpstream,
+    /// All commits of the branch are integrated into the target branch.
+    Integrated,
+    /// The branch and its remote tracking branch point to the same commits.
+    UpToDate,
+    /// The branch has commits that aren't on its remote tracking branch.
+    #[serde(rename_all = "camelCase")]
+    Ahead { ahead: usize },
+    /// The remote tracking branch has commits that aren't on the branch.
+    #[serde(rename_all = "camelCase")]
+    Behind { behind: usize },
+    /// Both the branch and its remote tracking branch have commits the other doesn't have.
+    #[serde(rename_all = "camelCase")]
+    Diverged { ahead: usize, behind: usize },
+}
+
+impl UpstreamState {
+    fn of(branch: &BranchDetails) -> Self {
+        if branch.push_status == PushStatus::Integrated {
+            return UpstreamState::Integrated;
+        }
+        if branch.remote_tracking_branch.is_none() {
+            return UpstreamState::NoUpstream;
+        }
+        // Commits that were pushed but rewritten locally have to be pushed again.
+        let ahead = branch
+            .commits
+            .iter()
+            .filter(|commit| match commit.state {
+                CommitState::LocalOnly => true,
+                CommitState::LocalAndRemote(remote_id) => remote_id != commit.id,
+                CommitState::Integrated => false,
+            })
+            .count();
+        let behind = branch.upstream_commits.len();
+        match (ahead, behind) {
+            (0, 0) => UpstreamState::UpToDate,
+            (ahead, 0) => UpstreamState::Ahead { ahead },
+            (0, behind) => UpstreamState::Behind { behind },
+            (ahead, behind) => UpstreamState::Diverged { ahead, behind },
+        }
+    }
+}
+
+fn upstream_str(state: UpstreamState) -> ColoredString {
+    match state {
+        UpstreamState::NoUpstream => "{not pushed}".dimmed(),
+        UpstreamState::Integrated => "{integrated}".purple(),
+        UpstreamState::UpToDate => "{up to date}".cyan(),
+        UpstreamState::Ahead { ahead } => format!("{{{ahead} ahead}}").yellow(),
+        UpstreamState::Behind { behind } => format!("{{{behind} behind}}").yellow(),
+        UpstreamState::Diverged { ahead, behind } => {
+            format!("{{diverged, {ahead} ahead, {behind} behind}}").red()
+        }
+    }
+}
+
+/// A stack as printed by `but log --json`, which is [`StackDetails`] with the upstream state of each branch.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct StackLog<'a> {
+    #[serde(flatten)]
+    details: &'a StackDetails,
+    /// The upstream state of each branch in `branchDetails`, by branch name.
+    upstream_states: BTreeMap<String, UpstreamState>,
+}
+
+fn output_json<'a>(stacks: impl Iterator<Item = &'a StackDetails>) -> anyhow::Result<()> {
+    let stacks = stacks
+        .map(|details| StackLog {
+            details,
+            upstream_states: details
+                .branch_details
+                .iter()
+                .map(|branch| (branch.name.to_string(), UpstreamState::of(branch)))
+                .collect(),
+        })
+        .collect::<Vec<_>>();
     let json_output = serde_json::to_string_pretty(&stacks)?;
     println!("{json_output}");
     Ok(())
@@ -1,5 +1,6 @@
 use anyhow::{Context, Result};
 
+mod absorb;
 mod args;
 use args::{Args, CommandName, Subcommands, actions, claude, cursor};
 use but_settings::AppSettings;
@@ -20,7 +21,11 @@ mod mcp;
 mod mcp_internal;
 mod metrics;
 mod oplog;
+mod push;
+mod reorder;
 mod rub;
+mod rules;
+mod split;
 mod status;
 
 #[tokio::main]
@@ -130,7 +135,7 @@ async fn main() -> Result<()> {
                 app_settings,
                 match cmd {
                     base::Subcommands::Check => CommandName::BaseCheck,
-                    base::Subcommands::Update => CommandName::BaseUpdate,
+                    base::Subcommands::Update(_) => CommandName::BaseUpdate,
                 },
                 props(start, &result),
             )
@@ -140,12 +145,26 @@ async fn main() -> Result<()> {
         Subcommands::Branch(branch::Platform { cmd }) => {
             let project = get_or_init_project(&args.current_dir)?;
             let result = branch::handle(cmd, &project, args.json);
-            metrics_if_configured(app_settings, CommandName::BranchNew, props(start, &result)).ok();
-            Ok(())
+            metrics_if_configured(
+                app_settings,
+                match cmd {
+                    branch::Subcommands::New { .. } => CommandName::BranchNew,
+                    branch::Subcommands::List { .. } => CommandName::BranchList,
+                    branch::Subcommands::Show { .. } => CommandName::BranchShow,
+                    branch::Subcommands::Delete { .. } => CommandName::BranchDelete,
+                    branch::Subcommands::Rename { .. } => CommandName::BranchRename,
+                    branch::Subcommands::Apply { .. } => CommandName::BranchApply,
+                    branch::Subcommands::Unapply { .. } => CommandName::BranchUnapply,
+                    branch::Subcommands::Move { .. } => CommandName::BranchMove,
+                },
+                props(start, &result),
+            )
+            .ok();
+            result
         }
-        Subcommands::Log => {
+        Subcommands::Log { verify_signatures } => {
             let project = get_or_init_project(&args.current_dir)?;
-            let result = log::commit_graph(&project, args.json);
+            let result = log::commit_graph(&project, args.json, *verify_signatures);
             metrics_if_configured(app_settings, CommandName::Log, props(start, &result)).ok();
             Ok(())
         }
@@ -194,6 +213,19 @@ async fn main() -> Result<()> {
             metrics_if_configured(app_settings, CommandName::Rub, props(start, &result)).ok();
             Ok(())
         }
+        Subcommands::Rules(rules::Platform { cmd }) => {
+            let project = get_or_init_project(&args.current_dir)?;
+            let result = rules::handle(cmd, &project, args.json);
+            metrics_if_configured(
+                app_settings,
+                match cmd {
+                    rules::Subcommands::Test => CommandName::RulesTest,
+                },
+                props(start, &result),
+            )
+            .ok();
+            result
+        }
         Subcommands::Commit {
             message,
             branch,
@@ -222,10 +254,57 @@ async fn main() -> Result<()> {
             metrics_if_configured(app_settings, CommandName::Describe, props(start, &result)).ok();
             result
         }
-        Subcommands::Oplog { since } => {
+        Subcommands::Split {
+            commit,
+            pieces,
+            messages,
+        } => {
+            let project = get_or_init_project(&args.current_dir)?;
+            let result = split::split_commit(&project, args.json, commit, pieces, messages);
+            metrics_if_configured(app_settings, CommandName::Split, props(start, &result)).ok();
+            result
+        }
+        Subcommands::Absorb { dry_run } => {
+            let project = get_or_init_project(&args.current_dir)?;
+            let result = absorb::handle(&project, args.json, *dry_run);
+            metrics_if_configured(app_settings, CommandName::Absorb, props(start, &result)).ok();
+            result
+        }
+        Subcommands::Reorder { branch_name } => {
+            let project = get_or_init_project(&args.current_dir)?;
+            let result = reorder::handle(&project, args.json, branch_name);
+            metrics_if_configured(app_settings, CommandName::Reorder, props(start, &result)).ok();
+            result
+        }
+        Subcommands::Oplog { since, cmd } => {
             let project = get_or_init_project(&args.current_dir)?;
-            let result = oplog::show_oplog(&project, args.json, since.as_deref());
-            metrics_if_configured(app_settings, CommandName::Oplog, props(start, &result)).ok();
+            let (result, command_name) = match cmd {
+                None => (
+                    oplog::show_oplog(&project, args.json, since.as_deref()),
+                    CommandName::Oplog,
+                ),
+                Some(oplog::Subcommands::Prune {
+                    keep,
+                    keep_days,
+                    keep_operation,
+                    dry_run,
+                }) => (
+                    oplog::prune(
+                        &project,
+                        args.json,
+                        *keep,
+                        *keep_days,
+                        keep_operation,
+                        *dry_run,
+                    ),
+                    CommandName::OplogPrune,
+                ),
+                Some(oplog::Subcommands::Diff { from, to }) => (
+                    oplog::diff(&project, args.json, from, to),
+                    CommandName::OplogDiff,
+                ),
+            };
+            metrics_if_configured(app_settings, command_name, props(start, &result)).ok();
             result
         }
         Subcommands::Restore { oplog_sha, force } => {
@@ -240,6 +319,46 @@ async fn main() -> Result<()> {
             metrics_if_configured(app_settings, CommandName::Undo, props(start, &result)).ok();
             result
         }
+        Subcommands::Redo => {
+            let project = get_or_init_project(&args.current_dir)?;
+            let result = oplog::redo_last_undo(&project, args.json);
+            metrics_if_configured(app_settings, CommandName::Redo, props(start, &result)).ok();
+            result
+        }
+        Subcommands::Push {
+            branch,
+            review,
+            reviewers,
+            hashtags,
+        } => {
+            let project = get_or_init_project(&args.current_dir)?;
+            let gerrit_options = but_gerrit::push::PushOptions {
+                topic: None,
+                reviewers: reviewers.clone(),
+                hashtags: hashtags.clone(),
+            };
+            let result = push::push(
+                &project,
+                args.json,
+                branch.as_deref(),
+                *review,
+                gerrit_options,
+            );
+            metrics_if_configured(app_settings, CommandName::Push, props(start, &result)).ok();
+            result
+        }
+        Subcommands::Pr { branch } => {
+            let project = get_or_init_project(&args.current_dir)?;
+            let result = push::push(
+                &project,
+                args.json,
+                branch.as_deref(),
+                true,
+                Default::default(),
+            );
+            metrics_if_configured(app_settings, CommandName::Pr, props(start, &result)).ok();
+            result
+        }
         Subcommands::Init { repo } => init::repo(&args.current_dir, args.json, *repo)
             .context("Failed to initialize GitButler project."),
     }
@@ -289,11 +408,11 @@ fn print_grouped_help() {
         ("Inspection".yellow(), vec!["log", "status"]),
         (
             "Stack Operation".yellow(),
-            vec!["commit", "rub", "new", "describe", "branch"],
+            vec!["commit", "rub", "new", "describe", "branch", "push", "pr"],
         ),
         (
             "Operation History".yellow(),
-            vec!["oplog", "undo", "restore"],
+            vec!["oplog", "undo", "redo", "restore"],
         ),
     ];
 
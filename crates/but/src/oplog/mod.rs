@@ -1,7 +1,44 @@
+use std::str::FromStr;
+
+use bstr::ByteSlice;
 use colored::Colorize;
-use gitbutler_oplog::entry::OperationKind;
+use gitbutler_oplog::{
+    diff::StackChangeKind,
+    entry::{OperationKind, SnapshotDetails},
+};
 use gitbutler_project::Project;
 
+#[derive(Debug, clap::Subcommand)]
+pub enum Subcommands {
+    /// Remove old snapshots from the operation history so their data can be garbage collected.
+    ///
+    /// Snapshots are kept if any of the rules retains them. Rules that aren't given
+    /// are taken from the `oplog` section of the app settings.
+    Prune {
+        /// Keep this many of the most recent snapshots.
+        #[clap(long)]
+        keep: Option<usize>,
+        /// Keep all snapshots created within this many days.
+        #[clap(long)]
+        keep_days: Option<u64>,
+        /// Keep all snapshots of this operation, like `RestoreFromSnapshot`. Can be repeated.
+        #[clap(long, value_name = "OPERATION")]
+        keep_operation: Vec<String>,
+        /// Only show how many snapshots would be pruned.
+        #[clap(long)]
+        dry_run: bool,
+    },
+    /// Show what changed between two snapshots, including the worktree, stacks and conflicts.
+    ///
+    /// The snapshots don't have to follow each other, which shows what a series of operations did.
+    Diff {
+        /// Oplog SHA of the snapshot to compare from, at least 7 characters.
+        from: String,
+        /// Oplog SHA of the snapshot to compare to, at least 7 characters.
+        to: String,
+    },
+}
+
 pub(crate) fn show_oplog(project: &Project, json: bool, since: Option<&str>) -> anyhow::Result<()> {
     let snapshots = if let Some(since_sha) = since {
         // Get all snapshots first to find the starting point
@@ -243,3 +280,204 @@ pub(crate) fn undo_last_operation(project: &Project, _json: bool) -> anyhow::Res
 
     Ok(())
 }
+
+pub(crate) fn redo_last_undo(project: &Project, json: bool) -> anyhow::Result<()> {
+    let Some(redo_snapshot) = but_api::undo::redo_snapshot(project.id)? else {
+        if json {
+            println!("{}", serde_json::json!({ "redone": null }));
+        } else {
+            println!("{}", "Nothing to redo.".yellow());
+        }
+        return Ok(());
+    };
+
+    let undo_time = chrono::DateTime::from_timestamp(redo_snapshot.created_at.seconds(), 0)
+        .ok_or(anyhow::anyhow!("Could not parse timestamp"))?
+        .format("%Y-%m-%d %H:%M:%S")
+        .to_string();
+    if !json {
+        println!("{}", "Redoing operation...".blue().bold());
+        println!(
+            "  Restoring the state before the undo at {}",
+            undo_time.dimmed()
+        );
+    }
+
+    let Some(snapshot_id) = but_api::undo::redo(project.id)? else {
+        anyhow::bail!("Nothing to redo, the oplog changed in the meantime.");
+    };
+
+    if json {
+        println!(
+            "{}",
+            serde_json::json!({
+                "redone": redo_snapshot.commit_id.to_string(),
+                "snapshot": snapshot_id,
+            })
+        );
+        return Ok(());
+    }
+    let redone_commit_short = format!(
+        "{}{}",
+        &redo_snapshot.commit_id.to_string()[..7].blue().underline(),
+        &redo_snapshot.commit_id.to_string()[7..12].blue().dimmed()
+    );
+    println!(
+        "{} Redo completed successfully! Restored to snapshot: {}",
+        "✓".green().bold(),
+        redone_commit_short
+    );
+    Ok(())
+}
+
+pub(crate) fn prune(
+    project: &Project,
+    json: bool,
+    keep: Option<usize>,
+    keep_days: Option<u64>,
+    keep_operations: &[String],
+    dry_run: bool,
+) -> anyhow::Result<()> {
+    let keep_operations = if keep_operations.is_empty() {
+        None
+    } else {
+        Some(
+            keep_operations
+                .iter()
+                .map(|name| {
+                    OperationKind::from_str(name)
+                        .map_err(|_| anyhow::anyhow!("Unknown operation: {name}"))
+                })
+                .collect::<anyhow::Result<Vec<_>>>()?,
+        )
+    };
+    let outcome =
+        but_api::undo::prune_snapshots(project.id, keep, keep_days, keep_operations, dry_run)?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&outcome)?);
+        return Ok(());
+    }
+
+    if outcome.pruned == 0 {
+        println!(
+            "Nothing to prune, all {} snapshots are retained.",
+            outcome.kept
+        );
+    } else if dry_run {
+        println!(
+            "Would prune {} snapshots and keep {}.",
+            outcome.pruned.to_string().yellow(),
+            outcome.kept.to_string().green()
+        );
+    } else {
+        println!(
+            "{} Pruned {} snapshots and kept {}.",
+            "✓".green().bold(),
+            outcome.pruned.to_string().yellow(),
+            outcome.kept.to_string().green()
+        );
+    }
+    Ok(())
+}
+
+pub(crate) fn diff(project: &Project, json: bool, from: &str, to: &str) -> anyhow::Result<()> {
+    let repo = gix::open(&project.path)?;
+    let resolve = |sha: &str| -> anyhow::Result<String> {
+        if sha.len() < 7 {
+            anyhow::bail!("Oplog SHA must be at least 7 characters long");
+        }
+        let commit = repo
+            .rev_parse_single(sha)
+            .ok()
+            .and_then(|id| id.object().ok()?.try_into_commit().ok())
+            .ok_or_else(|| anyhow::anyhow!("No oplog snapshot found matching '{}'", sha))?;
+        let message = commit.message_raw()?.to_str_lossy();
+        if SnapshotDetails::from_str(&message).is_err() {
+            anyhow::bail!("Commit '{}' isn't an oplog snapshot", sha);
+        }
+        Ok(commit.id.to_string())
+    };
+    let comparison = but_api::undo::compare_snapshots(project.id, resolve(from)?, resolve(to)?)?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&comparison)?);
+        return Ok(());
+    }
+
+    println!(
+        "{} {}..{}",
+        "Changes between snapshots".blue().bold(),
+        comparison.from.to_string()[..7].blue().underline(),
+        comparison.to.to_string()[..7].blue().underline()
+    );
+    if comparison.worktree_changes.is_empty()
+        && comparison.stacks.is_empty()
+        && comparison.conflicts.is_none()
+    {
+        println!("{}", "No differences.".dimmed());
+        return Ok(());
+    }
+
+    if !comparison.worktree_changes.is_empty() {
+        println!();
+        println!("{}", "Worktree:".bold());
+        for change in &comparison.worktree_changes {
+            let status = match &change.status {
+                but_core::ui::TreeStatus::Addition { .. } => "added:".green(),
+                but_core::ui::TreeStatus::Modification { .. } => "modified:".yellow(),
+                but_core::ui::TreeStatus::Deletion { .. } => "deleted:".red(),
+                but_core::ui::TreeStatus::Rename { .. } => "renamed:".yellow(),
+            };
+            println!("  {:<10} {}", status, change.path_bytes.to_str_lossy());
+        }
+    }
+
+    if !comparison.stacks.is_empty() {
+        println!();
+        println!("{}", "Stacks:".bold());
+        for stack in &comparison.stacks {
+            let kind = match stack.kind {
+                StackChangeKind::Added => "added".green().to_string(),
+                StackChangeKind::Removed => "removed".red().to_string(),
+                StackChangeKind::Reordered {
+                    from_position,
+                    to_position,
+                } => format!("moved from position {from_position} to {to_position}")
+                    .yellow()
+                    .to_string(),
+                StackChangeKind::Modified => "modified".yellow().to_string(),
+            };
+            println!("  {} ({})", stack.name.bold(), kind);
+            for head in &stack.heads {
+                let short_target = |target: &Option<String>| {
+                    target
+                        .as_deref()
+                        .map(|t| t.chars().take(7).collect::<String>())
+                        .unwrap_or_else(|| "-".to_string())
+                };
+                println!(
+                    "    {}: {} → {}",
+                    head.name,
+                    short_target(&head.before).dimmed(),
+                    short_target(&head.after).blue()
+                );
+            }
+        }
+    }
+
+    if let Some(conflicts) = &comparison.conflicts {
+        println!();
+        println!("{}", "Conflicts:".bold());
+        let describe = |state: &gitbutler_oplog::diff::ConflictState| {
+            if state.paths.is_empty() {
+                "none".to_string()
+            } else {
+                state.paths.join(", ")
+            }
+        };
+        println!("  before: {}", describe(&conflicts.before).dimmed());
+        println!("  after:  {}", describe(&conflicts.after));
+    }
+    Ok(())
+}
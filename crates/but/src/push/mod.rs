@@ -0,0 +1,255 @@
+use anyhow::bail;
+use bstr::ByteSlice;
+use but_core::RepositoryExt;
+use but_gerrit::push::{PushOptions, PushedChange};
+use but_settings::AppSettings;
+use but_workspace::{StackId, ui::StackDetails};
+use colored::Colorize;
+use gitbutler_command_context::CommandContext;
+use gitbutler_forge::forge::ForgeName;
+use gitbutler_project::Project;
+use serde::Serialize;
+
+use crate::id::CliId;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PushOutput {
+    remote: String,
+    branches: Vec<PushedBranch>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PushedBranch {
+    name: String,
+    remote_ref: String,
+    /// The review template that was used to fill in the description of the branch, if any.
+    review_template: Option<String>,
+    /// The forge's web page comparing the branch to the branch it is based on, if the forge is known.
+    compare_url: Option<String>,
+    /// The Gerrit changes that were created or updated by the push, in Gerrit mode.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    gerrit_changes: Vec<PushedChange>,
+}
+
+/// Push all branches of the stack containing `branch_hint` (or the only stack) to the push remote.
+///
+/// Branches are pushed bottom-up, so each dependent branch finds its base on the remote already.
+/// If `branch_hint` names a branch in the middle of a stack, the branches above it are not pushed.
+/// With `review`, branches without a description get the forge's review template as description.
+/// In Gerrit mode, `gerrit_options` are applied to all pushed changes.
+pub(crate) fn push(
+    project: &Project,
+    json: bool,
+    branch_hint: Option<&str>,
+    review: bool,
+    gerrit_options: PushOptions,
+) -> anyhow::Result<()> {
+    let mut ctx = CommandContext::open(project, AppSettings::load_from_default_path_creating()?)?;
+    let gerrit_mode = ctx
+        .gix_repo()?
+        .git_settings()?
+        .gitbutler_gerrit_mode
+        .unwrap_or(false);
+    if !gerrit_mode && (!gerrit_options.reviewers.is_empty() || !gerrit_options.hashtags.is_empty())
+    {
+        bail!("Reviewers and hashtags can only be set when pushing to Gerrit.");
+    }
+    let stacks = crate::log::stacks(&ctx)?
+        .iter()
+        .filter_map(|s| {
+            s.id.map(|id| crate::log::stack_details(&ctx, id).map(|d| (id, d)))
+        })
+        .filter_map(Result::ok)
+        .collect::<Vec<_>>();
+
+    let (stack_id, stack, limit) = select_stack_and_branch(&mut ctx, &stacks, branch_hint)?;
+
+    if !json {
+        println!("🚀 Pushing {}...", limit.green().bold());
+    }
+    let mut result = but_api::stack::push_stack(
+        project.id,
+        stack_id,
+        true,
+        false,
+        limit,
+        true,
+        gerrit_mode.then_some(gerrit_options),
+    )?;
+
+    let repo_info = but_api::forge::forge_repo_info(project.id)?;
+    let review_template = if review {
+        // The forge configured for the project takes precedence over the one detected from its remotes.
+        let forge = project
+            .forge_override
+            .as_deref()
+            .and_then(ForgeName::from_name)
+            .or_else(|| repo_info.as_ref().map(|info| info.forge.clone()));
+        match forge {
+            Some(forge) => review_template(project, forge)?,
+            None => {
+                if !json {
+                    println!(
+                        "{}",
+                        "Not setting review templates as the forge of the project is unknown."
+                            .yellow()
+                    );
+                }
+                None
+            }
+        }
+    } else {
+        None
+    };
+    let target_branch = gitbutler_stack::VirtualBranchesHandle::new(ctx.project().gb_dir())
+        .get_default_target()?
+        .branch
+        .branch()
+        .to_owned();
+
+    let mut branches = Vec::new();
+    for (name, remote_ref) in result.branch_to_remote {
+        let mut used_template = None;
+        if let Some((template_path, template)) = &review_template {
+            let has_description = stack
+                .branch_details
+                .iter()
+                .find(|b| b.name == name)
+                .and_then(|b| b.description.as_ref())
+                .is_some_and(|d| !d.trim().is_empty());
+            if !has_description {
+                but_api::stack::update_branch_description(
+                    project.id,
+                    stack_id,
+                    name.clone(),
+                    Some(template.clone()),
+                )?;
+                used_template = Some(template_path.clone());
+            }
+        }
+        let gerrit_changes = result
+            .branch_to_gerrit_changes
+            .iter_mut()
+            .find(|(branch, _)| *branch == name)
+            .map(|(_, changes)| std::mem::take(changes))
+            .unwrap_or_default();
+        // Gerrit reviews changes, not branches, so there is nothing to compare.
+        let compare_url = repo_info.as_ref().filter(|_| !gerrit_mode).map(|info| {
+            let base = base_branch_name(stack, &name).unwrap_or(&target_branch);
+            info.compare_url(base, &name)
+        });
+        branches.push(PushedBranch {
+            name,
+            remote_ref: remote_ref.to_string(),
+            review_template: used_template,
+            compare_url,
+            gerrit_changes,
+        });
+    }
+
+    let output = PushOutput {
+        remote: result.remote,
+        branches,
+    };
+    if json {
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(());
+    }
+
+    if output.branches.is_empty() {
+        println!("Nothing to push, all branches are up to date or integrated.");
+        return Ok(());
+    }
+    for branch in &output.branches {
+        println!("  {} → {}", branch.name.green(), branch.remote_ref.blue());
+        if let Some(template) = &branch.review_template {
+            println!("    description set from {}", template.dimmed());
+        }
+        if let Some(url) = &branch.compare_url {
+            println!("    {}", url.underline());
+        }
+        for change in &branch.gerrit_changes {
+            let new = if change.is_new { " [NEW]" } else { "" };
+            println!(
+                "    {} {}{}",
+                change.url.underline(),
+                change.subject,
+                new.green()
+            );
+        }
+    }
+    println!("Pushed to {}", output.remote.bold());
+    Ok(())
+}
+
+/// Find the stack to push and the name of the top-most branch to include.
+fn select_stack_and_branch<'a>(
+    ctx: &mut CommandContext,
+    stacks: &'a [(StackId, StackDetails)],
+    branch_hint: Option<&str>,
+) -> anyhow::Result<(StackId, &'a StackDetails, String)> {
+    let Some(hint) = branch_hint else {
+        return match stacks {
+            [] => bail!("No stacks found. Create a stack first with 'but branch new <name>'."),
+            [(id, stack)] => {
+                let top = stack
+                    .branch_details
+                    .first()
+                    .ok_or_else(|| anyhow::anyhow!("No branches found in stack"))?;
+                Ok((*id, stack, top.name.to_string()))
+            }
+            _ => bail!(
+                "Multiple stacks found. Specify the branch to push, e.g. `but push <branch>`."
+            ),
+        };
+    };
+
+    let mut name = hint.to_owned();
+    if !stacks
+        .iter()
+        .any(|(_, s)| s.branch_details.iter().any(|b| b.name == hint))
+    {
+        let branch_ids = CliId::from_str(ctx, hint)?
+            .into_iter()
+            .filter_map(|id| match id {
+                CliId::Branch { name } => Some(name),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        name = match branch_ids.as_slice() {
+            [] => bail!("Branch '{}' not found", hint),
+            [name] => name.clone(),
+            _ => bail!("Branch '{}' is ambiguous: {:?}", hint, branch_ids),
+        };
+    }
+
+    stacks
+        .iter()
+        .find(|(_, s)| s.branch_details.iter().any(|b| b.name == name))
+        .map(|(id, stack)| (*id, stack, name.clone()))
+        .ok_or_else(|| anyhow::anyhow!("Branch '{}' not found in any stack", name))
+}
+
+/// The name of the branch `name` is stacked on, or `None` if it's the bottom-most branch of the `stack`.
+fn base_branch_name<'a>(stack: &'a StackDetails, name: &str) -> Option<&'a str> {
+    let mut branches = stack.branch_details.iter().skip_while(|b| b.name != name);
+    branches.next()?;
+    branches.next().map(|b| b.name.to_str().unwrap_or_default())
+}
+
+/// Read the first review template available for `forge`, along with its path.
+fn review_template(
+    project: &Project,
+    forge: ForgeName,
+) -> anyhow::Result<Option<(String, String)>> {
+    let Some(path) = but_api::forge::pr_templates(project.id, forge.clone())?
+        .into_iter()
+        .next()
+    else {
+        return Ok(None);
+    };
+    let template = but_api::forge::pr_template(project.id, path.clone().into(), forge)?;
+    Ok(Some((path, template)))
+}
@@ -0,0 +1,215 @@
+use std::collections::HashMap;
+
+use but_rebase::RebaseStep;
+use but_settings::AppSettings;
+use colored::Colorize;
+use gitbutler_branch_actions::reorder::{StackOrder, commits_order};
+use gitbutler_command_context::CommandContext;
+use gitbutler_oxidize::OidExt;
+use gitbutler_project::Project;
+use gitbutler_stack::VirtualBranchesHandle;
+
+use crate::rub::assign::branch_name_to_stack_id;
+
+const HELP: &str = "
+# Commands:
+# p, pick <commit> = use the commit
+# e, edit <commit> = use the commit, and stop to edit it in edit mode
+# s, squash <commit> = meld the commit into the previous one, keeping both messages
+# d, drop <commit> = remove the commit
+# x, exec <command> = run the command against the commit above, abort if it fails
+# b, branch <name> = the branch ends at the commit above
+#
+# Lines are applied from top to bottom, the oldest commit comes first.
+# Every commit and branch of the stack must appear exactly once, and the
+# branches must stay in their order. Commits can be moved across branches.
+#
+# An empty list aborts the operation.
+";
+
+pub(crate) fn handle(project: &Project, json: bool, branch_name: &str) -> anyhow::Result<()> {
+    let ctx = &mut CommandContext::open(project, AppSettings::load_from_default_path_creating()?)?;
+    let stack_id = branch_name_to_stack_id(ctx, Some(branch_name))?
+        .ok_or_else(|| anyhow::anyhow!("Could not find stack for branch {branch_name}"))?;
+    let vb_state = VirtualBranchesHandle::new(ctx.project().gb_dir());
+    let stack = vb_state.get_stack_in_workspace(stack_id)?;
+    let current_order = commits_order(ctx, &stack)?;
+
+    let mut messages = HashMap::new();
+    for series in &current_order.series {
+        for commit_id in &series.commit_ids {
+            let commit = ctx.repo().find_commit(*commit_id)?;
+            messages.insert(
+                commit_id.to_gix(),
+                String::from_utf8_lossy(commit.message_bytes()).into_owned(),
+            );
+        }
+    }
+
+    let todo = todo_list(&current_order, &messages);
+    let edited = edit_todo(&format!(
+        "{todo}\n# Rebase the stack of branch '{branch_name}'.\n{HELP}"
+    ))?;
+    let edited = edited
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .collect::<Vec<_>>();
+    if edited.is_empty() {
+        anyhow::bail!("Aborting due to empty todo list");
+    }
+    if edited == todo.lines().map(str::trim).collect::<Vec<_>>() {
+        if json {
+            println!("{}", serde_json::to_string_pretty(&current_order)?);
+        } else {
+            println!("{}", "Nothing to do.".dimmed());
+        }
+        return Ok(());
+    }
+
+    let steps = parse_todo(&edited, &messages)?;
+    let edit_commit = steps.iter().find_map(|step| match step {
+        RebaseStep::Edit { commit_id } => Some(*commit_id),
+        _ => None,
+    });
+    let output = gitbutler_branch_actions::rebase_stack(ctx, stack_id, steps)?;
+    if let Some(commit_id) = edit_commit.and_then(|id| output.rewritten_commit(id)) {
+        but_api::modes::enter_edit_mode(project.id, commit_id.to_string(), stack_id)?;
+        if json {
+            println!(
+                "{}",
+                serde_json::json!({ "editing": commit_id.to_string() })
+            );
+        } else {
+            println!(
+                "Stopped to edit {}. Change the worktree, then save or abort the edit in the GitButler app.",
+                commit_id.to_hex_with_len(7).to_string().blue()
+            );
+        }
+        return Ok(());
+    }
+
+    let stack = vb_state.get_stack_in_workspace(stack_id)?;
+    let new_order = commits_order(ctx, &stack)?;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&new_order)?);
+        return Ok(());
+    }
+    println!("Rebased the stack of branch {}", branch_name.green().bold());
+    for series in &new_order.series {
+        println!(
+            "  {} {}",
+            series.name.green(),
+            format!("{} commit(s)", series.commit_ids.len()).dimmed()
+        );
+    }
+    Ok(())
+}
+
+fn edit_todo(template: &str) -> anyhow::Result<String> {
+    let editor = crate::describe::get_editor_command()?;
+    let temp_file = std::env::temp_dir().join(format!("but_reorder_todo_{}", std::process::id()));
+    std::fs::write(&temp_file, template)?;
+
+    let status = std::process::Command::new(&editor)
+        .arg(&temp_file)
+        .status()?;
+    if !status.success() {
+        anyhow::bail!("Editor exited with non-zero status");
+    }
+
+    let content = std::fs::read_to_string(&temp_file)?;
+    std::fs::remove_file(&temp_file).ok(); // Best effort cleanup
+    Ok(content)
+}
+
+/// Render `order`, whose series and commits are listed from the top, as todo list with the oldest commit first.
+fn todo_list(order: &StackOrder, messages: &HashMap<gix::ObjectId, String>) -> String {
+    let mut todo = String::new();
+    for series in order.series.iter().rev() {
+        for commit_id in series.commit_ids.iter().rev() {
+            let commit_id = commit_id.to_gix();
+            let title = messages
+                .get(&commit_id)
+                .and_then(|message| message.lines().next())
+                .unwrap_or_default();
+            todo.push_str(&format!("pick {} {title}\n", commit_id.to_hex_with_len(7)));
+        }
+        todo.push_str(&format!("branch {}\n", series.name));
+    }
+    todo
+}
+
+/// Turn the `lines` of an edited todo list into rebase steps, resolving abbreviated ids among the commits in `messages`.
+fn parse_todo(
+    lines: &[&str],
+    messages: &HashMap<gix::ObjectId, String>,
+) -> anyhow::Result<Vec<RebaseStep>> {
+    let mut steps = Vec::new();
+    // The message of the commit that a squash would go into.
+    let mut preceding_message: Option<String> = None;
+    for line in lines {
+        let (command, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        let rest = rest.trim();
+        let argument = rest
+            .split_whitespace()
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("Missing argument in line '{line}'"))?;
+        match command {
+            "p" | "pick" => {
+                let commit_id = resolve_commit(argument, messages)?;
+                preceding_message = Some(messages[&commit_id].clone());
+                steps.push(RebaseStep::Pick {
+                    commit_id,
+                    new_message: None,
+                });
+            }
+            "e" | "edit" => {
+                let commit_id = resolve_commit(argument, messages)?;
+                preceding_message = Some(messages[&commit_id].clone());
+                steps.push(RebaseStep::Edit { commit_id });
+            }
+            "d" | "drop" => steps.push(RebaseStep::Drop {
+                commit_id: resolve_commit(argument, messages)?,
+            }),
+            "x" | "exec" => steps.push(RebaseStep::Exec {
+                command: rest.to_owned(),
+            }),
+            "s" | "squash" => {
+                let commit_id = resolve_commit(argument, messages)?;
+                let Some(message) = preceding_message.take() else {
+                    anyhow::bail!("Can't squash {argument} as no commit was picked before it");
+                };
+                let message = format!(
+                    "{}\n\n{}",
+                    message.trim_end(),
+                    messages[&commit_id].trim_end()
+                );
+                steps.push(RebaseStep::SquashIntoPreceding {
+                    commit_id,
+                    new_message: Some(message.clone().into()),
+                });
+                preceding_message = Some(message);
+            }
+            "b" | "branch" => steps.push(RebaseStep::Reference(but_core::Reference::Virtual(
+                argument.to_owned(),
+            ))),
+            _ => anyhow::bail!("Unknown command '{command}' in line '{line}'"),
+        }
+    }
+    Ok(steps)
+}
+
+fn resolve_commit(
+    prefix: &str,
+    messages: &HashMap<gix::ObjectId, String>,
+) -> anyhow::Result<gix::ObjectId> {
+    let mut matches = messages
+        .keys()
+        .filter(|id| id.to_string().starts_with(prefix));
+    match (matches.next(), matches.next()) {
+        (Some(id), None) => Ok(*id),
+        (None, _) => anyhow::bail!("Commit '{prefix}' isn't part of the stack"),
+        (Some(_), Some(_)) => anyhow::bail!("Commit '{prefix}' is ambiguous"),
+    }
+}
@@ -1,6 +1,7 @@
+use anyhow::bail;
 use but_hunk_assignment::HunkAssignment;
 use but_workspace::{
-    DiffSpec, StackId,
+    DiffSpec, HunkHeader, StackId,
     commit_engine::{self, CreateCommitOutcome},
 };
 use colored::Colorize;
@@ -34,6 +35,38 @@ pub(crate) fn file_to_commit(
     Ok(())
 }
 
+pub(crate) fn hunk_to_commit(
+    ctx: &mut CommandContext,
+    path: &str,
+    hunk_header: HunkHeader,
+    stack_id: Option<StackId>,
+    oid: &ObjectId,
+) -> anyhow::Result<()> {
+    let diff_specs: Vec<DiffSpec> = wt_assignments(ctx)?
+        .into_iter()
+        .filter(|assignment| {
+            assignment.stack_id == stack_id
+                && assignment.path == path
+                && assignment.hunk_header == Some(hunk_header)
+        })
+        .map(|assignment| assignment.into())
+        .collect();
+    if diff_specs.is_empty() {
+        bail!("The hunk in {path} doesn't exist anymore. Run 'but status' to refresh the IDs.");
+    }
+
+    let mut guard = ctx.project().exclusive_worktree_access();
+    let new_commit = amend_diff_specs(ctx, diff_specs, stack_id, *oid, guard.write_permission())?
+        .new_commit
+        .map(|c| {
+            let s = c.to_string();
+            format!("{}{}", s[..2].blue().underline(), s[2..7].blue())
+        })
+        .unwrap_or_default();
+    println!("Amended hunk in {} → {}", path.bold(), new_commit);
+    Ok(())
+}
+
 pub(crate) fn assignments_to_commit(
     ctx: &mut CommandContext,
     branch_name: Option<&str>,
@@ -74,7 +107,7 @@ fn wt_assignments(ctx: &mut CommandContext) -> anyhow::Result<Vec<HunkAssignment
     Ok(assignments)
 }
 
-fn amend_diff_specs(
+pub(crate) fn amend_diff_specs(
     ctx: &mut CommandContext,
     diff_specs: Vec<DiffSpec>,
     stack_id: Option<StackId>,
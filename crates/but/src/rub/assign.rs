@@ -1,5 +1,6 @@
-use but_hunk_assignment::HunkAssignmentRequest;
-use but_workspace::StackId;
+use anyhow::bail;
+use but_hunk_assignment::{HunkAssignment, HunkAssignmentRequest};
+use but_workspace::{HunkHeader, StackId};
 use colored::Colorize;
 use gitbutler_command_context::CommandContext;
 
@@ -20,6 +21,52 @@ pub(crate) fn assign_file_to_branch(
     Ok(())
 }
 
+pub(crate) fn assign_hunk_to_branch(
+    ctx: &mut CommandContext,
+    path: &str,
+    hunk_header: HunkHeader,
+    branch_name: Option<&str>,
+) -> anyhow::Result<()> {
+    let stack_id = branch_name_to_stack_id(ctx, branch_name)?;
+    let changes =
+        but_core::diff::ui::worktree_changes_by_worktree_dir(ctx.project().path.clone())?.changes;
+    let (assignments, _assignments_error) =
+        but_hunk_assignment::assignments_with_fallback(ctx, false, Some(changes.clone()), None)?;
+    let reqs = hunk_assignment_requests(assignments, path, hunk_header, stack_id);
+    if reqs.is_empty() {
+        bail!("The hunk in {path} doesn't exist anymore. Run 'but status' to refresh the IDs.");
+    }
+    do_assignments(ctx, reqs)?;
+    match branch_name {
+        Some(branch_name) => println!(
+            "Assigned hunk in {} → {}.",
+            path.bold(),
+            format!("[{branch_name}]").green()
+        ),
+        None => println!("Unassigned hunk in {}", path.bold()),
+    }
+    Ok(())
+}
+
+/// The requests to assign the hunk with `hunk_header` in `path` to `stack_id`, or to unassign it if `None`,
+/// which are empty if the hunk isn't one of `assignments`.
+pub(crate) fn hunk_assignment_requests(
+    assignments: Vec<HunkAssignment>,
+    path: &str,
+    hunk_header: HunkHeader,
+    stack_id: Option<StackId>,
+) -> Vec<HunkAssignmentRequest> {
+    assignments
+        .into_iter()
+        .filter(|a| a.path == path && a.hunk_header == Some(hunk_header))
+        .map(|a| HunkAssignmentRequest {
+            hunk_header: a.hunk_header,
+            path_bytes: a.path_bytes,
+            stack_id,
+        })
+        .collect()
+}
+
 pub(crate) fn unassign_file(ctx: &mut CommandContext, path: &str) -> anyhow::Result<()> {
     let reqs = to_assignment_request(ctx, path, None)?;
     do_assignments(ctx, reqs)?;
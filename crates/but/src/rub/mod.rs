@@ -4,12 +4,13 @@ use colored::Colorize;
 use gitbutler_command_context::CommandContext;
 use gitbutler_project::Project;
 mod amend;
-mod assign;
+pub(crate) mod assign;
 mod commits;
 mod move_commit;
 mod squash;
 mod undo;
 use crate::id::CliId;
+pub(crate) use amend::amend_diff_specs;
 pub(crate) use assign::branch_name_to_stack_id;
 use gitbutler_oplog::{
     OplogExt,
@@ -42,6 +43,44 @@ pub(crate) fn handle(
                 create_snapshot(ctx, project, OperationKind::MoveHunk);
                 assign::assign_file_to_branch(ctx, path, name)?;
             }
+            (
+                CliId::Hunk {
+                    path, hunk_header, ..
+                },
+                CliId::Unassigned,
+            ) => {
+                create_snapshot(ctx, project, OperationKind::MoveHunk);
+                assign::assign_hunk_to_branch(ctx, path, *hunk_header, None)?;
+            }
+            (
+                CliId::Hunk {
+                    path,
+                    hunk_header,
+                    assignment,
+                },
+                CliId::Commit { oid },
+            ) => {
+                create_snapshot(ctx, project, OperationKind::AmendCommit);
+                amend::hunk_to_commit(ctx, path, *hunk_header, *assignment, oid)?;
+            }
+            (
+                CliId::Hunk {
+                    path, hunk_header, ..
+                },
+                CliId::Branch { name },
+            ) => {
+                create_snapshot(ctx, project, OperationKind::MoveHunk);
+                assign::assign_hunk_to_branch(ctx, path, *hunk_header, Some(name))?;
+            }
+            (
+                CliId::Hunk { .. },
+                CliId::Hunk { .. } | CliId::UncommittedFile { .. } | CliId::CommittedFile { .. },
+            ) => {
+                bail!(makes_no_sense_error(&source, &target))
+            }
+            (_, CliId::Hunk { .. }) => {
+                bail!(makes_no_sense_error(&source, &target))
+            }
             (CliId::Unassigned, CliId::UncommittedFile { .. }) => {
                 bail!(makes_no_sense_error(&source, &target))
             }
@@ -346,7 +385,11 @@ fn parse_list(ctx: &mut CommandContext, source: &str) -> anyhow::Result<Vec<CliI
     Ok(result)
 }
 
-fn create_snapshot(ctx: &mut CommandContext, project: &Project, operation: OperationKind) {
+pub(crate) fn create_snapshot(
+    ctx: &mut CommandContext,
+    project: &Project,
+    operation: OperationKind,
+) {
     let mut guard = project.exclusive_worktree_access();
     let _snapshot = ctx
         .create_snapshot(SnapshotDetails::new(operation), guard.write_permission())
@@ -0,0 +1,123 @@
+use but_rules::evaluate::{EvaluatedAction, FilterOutcome, SkipReason};
+use colored::Colorize;
+use gitbutler_project::Project;
+
+#[derive(Debug, clap::Parser)]
+pub struct Platform {
+    #[clap(subcommand)]
+    pub cmd: Subcommands,
+}
+
+#[derive(Debug, clap::Subcommand)]
+pub enum Subcommands {
+    /// Shows which rules match the uncommitted changes and what they would do, without changing anything.
+    Test,
+}
+
+pub fn handle(cmd: &Subcommands, project: &Project, json: bool) -> anyhow::Result<()> {
+    match cmd {
+        Subcommands::Test => test(project, json),
+    }
+}
+
+fn test(project: &Project, json: bool) -> anyhow::Result<()> {
+    let evaluation = but_api::rules::evaluate_workspace_rules(project.id)?;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&evaluation)?);
+        return Ok(());
+    }
+
+    if evaluation.assignments.is_empty() {
+        println!(
+            "{}",
+            "No uncommitted changes to evaluate rules against.".dimmed()
+        );
+    }
+    for assignment in &evaluation.assignments {
+        let hunk = assignment
+            .hunk_header
+            .map(|h| {
+                format!(
+                    " @@ -{},{} +{},{} @@",
+                    h.old_start, h.old_lines, h.new_start, h.new_lines
+                )
+            })
+            .unwrap_or_default();
+        println!("{}{}", assignment.path.bold(), hunk.dimmed());
+        if assignment.rules.is_empty() {
+            println!("  {}", "no rules".dimmed());
+        }
+        for rule in &assignment.rules {
+            let rule_id = short(&rule.rule_id);
+            if let Some(reason) = rule.skipped {
+                let reason = match reason {
+                    SkipReason::Disabled => "disabled",
+                    SkipReason::Trigger => "not triggered by file changes",
+                    SkipReason::UnsupportedAction => "action isn't applied automatically",
+                };
+                println!(
+                    "  {} {}",
+                    rule_id.dimmed(),
+                    format!("skipped: {reason}").dimmed()
+                );
+                continue;
+            }
+            let status = if rule.matched {
+                "matched".green()
+            } else {
+                "no match".yellow()
+            };
+            let action = match &rule.action {
+                Some(action) => format!("→ {}", describe(action)).blue().to_string(),
+                None if rule.matched => "→ nothing to do".dimmed().to_string(),
+                None => String::new(),
+            };
+            println!("  {rule_id} {status} {action}");
+            for filter in &rule.filters {
+                let outcome = match filter.outcome {
+                    FilterOutcome::Matched => continue,
+                    FilterOutcome::Rejected => "rejected by".red(),
+                    FilterOutcome::NotEvaluated => "not evaluated:".dimmed(),
+                };
+                println!("    {outcome} {}", serde_json::to_string(&filter.filter)?);
+            }
+        }
+    }
+
+    if !evaluation.errors.is_empty() {
+        println!();
+        println!("{}", "Errors:".red().bold());
+        for error in &evaluation.errors {
+            println!("  {} {}", short(&error.rule_id), error.error);
+        }
+    }
+    Ok(())
+}
+
+fn describe(action: &EvaluatedAction) -> String {
+    match action {
+        EvaluatedAction::Assign {
+            stack_id: Some(stack_id),
+        } => format!("assign to stack {stack_id}"),
+        EvaluatedAction::Assign { stack_id: None } => "assign to a new stack".to_string(),
+        EvaluatedAction::Amend {
+            change_id,
+            commit_id,
+        } => format!("amend into {} ({change_id})", &commit_id.to_string()[..7]),
+        EvaluatedAction::NewCommit {
+            branch_name,
+            stack_id: Some(_),
+        } => format!("commit to {branch_name}"),
+        EvaluatedAction::NewCommit {
+            branch_name,
+            stack_id: None,
+        } => format!("commit to new branch {branch_name}"),
+        EvaluatedAction::Absorb { commit_id, .. } => {
+            format!("absorb into {}", &commit_id.to_string()[..7])
+        }
+    }
+}
+
+fn short(rule_id: &str) -> String {
+    rule_id.chars().take(8).collect()
+}
@@ -0,0 +1,204 @@
+use anyhow::{Result, bail};
+use but_api::hex_hash::HexHash;
+use but_settings::AppSettings;
+use but_workspace::{CommitChanges, DiffSpec, HunkHeader, StackId};
+use colored::Colorize;
+use gitbutler_command_context::CommandContext;
+use gitbutler_project::Project;
+use serde::Serialize;
+
+use crate::id::CliId;
+
+/// The changes of a single file in the commit to split, as they can be selected for a piece.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct FileHunks {
+    path: String,
+    hunks: Vec<HunkHeader>,
+}
+
+pub(crate) fn split_commit(
+    project: &Project,
+    json: bool,
+    commit: &str,
+    pieces: &[String],
+    messages: &[String],
+) -> Result<()> {
+    let mut ctx = CommandContext::open(project, AppSettings::load_from_default_path_creating()?)?;
+    let commit_oid = resolve_commit(&mut ctx, commit)?;
+    let files = commit_hunks(&ctx, commit_oid)?;
+    if pieces.is_empty() {
+        return show_hunks(commit_oid, &files, json);
+    }
+    if pieces.len() != messages.len() {
+        bail!(
+            "Each --piece needs a --message, got {} pieces and {} messages",
+            pieces.len(),
+            messages.len()
+        );
+    }
+
+    let pieces = pieces
+        .iter()
+        .zip(messages)
+        .map(|(selectors, message)| {
+            Ok(CommitChanges {
+                message: message.clone(),
+                changes: parse_piece(selectors, &files)?,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let stack_id = stack_of_commit(project, commit_oid)?;
+    let outcome =
+        but_api::workspace::split_commit(project.id, stack_id, HexHash::from(commit_oid), pieces)?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&outcome)?);
+        return Ok(());
+    }
+    println!(
+        "Split {} into {} commits:",
+        short(&commit_oid.to_string()).blue(),
+        outcome.new_commits.len() + usize::from(outcome.remainder.is_some())
+    );
+    for (commit_id, message) in outcome.new_commits.iter().zip(messages) {
+        println!("  {} {}", short(commit_id).blue(), message);
+    }
+    if let Some(remainder) = &outcome.remainder {
+        println!(
+            "  {} {}",
+            short(remainder).blue(),
+            "(remaining changes, original message)".dimmed()
+        );
+    }
+    Ok(())
+}
+
+fn resolve_commit(ctx: &mut CommandContext, commit: &str) -> Result<gix::ObjectId> {
+    let cli_ids = CliId::from_str(ctx, commit)?;
+    match cli_ids.as_slice() {
+        [] => bail!("Commit '{}' not found", commit),
+        [CliId::Commit { oid }] => Ok(*oid),
+        [cli_id] => bail!("Target must be a commit ID, not {}", cli_id.kind()),
+        _ => bail!(
+            "Commit '{}' is ambiguous. Found {} matches",
+            commit,
+            cli_ids.len()
+        ),
+    }
+}
+
+/// Obtain the changed files of `commit_oid` along with their hunks, as the split will see them.
+fn commit_hunks(ctx: &CommandContext, commit_oid: gix::ObjectId) -> Result<Vec<FileHunks>> {
+    let repo = ctx.gix_repo()?;
+    let changes = but_core::diff::ui::commit_changes_by_worktree_dir(&repo, commit_oid)?.changes;
+    let mut files = Vec::new();
+    for change in changes {
+        let change: but_core::TreeChange = change.into();
+        let hunks = match change.unified_diff(&repo, ctx.app_settings().context_lines)? {
+            Some(but_core::UnifiedDiff::Patch { hunks, .. }) => {
+                hunks.iter().map(HunkHeader::from).collect()
+            }
+            _ => Vec::new(),
+        };
+        files.push(FileHunks {
+            path: change.path.to_string(),
+            hunks,
+        });
+    }
+    Ok(files)
+}
+
+fn show_hunks(commit_oid: gix::ObjectId, files: &[FileHunks], json: bool) -> Result<()> {
+    if json {
+        println!("{}", serde_json::to_string_pretty(files)?);
+        return Ok(());
+    }
+    println!(
+        "Changes in {} that can be selected with --piece:",
+        short(&commit_oid.to_string()).blue()
+    );
+    for file in files {
+        println!("  {}", file.path.bold());
+        for (idx, hunk) in file.hunks.iter().enumerate() {
+            println!(
+                "    {} {}",
+                format!("{}#{}", file.path, idx + 1).blue(),
+                format!(
+                    "@@ -{},{} +{},{} @@",
+                    hunk.old_start, hunk.old_lines, hunk.new_start, hunk.new_lines
+                )
+                .dimmed()
+            );
+        }
+    }
+    println!(
+        "{}",
+        "Changes that no piece selects are kept in a commit with the original message.".dimmed()
+    );
+    Ok(())
+}
+
+/// Parse a comma-separated list of selectors, each either `<path>` for a whole file or `<path>#<n>` for its n-th hunk.
+fn parse_piece(selectors: &str, files: &[FileHunks]) -> Result<Vec<DiffSpec>> {
+    let mut changes: Vec<DiffSpec> = Vec::new();
+    for selector in selectors
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+    {
+        let (path, hunk) = match selector.rsplit_once('#') {
+            Some((path, n)) if files.iter().any(|f| f.path == path) => {
+                (path, Some(n.parse::<usize>().ok().filter(|n| *n > 0)))
+            }
+            _ => (selector, None),
+        };
+        let Some(file) = files.iter().find(|f| f.path == path) else {
+            bail!("'{}' isn't changed in the commit", path);
+        };
+        let hunk = match hunk {
+            None => None,
+            Some(n) => Some(
+                n.and_then(|n| file.hunks.get(n - 1))
+                    .copied()
+                    .ok_or_else(|| anyhow::anyhow!("'{}' doesn't match a hunk", selector))?,
+            ),
+        };
+        match changes.iter_mut().find(|spec| spec.path == file.path) {
+            Some(spec) => match hunk {
+                Some(hunk) if !spec.hunk_headers.is_empty() => spec.hunk_headers.push(hunk),
+                _ => bail!("'{}' is selected more than once", file.path),
+            },
+            None => changes.push(DiffSpec {
+                previous_path: None,
+                path: file.path.clone().into(),
+                hunk_headers: hunk.into_iter().collect(),
+            }),
+        }
+    }
+    if changes.is_empty() {
+        bail!("A piece must select at least one file or hunk");
+    }
+    Ok(changes)
+}
+
+fn stack_of_commit(project: &Project, commit_oid: gix::ObjectId) -> Result<StackId> {
+    for stack_entry in but_api::workspace::stacks(project.id, None)? {
+        let Some(stack_id) = stack_entry.id else {
+            continue;
+        };
+        let stack_details = but_api::workspace::stack_details(project.id, Some(stack_id))?;
+        if stack_details
+            .branch_details
+            .iter()
+            .any(|branch| branch.commits.iter().any(|c| c.id == commit_oid))
+        {
+            return Ok(stack_id);
+        }
+    }
+    bail!("Commit {} isn't in an applied stack", commit_oid)
+}
+
+fn short(commit_id: &str) -> &str {
+    &commit_id[..7.min(commit_id.len())]
+}
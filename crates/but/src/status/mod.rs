@@ -11,7 +11,7 @@ use gitbutler_commit::commit_ext::CommitExt;
 use gitbutler_oxidize::OidExt;
 use gitbutler_project::Project;
 use serde::Serialize;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap};
 pub(crate) mod assignment;
 
 use crate::id::CliId;
@@ -98,6 +98,7 @@ pub(crate) fn worktree(
         return Ok(());
     }
 
+    let gerrit_change_urls = gerrit_change_urls(ctx);
     let stack_details_len = stack_details.len();
     for (i, (stack_id, (details, assignments))) in stack_details.into_iter().enumerate() {
         let mut stack_mark = stack_id.and_then(|stack_id| {
@@ -116,6 +117,7 @@ pub(crate) fn worktree(
             show_files,
             verbose,
             &mut stack_mark,
+            &gerrit_change_urls,
             ctx,
             i == stack_details_len - 1,
             i == 0,
@@ -172,6 +174,26 @@ fn print_assignments(assignments: &Vec<FileAssignment>, changes: &[TreeChange],
         } else {
             println!("┊│   {id} {status} {path} {locks}");
         }
+
+        for assignment in &fa.assignments {
+            let (Some(id), Some(header)) = (
+                CliId::hunk_from_assignment(assignment),
+                assignment.hunk_header,
+            ) else {
+                continue;
+            };
+            let id = id.to_string().underline().blue();
+            let header = format!(
+                "@@ -{},{} +{},{} @@",
+                header.old_start, header.old_lines, header.new_start, header.new_lines
+            )
+            .dimmed();
+            if dotted {
+                println!("┊     {id} {header}");
+            } else {
+                println!("┊│     {id} {header}");
+            }
+        }
     }
 }
 
@@ -184,6 +206,7 @@ pub fn print_group(
     show_files: bool,
     verbose: bool,
     stack_mark: &mut Option<ColoredString>,
+    gerrit_change_urls: &HashMap<String, Vec<String>>,
     ctx: &mut CommandContext,
     _last: bool,
     first: bool,
@@ -217,6 +240,13 @@ pub fn print_group(
                 stack_mark.clone().unwrap_or_default()
             );
             *stack_mark = None; // Only show the stack mark for the first branch
+            for url in gerrit_change_urls
+                .get(branch.name.to_str()?)
+                .into_iter()
+                .flatten()
+            {
+                println!("┊│     {}", url.underline().dimmed());
+            }
             if first {
                 print_assignments(&assignments, changes, false);
             }
@@ -281,6 +311,18 @@ pub fn print_group(
     Ok(())
 }
 
+/// The URLs of the Gerrit changes recorded when each branch in the workspace was last pushed, by branch name.
+fn gerrit_change_urls(ctx: &CommandContext) -> HashMap<String, Vec<String>> {
+    gitbutler_stack::VirtualBranchesHandle::new(ctx.project().gb_dir())
+        .list_stacks_in_workspace()
+        .unwrap_or_default()
+        .into_iter()
+        .flat_map(|stack| stack.heads)
+        .filter(|head| !head.gerrit_change_urls.is_empty())
+        .map(|head| (head.name, head.gerrit_change_urls))
+        .collect()
+}
+
 fn status_letter(status: &TreeStatus) -> char {
     match status {
         TreeStatus::Addition { .. } => 'A',
@@ -313,6 +355,17 @@ pub(crate) fn all_files(ctx: &mut CommandContext) -> anyhow::Result<Vec<CliId>>
     Ok(out)
 }
 
+pub(crate) fn all_hunks(ctx: &mut CommandContext) -> anyhow::Result<Vec<CliId>> {
+    let changes =
+        but_core::diff::ui::worktree_changes_by_worktree_dir(ctx.project().path.clone())?.changes;
+    let (assignments, _assignments_error) =
+        but_hunk_assignment::assignments_with_fallback(ctx, false, Some(changes), None)?;
+    Ok(assignments
+        .iter()
+        .filter_map(CliId::hunk_from_assignment)
+        .collect())
+}
+
 pub(crate) fn all_branches(ctx: &CommandContext) -> anyhow::Result<Vec<CliId>> {
     let stacks = crate::log::stacks(ctx)?;
     let mut branches = Vec::new();
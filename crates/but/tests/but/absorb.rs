@@ -0,0 +1,196 @@
+use std::path::{Path, PathBuf};
+
+use crate::{but, but_json, workspace};
+
+/// A workspace with the stacks `feature` and `other`, where `feature` changed line 2 of `file`
+/// and `other` changed line 8.
+fn two_stacks_changing_file() -> anyhow::Result<(tempfile::TempDir, PathBuf, PathBuf)> {
+    let (tmp, repo) = workspace("absorb.sh")?;
+    let data_dir = tmp.path().join("app-data");
+    but(&repo, &data_dir, &["branch", "new", "feature"])?;
+    but(&repo, &data_dir, &["branch", "new", "other"])?;
+
+    write_file(&repo, &[(2, "two")])?;
+    but(&repo, &data_dir, &["commit", "-m", "change two", "feature"])?;
+    write_file(&repo, &[(2, "two"), (8, "eight")])?;
+    but(&repo, &data_dir, &["commit", "-m", "change eight", "other"])?;
+    Ok((tmp, repo, data_dir))
+}
+
+/// Write the lines 1 to 10 to `file`, with the given lines replaced.
+fn write_file(repo: &Path, replaced: &[(usize, &str)]) -> anyhow::Result<()> {
+    let content = (1..=10)
+        .map(|line| {
+            replaced
+                .iter()
+                .find(|(replaced_line, _)| *replaced_line == line)
+                .map_or_else(|| line.to_string(), |(_, content)| content.to_string())
+        })
+        .map(|line| line + "\n")
+        .collect::<String>();
+    std::fs::write(repo.join("file"), content)?;
+    Ok(())
+}
+
+/// The content of `file` at the tip of `branch`.
+fn file_in_branch(repo: &Path, branch: &str) -> anyhow::Result<String> {
+    let repo = gix::open(repo)?;
+    let blob = repo
+        .rev_parse_single(format!("{branch}:file").as_str())?
+        .object()?;
+    Ok(String::from_utf8(blob.data.clone())?)
+}
+
+fn branch_tip(repo: &Path, branch: &str) -> anyhow::Result<gix::ObjectId> {
+    Ok(gix::open(repo)?
+        .find_reference(format!("refs/heads/{branch}").as_str())?
+        .id()
+        .detach())
+}
+
+fn message_of_tip(repo: &Path, branch: &str) -> anyhow::Result<String> {
+    let commit = gix::open(repo)?.find_commit(branch_tip(repo, branch)?)?;
+    Ok(commit.message_raw()?.to_string())
+}
+
+#[test]
+fn dry_run_shows_the_commit_of_each_hunk() -> anyhow::Result<()> {
+    let (_tmp, repo, data_dir) = two_stacks_changing_file()?;
+    write_file(&repo, &[(2, "TWO"), (8, "EIGHT")])?;
+    std::fs::write(repo.join("new-file"), "content\n")?;
+    let tips = (branch_tip(&repo, "feature")?, branch_tip(&repo, "other")?);
+
+    let planned = but_json(&repo, &data_dir, &["absorb", "--dry-run"])?;
+    let planned = planned.as_array().expect("a list of hunks");
+    let outcome_of = |path: &str, new_start: u64| {
+        planned
+            .iter()
+            .find(|hunk| {
+                hunk["path"] == path
+                    && (hunk["hunkHeader"].is_null()
+                        || hunk["hunkHeader"]["newStart"].as_u64() == Some(new_start))
+            })
+            .map(|hunk| hunk["outcome"].clone())
+            .unwrap_or_else(|| panic!("{path}:{new_start} is planned: {planned:?}"))
+    };
+    let line_two = outcome_of("file", 2);
+    assert_eq!(line_two["type"], "amend");
+    assert_eq!(line_two["subject"]["branchName"], "feature");
+    assert_eq!(line_two["subject"]["commitTitle"], "change two");
+    let line_eight = outcome_of("file", 8);
+    assert_eq!(line_eight["type"], "amend");
+    assert_eq!(line_eight["subject"]["branchName"], "other");
+    assert_eq!(line_eight["subject"]["commitTitle"], "change eight");
+    assert_eq!(outcome_of("new-file", 1)["type"], "noDependency");
+
+    let text = but(&repo, &data_dir, &["absorb", "--dry-run"])?;
+    assert!(text.contains("HUNK"), "{text}");
+    assert!(text.contains("TARGET"), "{text}");
+    assert!(text.contains("change two [feature]"), "{text}");
+    assert!(text.contains("change eight [other]"), "{text}");
+    assert!(
+        text.contains("left unassigned: no commit touched these lines"),
+        "{text}"
+    );
+
+    assert_eq!(
+        (branch_tip(&repo, "feature")?, branch_tip(&repo, "other")?),
+        tips,
+        "nothing is amended in a dry-run"
+    );
+    Ok(())
+}
+
+#[test]
+fn hunks_are_amended_into_the_commit_that_touched_their_lines() -> anyhow::Result<()> {
+    let (_tmp, repo, data_dir) = two_stacks_changing_file()?;
+    write_file(&repo, &[(2, "TWO"), (8, "EIGHT")])?;
+    std::fs::write(repo.join("new-file"), "content\n")?;
+
+    let absorbed = but_json(&repo, &data_dir, &["absorb"])?;
+    let absorbed = absorbed.as_array().expect("a list of absorbed hunks");
+    assert_eq!(absorbed.len(), 2, "{absorbed:?}");
+    assert!(
+        absorbed.iter().all(|hunk| hunk["path"] == "file"),
+        "hunks without dependencies stay in the worktree: {absorbed:?}"
+    );
+
+    let feature = file_in_branch(&repo, "feature")?;
+    assert!(feature.contains("TWO\n"), "{feature}");
+    assert!(
+        !feature.contains("EIGHT"),
+        "line 8 belongs to the commit in the other stack"
+    );
+    let other = file_in_branch(&repo, "other")?;
+    assert!(other.contains("EIGHT\n"), "{other}");
+    assert!(
+        !other.contains("TWO"),
+        "line 2 belongs to the commit in the feature stack"
+    );
+
+    assert_eq!(
+        message_of_tip(&repo, "feature")?.trim(),
+        "change two",
+        "the commit was amended, not added"
+    );
+    assert_eq!(message_of_tip(&repo, "other")?.trim(), "change eight");
+    assert!(repo.join("new-file").exists());
+    Ok(())
+}
+
+#[test]
+fn ambiguous_hunks_and_hunks_without_dependencies_are_left_alone() -> anyhow::Result<()> {
+    let (_tmp, repo, data_dir) = two_stacks_changing_file()?;
+    write_file(
+        &repo,
+        &[
+            (2, "TWO"),
+            (3, "THREE"),
+            (4, "FOUR"),
+            (5, "FIVE"),
+            (6, "SIX"),
+            (7, "SEVEN"),
+            (8, "EIGHT"),
+        ],
+    )?;
+    std::fs::write(repo.join("new-file"), "content\n")?;
+    let tips = (branch_tip(&repo, "feature")?, branch_tip(&repo, "other")?);
+
+    let planned = but_json(&repo, &data_dir, &["absorb"])?;
+    let mut outcomes = planned
+        .as_array()
+        .expect("the plan is shown as nothing can be absorbed")
+        .iter()
+        .map(|hunk| {
+            (
+                hunk["path"].as_str().unwrap_or_default().to_owned(),
+                hunk["outcome"]["type"]
+                    .as_str()
+                    .unwrap_or_default()
+                    .to_owned(),
+            )
+        })
+        .collect::<Vec<_>>();
+    outcomes.sort();
+    assert_eq!(
+        outcomes,
+        [
+            ("file".to_owned(), "ambiguous".to_owned()),
+            ("new-file".to_owned(), "noDependency".to_owned())
+        ],
+        "the hunk of `file` touches the lines changed by both stacks"
+    );
+
+    let text = but(&repo, &data_dir, &["absorb"])?;
+    assert!(
+        text.contains("left unassigned: depends on more than one stack"),
+        "{text}"
+    );
+    assert_eq!(
+        (branch_tip(&repo, "feature")?, branch_tip(&repo, "other")?),
+        tips,
+        "no commit was amended"
+    );
+    assert!(std::fs::read_to_string(repo.join("file"))?.contains("FIVE"));
+    Ok(())
+}
@@ -0,0 +1,75 @@
+use crate::{but, but_json, workspace};
+
+#[test]
+fn list_marks_applied_branches() -> anyhow::Result<()> {
+    let (tmp, repo) = workspace("branch.sh")?;
+    let data_dir = tmp.path().join("app-data");
+    but(&repo, &data_dir, &["branch", "new", "feature"])?;
+
+    let branches = but_json(&repo, &data_dir, &["branch", "list"])?;
+    let branches = branches.as_array().expect("a list of branches");
+    let feature = branches
+        .iter()
+        .find(|b| b["name"] == "feature")
+        .expect("the new branch is listed");
+    assert_eq!(feature["stack"]["inWorkspace"], true);
+    let local_only = branches
+        .iter()
+        .find(|b| b["name"] == "local-only")
+        .expect("local branches are listed");
+    assert!(local_only["stack"].is_null(), "it's not in the workspace");
+
+    let text = but(&repo, &data_dir, &["branch", "list"])?;
+    assert!(text.contains("feature"));
+    assert!(text.contains("local-only"));
+    Ok(())
+}
+
+#[test]
+fn show_branch_in_workspace() -> anyhow::Result<()> {
+    let (tmp, repo) = workspace("branch.sh")?;
+    let data_dir = tmp.path().join("app-data");
+    but(&repo, &data_dir, &["branch", "new", "feature"])?;
+
+    let branch = but_json(&repo, &data_dir, &["branch", "show", "feature"])?;
+    assert_eq!(branch["name"], "feature");
+    assert_eq!(branch["commits"], serde_json::json!([]));
+
+    let text = but(&repo, &data_dir, &["branch", "show", "feature"])?;
+    assert!(text.contains("[feature]"));
+    assert!(text.contains("(no commits)"));
+
+    let err = but(&repo, &data_dir, &["branch", "show", "local-only"]).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("Branch 'local-only' is not in the workspace")
+    );
+    Ok(())
+}
+
+#[test]
+fn delete_branch_in_workspace_and_local_branch() -> anyhow::Result<()> {
+    let (tmp, repo) = workspace("branch.sh")?;
+    let data_dir = tmp.path().join("app-data");
+    but(&repo, &data_dir, &["branch", "new", "feature"])?;
+    but(&repo, &data_dir, &["branch", "new", "other"])?;
+
+    let deleted = but_json(&repo, &data_dir, &["branch", "delete", "feature"])?;
+    assert_eq!(deleted, serde_json::json!({ "deleted": "feature" }));
+
+    let text = but(&repo, &data_dir, &["branch", "delete", "local-only"])?;
+    assert!(text.contains("Deleted branch"));
+    assert!(text.contains("local-only"));
+
+    let branches = but_json(&repo, &data_dir, &["branch", "list"])?;
+    let names = branches
+        .as_array()
+        .expect("a list of branches")
+        .iter()
+        .filter_map(|b| b["name"].as_str())
+        .collect::<Vec<_>>();
+    assert!(!names.contains(&"feature"), "{names:?}");
+    assert!(!names.contains(&"local-only"), "{names:?}");
+    assert!(names.contains(&"other"), "{names:?}");
+    Ok(())
+}
@@ -0,0 +1,43 @@
+use std::{
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+mod absorb;
+mod branch;
+mod push;
+mod status;
+
+/// Run the `but` binary in `repo`, with the app data kept in `data_dir`, and return its stdout.
+fn but(repo: &Path, data_dir: &Path, args: &[&str]) -> anyhow::Result<String> {
+    let output = Command::new(env!("CARGO_BIN_EXE_but"))
+        .arg("-C")
+        .arg(repo)
+        .args(args)
+        .env("E2E_TEST_APP_DATA_DIR", data_dir)
+        .output()?;
+    anyhow::ensure!(
+        output.status.success(),
+        "`but {}` failed: {}",
+        args.join(" "),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    Ok(String::from_utf8(output.stdout)?)
+}
+
+/// Like [`but()`], but with `--json` and the output parsed.
+fn but_json(repo: &Path, data_dir: &Path, args: &[&str]) -> anyhow::Result<serde_json::Value> {
+    let args = std::iter::once("--json")
+        .chain(args.iter().copied())
+        .collect::<Vec<_>>();
+    Ok(serde_json::from_str(&but(repo, data_dir, &args)?)?)
+}
+
+/// A writable copy of the `workspace` repository in `script_name`, initialized as GitButler project.
+fn workspace(script_name: &str) -> anyhow::Result<(tempfile::TempDir, PathBuf)> {
+    let tmp =
+        gix_testtools::scripted_fixture_writable(script_name).map_err(anyhow::Error::from_boxed)?;
+    let repo = tmp.path().join("workspace");
+    but(&repo, &tmp.path().join("app-data"), &["init"])?;
+    Ok((tmp, repo))
+}
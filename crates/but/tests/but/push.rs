@@ -0,0 +1,62 @@
+use crate::{but, but_json, workspace};
+
+#[test]
+fn push_the_only_stack_to_the_remote() -> anyhow::Result<()> {
+    let (tmp, repo) = workspace("branch.sh")?;
+    let data_dir = tmp.path().join("app-data");
+    but(&repo, &data_dir, &["branch", "new", "feature"])?;
+    std::fs::write(repo.join("new-file"), "content\n")?;
+    but(
+        &repo,
+        &data_dir,
+        &["commit", "-m", "add new-file", "feature"],
+    )?;
+
+    let output = but_json(&repo, &data_dir, &["push"])?;
+    assert_eq!(output["remote"], "origin");
+    let branches = output["branches"].as_array().expect("a list of branches");
+    assert_eq!(branches.len(), 1);
+    assert_eq!(branches[0]["name"], "feature");
+    assert!(
+        branches[0]["reviewTemplate"].is_null(),
+        "templates are only used with --review"
+    );
+
+    let local = gix::open(&repo)?
+        .find_reference("refs/heads/feature")?
+        .id()
+        .detach();
+    let remote = gix::open(tmp.path().join("remote"))?
+        .find_reference("refs/heads/feature")?
+        .id()
+        .detach();
+    assert_eq!(remote, local, "the remote has the branch as it is locally");
+    Ok(())
+}
+
+#[test]
+fn push_without_stacks_fails() -> anyhow::Result<()> {
+    let (tmp, repo) = workspace("branch.sh")?;
+    let data_dir = tmp.path().join("app-data");
+
+    let err = but(&repo, &data_dir, &["push"]).expect_err("there is nothing to push");
+    assert!(
+        err.to_string().contains("No stacks found"),
+        "the user is told to create a stack first: {err}"
+    );
+    Ok(())
+}
+
+#[test]
+fn push_unknown_branch_fails() -> anyhow::Result<()> {
+    let (tmp, repo) = workspace("branch.sh")?;
+    let data_dir = tmp.path().join("app-data");
+    but(&repo, &data_dir, &["branch", "new", "feature"])?;
+
+    let err = but(&repo, &data_dir, &["push", "does-not-exist"]).expect_err("no such branch");
+    assert!(
+        err.to_string().contains("not found"),
+        "unknown branches are rejected: {err}"
+    );
+    Ok(())
+}
@@ -0,0 +1,29 @@
+use crate::{but, workspace};
+
+#[test]
+fn shows_gerrit_change_urls_recorded_for_a_branch() -> anyhow::Result<()> {
+    let (tmp, repo) = workspace("branch.sh")?;
+    let data_dir = tmp.path().join("app-data");
+    but(&repo, &data_dir, &["branch", "new", "feature"])?;
+
+    let url = "https://gerrit.example.com/c/project/+/42";
+    let vb_state = gitbutler_stack::VirtualBranchesHandle::new(repo.join(".git").join("gitbutler"));
+    let mut stack = vb_state
+        .list_stacks_in_workspace()?
+        .into_iter()
+        .find(|stack| stack.heads.iter().any(|head| head.name == "feature"))
+        .expect("the new branch has a stack");
+    for head in &mut stack.heads {
+        if head.name == "feature" {
+            head.gerrit_change_urls = vec![url.to_owned()];
+        }
+    }
+    vb_state.set_stack(stack)?;
+
+    let text = but(&repo, &data_dir, &["status"])?;
+    assert!(
+        text.contains(url),
+        "the recorded change URL is shown below its branch:\n{text}"
+    );
+    Ok(())
+}
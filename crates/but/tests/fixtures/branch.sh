@@ -0,0 +1,18 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init remote
+(cd remote
+  git config user.name "Author"
+  git config user.email "author@example.com"
+
+  echo first > file
+  git add . && git commit -m "init"
+)
+
+git clone remote workspace
+(cd workspace
+  git config user.name "Author"
+  git config user.email "author@example.com"
+  git branch local-only
+)
@@ -18,6 +18,7 @@ use crate::{
     VirtualBranchesExt,
 };
 use anyhow::{Context, Result};
+use but_rebase::{RebaseOutput, RebaseStep};
 use but_workspace::{commit_engine, stack_heads_info, ui, DiffSpec};
 use gitbutler_branch::{BranchCreateRequest, BranchUpdateRequest};
 use gitbutler_command_context::CommandContext;
@@ -67,6 +68,15 @@ pub fn can_apply_remote_branch(ctx: &CommandContext, branch_name: &RemoteRefname
     vbranch::is_remote_branch_mergeable(ctx, branch_name)
 }
 
+pub fn preview_apply_branch(
+    ctx: &CommandContext,
+    branch_name: &Refname,
+) -> Result<vbranch::ApplyBranchPreview> {
+    ensure_open_workspace_mode(ctx)
+        .context("Previewing how a branch applies requires open workspace mode")?;
+    vbranch::preview_apply_branch(ctx, branch_name)
+}
+
 pub fn create_virtual_branch(
     ctx: &CommandContext,
     create: &BranchCreateRequest,
@@ -377,6 +387,21 @@ pub fn reorder_stack(
     Ok(())
 }
 
+pub fn rebase_stack(
+    ctx: &CommandContext,
+    stack_id: StackId,
+    steps: Vec<RebaseStep>,
+) -> Result<RebaseOutput> {
+    let mut guard = ctx.project().exclusive_worktree_access();
+    ctx.verify(guard.write_permission())?;
+    ensure_open_workspace_mode(ctx).context("Rebasing a stack requires open workspace mode")?;
+    let _ = ctx.create_snapshot(
+        SnapshotDetails::new(OperationKind::ReorderCommit),
+        guard.write_permission(),
+    );
+    reorder::rebase_stack(ctx, stack_id, steps, guard.write_permission())
+}
+
 pub fn find_git_branches(ctx: &CommandContext, branch_name: &str) -> Result<Vec<RemoteBranchData>> {
     remote::find_git_branches(ctx, branch_name)
 }
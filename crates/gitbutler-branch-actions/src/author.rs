@@ -1,9 +1,10 @@
 use bstr::ByteSlice;
+use schemars::JsonSchema;
 use serde::Serialize;
 
 use crate::gravatar::gravatar_url_from_email;
 
-#[derive(Debug, Serialize, Hash, Clone, PartialEq, Eq)]
+#[derive(Debug, Serialize, Hash, Clone, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Author {
     pub name: String,
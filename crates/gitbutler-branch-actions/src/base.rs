@@ -22,10 +22,11 @@ use gitbutler_repo_actions::RepoActionsExt;
 use gitbutler_stack::{
     canned_branch_name, BranchOwnershipClaims, Stack, Target, VirtualBranchesHandle,
 };
+use schemars::JsonSchema;
 use serde::Serialize;
 use tracing::instrument;
 
-#[derive(Debug, Serialize, PartialEq, Clone)]
+#[derive(Debug, Serialize, PartialEq, Clone, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct BaseBranch {
     pub branch_name: String,
@@ -34,8 +35,10 @@ pub struct BaseBranch {
     pub push_remote_name: Option<String>,
     pub push_remote_url: String,
     #[serde(with = "gitbutler_serde::oid")]
+    #[schemars(with = "String")]
     pub base_sha: git2::Oid,
     #[serde(with = "gitbutler_serde::oid")]
+    #[schemars(with = "String")]
     pub current_sha: git2::Oid,
     pub behind: usize,
     pub upstream_commits: Vec<RemoteCommit>,
@@ -44,8 +47,10 @@ pub struct BaseBranch {
     pub conflicted: bool,
     pub diverged: bool,
     #[serde(with = "gitbutler_serde::oid_vec")]
+    #[schemars(with = "Vec<String>")]
     pub diverged_ahead: Vec<git2::Oid>,
     #[serde(with = "gitbutler_serde::oid_vec")]
+    #[schemars(with = "Vec<String>")]
     pub diverged_behind: Vec<git2::Oid>,
 }
 
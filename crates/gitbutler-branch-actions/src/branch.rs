@@ -16,6 +16,7 @@ use gitbutler_stack::{Stack, StackId, Target};
 use gix::object::tree::diff::Action;
 use gix::prelude::TreeDiffChangeExt;
 use gix::reference::Category;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::borrow::Cow;
 use std::collections::BTreeSet;
@@ -568,7 +569,7 @@ fn should_list_git_branch(identity: &BranchIdentity) -> bool {
 }
 
 /// A filter that can be applied to the branch listing
-#[derive(Default, Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[derive(Default, Debug, Clone, Copy, Serialize, Deserialize, PartialEq, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct BranchListingFilter {
     /// If the value is true, the listing will only include branches that have local references or virtual branches.
@@ -584,7 +585,7 @@ pub struct BranchListingFilter {
 /// Branch entry is not meant to contain all the data a branch can have (e.g. full commit history, all files and diffs, etc.).
 /// It is intended a summary that can be quickly retrieved and displayed in the UI.
 /// For more detailed information, each branch can be queried individually for it's `BranchData`.
-#[derive(Debug, Clone, Serialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, PartialEq, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct BranchListing {
     /// The `identity` of the branch (e.g. `main`, `feature/branch`), excluding the remote name.
@@ -592,6 +593,7 @@ pub struct BranchListing {
     /// This is a list of remotes that this branch can be found on (e.g. `origin`, `upstream` etc.),
     /// by collecting remotes from all local branches with the same identity that have a tracking setup.
     #[serde(serialize_with = "gitbutler_serde::as_string_lossy_vec_remote_name")]
+    #[schemars(with = "Vec<String>")]
     pub remotes: Vec<gix::remote::Name<'static>>,
     /// The branch may or may not have a virtual branch associated with it.
     pub stack: Option<StackReference>,
@@ -612,7 +614,7 @@ pub struct BranchListing {
 }
 
 /// Represents a "commit author" or "signature", based on the data from the git history
-#[derive(Debug, Clone, Serialize, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Serialize, PartialEq, Eq, Hash, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Author {
     /// The name of the author as configured in the git config
@@ -660,7 +662,7 @@ impl From<gix::actor::SignatureRef<'_>> for Author {
 }
 
 /// Represents a reference to an associated virtual branch
-#[derive(Debug, Clone, Serialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, PartialEq, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct StackReference {
     /// A non-normalized name of the branch, set by the user
@@ -863,7 +865,7 @@ pub fn get_branch_listing_details(
 }
 
 /// Represents a fat struct with all the data associated with a branch
-#[derive(Debug, Clone, Serialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, PartialEq, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct BranchListingDetails {
     /// The name of the branch (e.g. `main`, `feature/branch`), excluding the remote name
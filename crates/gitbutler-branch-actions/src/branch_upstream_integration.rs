@@ -8,32 +8,38 @@ use gitbutler_project::access::WorktreeWritePermission;
 use gitbutler_stack::{StackId, VirtualBranchesHandle};
 use gitbutler_workspace::branch_trees::{update_uncommited_changes, WorkspaceState};
 use gix::ObjectId;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(tag = "type", content = "subject", rename_all = "camelCase")]
 pub enum InteractiveIntegrationStep {
     Skip {
         id: Uuid,
         #[serde(with = "gitbutler_serde::object_id", rename = "commitId")]
+        #[schemars(with = "String")]
         commit_id: ObjectId,
     },
     Pick {
         id: Uuid,
         #[serde(with = "gitbutler_serde::object_id", rename = "commitId")]
+        #[schemars(with = "String")]
         commit_id: ObjectId,
     },
     PickUpstream {
         id: Uuid,
         #[serde(with = "gitbutler_serde::object_id", rename = "commitId")]
+        #[schemars(with = "String")]
         commit_id: ObjectId,
         #[serde(with = "gitbutler_serde::object_id", rename = "upstreamCommitId")]
+        #[schemars(with = "String")]
         upstream_commit_id: ObjectId,
     },
     Squash {
         id: Uuid,
         #[serde(with = "gitbutler_serde::object_id_vec", rename = "commits")]
+        #[schemars(with = "Vec<String>")]
         commits: Vec<ObjectId>,
         message: Option<String>,
     },
@@ -154,6 +160,9 @@ pub fn integrate_branch_with_steps(
             RebaseStep::SquashIntoPreceding { .. } => {
                 continue;
             }
+            RebaseStep::Drop { .. } | RebaseStep::Edit { .. } | RebaseStep::Exec { .. } => {
+                continue;
+            }
             RebaseStep::Reference(_) => {
                 new_rebase_steps.push(step);
                 let rebase_steps = integration_steps_to_rebase_steps(&steps)?;
@@ -258,7 +267,7 @@ pub fn integrate_upstream_commits_for_series(
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Serialize, Deserialize, PartialEq, JsonSchema)]
 #[serde(tag = "type", rename_all = "lowercase")]
 pub enum IntegrationStrategy {
     Merge,
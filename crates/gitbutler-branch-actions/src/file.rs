@@ -7,11 +7,12 @@ use anyhow::{anyhow, Context, Result};
 use gitbutler_cherry_pick::RepositoryExt as _;
 use gitbutler_command_context::CommandContext;
 use gitbutler_diff::FileDiff;
+use schemars::JsonSchema;
 use serde::Serialize;
 
 use crate::hunk::{file_hunks_from_diffs, VirtualBranchHunk};
 
-#[derive(Debug, PartialEq, Clone, Serialize)]
+#[derive(Debug, PartialEq, Clone, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct RemoteBranchFile {
     pub path: path::PathBuf,
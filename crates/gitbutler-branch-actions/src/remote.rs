@@ -11,12 +11,14 @@ use gitbutler_repo::logging::{LogUntil, RepositoryExt};
 use gitbutler_repo_actions::RepoActionsExt;
 use gitbutler_serde::BStringForFrontend;
 use gitbutler_stack::{Target, VirtualBranchesHandle};
+use schemars::JsonSchema;
 use serde::Serialize;
 
-#[derive(Debug, Clone, Serialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, PartialEq, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct RemoteBranchData {
     #[serde(with = "gitbutler_serde::oid")]
+    #[schemars(with = "String")]
     pub sha: git2::Oid,
     pub name: Refname,
     pub given_name: String,
@@ -24,11 +26,12 @@ pub struct RemoteBranchData {
     pub behind: u32,
     pub commits: Vec<RemoteCommit>,
     #[serde(with = "gitbutler_serde::oid_opt", default)]
+    #[schemars(with = "Option<String>")]
     pub fork_point: Option<git2::Oid>,
     pub is_remote: bool,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct RemoteCommit {
     pub id: String,
@@ -37,6 +40,7 @@ pub struct RemoteCommit {
     pub author: Author,
     pub change_id: Option<String>,
     #[serde(with = "gitbutler_serde::oid_vec")]
+    #[schemars(with = "Vec<String>")]
     pub parent_ids: Vec<git2::Oid>,
     pub conflicted: bool,
 }
@@ -8,6 +8,7 @@ use gitbutler_stack::{Stack, StackId};
 
 use gitbutler_workspace::branch_trees::{update_uncommited_changes, WorkspaceState};
 use itertools::Itertools;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::VirtualBranchesExt;
@@ -29,23 +30,11 @@ pub fn reorder_stack(
     new_order: StackOrder,
     perm: &mut WorktreeWritePermission,
 ) -> Result<RebaseOutput> {
-    let old_workspace = WorkspaceState::create(ctx, perm.read_permission())?;
     let state = ctx.project().virtual_branches();
-    let repo = ctx.repo();
-    let mut stack = state.get_stack(stack_id)?;
+    let stack = state.get_stack(stack_id)?;
     let current_order = commits_order(ctx, &stack)?;
     new_order.validate(current_order.clone())?;
 
-    let gix_repo = ctx.gix_repo()?;
-    let default_target = state.get_default_target()?;
-    let default_target_commit = repo
-        .find_reference(&default_target.branch.to_string())?
-        .peel_to_commit()?;
-    let merge_base = repo.merge_base(
-        default_target_commit.id(),
-        stack.head_oid(&gix_repo)?.to_git2(),
-    )?;
-
     let mut steps: Vec<RebaseStep> = Vec::new();
     for series in new_order.series.iter().rev() {
         for oid in series.commit_ids.iter().rev() {
@@ -58,6 +47,126 @@ pub fn reorder_stack(
             series.name.clone(),
         )));
     }
+    rebase_stack_from_merge_base(ctx, stack, steps, perm)
+}
+
+/// This API allows the client to rewrite a stack with arbitrary `steps`, which are applied in order on top of
+/// the merge base of the stack and the target branch.
+/// This makes it possible to reorder and squash commits, and to move the boundaries between the series of the stack,
+/// all in one operation.
+///
+/// # Errors
+/// Errors out upon invalid steps. The following conditions are checked:
+/// - Every commit in the stack must be picked, edited, squashed or dropped exactly once, and no other commit may be used
+/// - There must be one `Reference` step for each series in the stack, naming them in their current order from the bottom
+/// - The last step, ignoring `Exec` steps, must be a `Reference` step, so that every commit belongs to a series
+/// - A commit can only be squashed if a commit was picked before it
+pub fn rebase_stack(
+    ctx: &CommandContext,
+    stack_id: StackId,
+    steps: Vec<RebaseStep>,
+    perm: &mut WorktreeWritePermission,
+) -> Result<RebaseOutput> {
+    let state = ctx.project().virtual_branches();
+    let stack = state.get_stack(stack_id)?;
+    validate_steps(&steps, commits_order(ctx, &stack)?)?;
+    rebase_stack_from_merge_base(ctx, stack, steps, perm)
+}
+
+fn validate_steps(steps: &[RebaseStep], current_order: StackOrder) -> Result<()> {
+    let mut remaining_commit_ids = current_order
+        .series
+        .iter()
+        .flat_map(|s| s.commit_ids.iter())
+        .map(|id| id.to_gix())
+        .collect_vec();
+    let mut series_names = Vec::new();
+    // The last step that isn't a drop or exec step, which is what a squash applies to.
+    let mut preceding: Option<&RebaseStep> = None;
+    for step in steps {
+        match step {
+            RebaseStep::Pick { commit_id, .. }
+            | RebaseStep::SquashIntoPreceding { commit_id, .. }
+            | RebaseStep::Edit { commit_id }
+            | RebaseStep::Drop { commit_id } => {
+                if matches!(step, RebaseStep::SquashIntoPreceding { .. })
+                    && !matches!(
+                        preceding,
+                        Some(
+                            RebaseStep::Pick { .. }
+                                | RebaseStep::Edit { .. }
+                                | RebaseStep::SquashIntoPreceding { .. }
+                        )
+                    )
+                {
+                    bail!(
+                        "Commit '{commit_id}' can't be squashed as no commit of its series was picked before it"
+                    );
+                }
+                let Some(idx) = remaining_commit_ids.iter().position(|id| id == commit_id) else {
+                    bail!("Commit '{commit_id}' does not exist in the stack or is used more than once");
+                };
+                remaining_commit_ids.remove(idx);
+                if !matches!(step, RebaseStep::Drop { .. }) {
+                    preceding = Some(step);
+                }
+            }
+            RebaseStep::Exec { .. } => {}
+            RebaseStep::Reference(but_core::Reference::Virtual(name)) => {
+                series_names.push(name.as_str());
+                preceding = Some(step);
+            }
+            RebaseStep::Reference(reference) => {
+                bail!("Only series of the stack can be referenced, got {reference:?}")
+            }
+        }
+    }
+    if let Some(commit_id) = remaining_commit_ids.first() {
+        bail!("Commit '{commit_id}' of the stack is missing");
+    }
+    let current_names = current_order
+        .series
+        .iter()
+        .rev()
+        .map(|s| s.name.as_str())
+        .collect_vec();
+    if series_names != current_names {
+        bail!(
+            "The series must be referenced exactly once and in their current order, from bottom to top: {}",
+            current_names.join(", ")
+        );
+    }
+    if !matches!(
+        steps
+            .iter()
+            .rfind(|step| !matches!(step, RebaseStep::Exec { .. })),
+        Some(RebaseStep::Reference(_))
+    ) {
+        bail!("The last step must reference a series, otherwise the commits on top wouldn't belong to any series");
+    }
+    Ok(())
+}
+
+/// Rebase `stack` onto its merge base with the target branch using `steps`, and update its series and the workspace accordingly.
+fn rebase_stack_from_merge_base(
+    ctx: &CommandContext,
+    mut stack: Stack,
+    steps: Vec<RebaseStep>,
+    perm: &mut WorktreeWritePermission,
+) -> Result<RebaseOutput> {
+    let old_workspace = WorkspaceState::create(ctx, perm.read_permission())?;
+    let state = ctx.project().virtual_branches();
+    let repo = ctx.repo();
+    let gix_repo = ctx.gix_repo()?;
+    let default_target = state.get_default_target()?;
+    let default_target_commit = repo
+        .find_reference(&default_target.branch.to_string())?
+        .peel_to_commit()?;
+    let merge_base = repo.merge_base(
+        default_target_commit.id(),
+        stack.head_oid(&gix_repo)?.to_git2(),
+    )?;
+
     let mut builder = but_rebase::Rebase::new(&gix_repo, merge_base.to_gix(), None)?;
     let builder = builder.steps(steps)?;
     builder.rebase_noops(false);
@@ -80,7 +189,7 @@ pub fn reorder_stack(
 }
 
 /// Represents the order of series (branches) and changes (commits) in a stack.
-#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct StackOrder {
     /// The series are ordered from newest to oldest (most recent stacks go first)
@@ -88,7 +197,7 @@ pub struct StackOrder {
 }
 
 /// Represents the order of changes (commits) in a series (branch).
-#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct SeriesOrder {
     /// Unique name of the series (branch). Must already exist in the stack.
@@ -97,6 +206,7 @@ pub struct SeriesOrder {
     /// naturally, the the commit ids will be different afte updating.
     /// The changes are ordered from newest to oldest (most recent changes go first)
     #[serde(with = "gitbutler_serde::oid_vec")]
+    #[schemars(with = "Vec<String>")]
     pub commit_ids: Vec<Oid>,
 }
 
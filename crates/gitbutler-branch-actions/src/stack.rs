@@ -9,6 +9,7 @@ use gitbutler_repo::hooks;
 use gitbutler_repo_actions::RepoActionsExt;
 use gitbutler_stack::StackId;
 use gitbutler_stack::{PatchReferenceUpdate, StackBranch};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::actions::Verify;
@@ -52,7 +53,7 @@ pub fn create_branch(
 }
 
 /// Request to create a new series in a stack
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
 pub struct CreateSeriesRequest {
     /// Name of the new series
     pub name: String,
@@ -156,6 +157,9 @@ pub fn update_branch_pr_number(
 
 /// Pushes all series in the stack to the remote.
 /// This operation will error out if the target has no push remote configured.
+///
+/// In Gerrit mode, each series is pushed to `refs/for/<target>` with its name as topic and `gerrit_options`
+/// applied, and the URLs of the changes reported by Gerrit are stored with the series.
 pub fn push_stack(
     ctx: &CommandContext,
     stack_id: StackId,
@@ -163,11 +167,12 @@ pub fn push_stack(
     skip_force_push_protection: bool,
     branch_limit: String,
     run_hooks: bool,
+    gerrit_options: Option<but_gerrit::push::PushOptions>,
 ) -> Result<PushResult> {
     ctx.verify(ctx.project().exclusive_worktree_access().write_permission())?;
     ensure_open_workspace_mode(ctx).context("Requires an open workspace mode")?;
     let state = ctx.project().virtual_branches();
-    let stack = state.get_stack(stack_id)?;
+    let mut stack = state.get_stack(stack_id)?;
 
     let repo = ctx.repo();
     let default_target = state.get_default_target()?;
@@ -189,6 +194,7 @@ pub fn push_stack(
     let mut result = PushResult {
         remote: default_target.push_remote_name(),
         branch_to_remote: vec![],
+        branch_to_gerrit_changes: vec![],
     };
     let gerrit_mode = gix_repo
         .git_settings()?
@@ -236,16 +242,20 @@ pub fn push_stack(
         }
 
         let refspec = if gerrit_mode {
+            let options = but_gerrit::push::PushOptions {
+                topic: Some(branch.name().to_owned()),
+                ..gerrit_options.clone().unwrap_or_default()
+            };
             Some(format!(
-                "{}:refs/for/{}",
+                "{}:{}",
                 push_details.head,
-                default_target.branch.branch()
+                options.destination_ref(default_target.branch.branch())
             ))
         } else {
             None
         };
 
-        ctx.push(
+        let remote_messages = ctx.push(
             push_details.head,
             &push_details.remote_refname,
             with_force,
@@ -259,6 +269,18 @@ pub fn push_stack(
             push_details.remote_refname.to_owned().into(),
         ));
 
+        if gerrit_mode {
+            let changes = but_gerrit::push::parse_push_output(&remote_messages);
+            stack.set_gerrit_change_urls(
+                ctx,
+                branch.name(),
+                changes.iter().map(|c| c.url.clone()).collect(),
+            )?;
+            result
+                .branch_to_gerrit_changes
+                .push((branch.name().to_owned(), changes));
+        }
+
         if branch.name().eq(&branch_limit) {
             break;
         }
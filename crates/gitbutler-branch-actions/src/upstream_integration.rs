@@ -22,23 +22,24 @@ use gitbutler_serde::BStringForFrontend;
 use gitbutler_stack::{StackId, Target, VirtualBranchesHandle};
 use gitbutler_workspace::branch_trees::{update_uncommited_changes, WorkspaceState};
 use gix::merge::tree::TreatAsUnresolved;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, PartialEq, Debug)]
+#[derive(Serialize, PartialEq, Debug, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct NameAndStatus {
     pub name: String,
     pub status: BranchStatus,
 }
 
-#[derive(Serialize, PartialEq, Debug)]
+#[derive(Serialize, PartialEq, Debug, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct StackStatus {
     pub tree_status: TreeStatus,
     pub branch_statuses: Vec<NameAndStatus>,
 }
 
-#[derive(Serialize, PartialEq, Debug)]
+#[derive(Serialize, PartialEq, Debug, JsonSchema)]
 #[serde(tag = "type", content = "subject", rename_all = "camelCase")]
 pub enum TreeStatus {
     SaflyUpdatable,
@@ -46,7 +47,7 @@ pub enum TreeStatus {
     Empty,
 }
 
-#[derive(Serialize, PartialEq, Debug)]
+#[derive(Serialize, PartialEq, Debug, JsonSchema)]
 #[serde(tag = "type", content = "subject", rename_all = "camelCase")]
 pub enum BranchStatus {
     SaflyUpdatable,
@@ -58,7 +59,7 @@ pub enum BranchStatus {
     Empty,
 }
 
-#[derive(Serialize, PartialEq, Debug)]
+#[derive(Serialize, PartialEq, Debug, JsonSchema)]
 #[serde(tag = "type", content = "subject", rename_all = "camelCase")]
 pub enum StackStatuses {
     UpToDate,
@@ -69,7 +70,7 @@ pub enum StackStatuses {
     },
 }
 
-#[derive(Serialize, Deserialize, PartialEq, Debug)]
+#[derive(Serialize, Deserialize, PartialEq, Debug, JsonSchema)]
 #[serde(tag = "type", content = "subject", rename_all = "camelCase")]
 pub enum BaseBranchResolutionApproach {
     Rebase,
@@ -77,7 +78,7 @@ pub enum BaseBranchResolutionApproach {
     HardReset,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, JsonSchema)]
 #[serde(tag = "type", content = "subject", rename_all = "camelCase")]
 pub enum ResolutionApproach {
     Rebase,
@@ -86,21 +87,33 @@ pub enum ResolutionApproach {
     Delete,
 }
 
-#[derive(Serialize, Deserialize, PartialEq, Debug)]
+#[derive(Serialize, Deserialize, PartialEq, Debug, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct BaseBranchResolution {
     #[serde(with = "gitbutler_serde::oid")]
+    #[schemars(with = "String")]
     target_commit_oid: git2::Oid,
     approach: BaseBranchResolutionApproach,
 }
 
-#[derive(Serialize, Deserialize, PartialEq, Debug)]
+impl BaseBranchResolution {
+    /// Resolve the diverged base branch with `approach`, integrating the stacks onto `target_commit_oid`
+    /// as returned by [`resolve_upstream_integration()`](crate::resolve_upstream_integration).
+    pub fn new(target_commit_oid: git2::Oid, approach: BaseBranchResolutionApproach) -> Self {
+        Self {
+            target_commit_oid,
+            approach,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct IntegrationOutcome {
     /// This is the list of branch names that have become archived as a result of the upstream integration
-    archived_branches: Vec<String>,
+    pub archived_branches: Vec<String>,
     /// This is the list of review ids that have been closed as a result of the upstream integration
-    review_ids_to_close: Vec<String>,
+    pub review_ids_to_close: Vec<String>,
 }
 
 impl StackStatus {
@@ -148,7 +161,7 @@ impl StackStatus {
     }
 }
 
-#[derive(Serialize, Deserialize, PartialEq, Debug)]
+#[derive(Serialize, Deserialize, PartialEq, Debug, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Resolution {
     pub stack_id: StackId,
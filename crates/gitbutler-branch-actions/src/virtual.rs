@@ -1,5 +1,6 @@
 use crate::{hunk::VirtualBranchHunk, status::get_applied_status_cached, VirtualBranchesExt};
 use anyhow::{anyhow, bail, Context, Result};
+use bstr::BString;
 use but_rebase::RebaseStep;
 use but_workspace::stack_ext::StackExt;
 use gitbutler_branch::dedup;
@@ -23,16 +24,19 @@ use gitbutler_stack::{
 };
 use gitbutler_time::time::now_since_unix_epoch_ms;
 use itertools::Itertools;
+use schemars::JsonSchema;
 use serde::Serialize;
 use std::{collections::HashMap, path::PathBuf, vec};
 
-#[derive(Debug, PartialEq, Clone, Serialize)]
+#[derive(Debug, PartialEq, Clone, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct PushResult {
     /// The name of the remote to which the branches were pushed.
     pub remote: String,
     /// The list of pushed branches and their corresponding remote refnames.
     pub branch_to_remote: Vec<(String, Refname)>,
+    /// The Gerrit changes created or updated for each pushed branch, only set when pushing in Gerrit mode.
+    pub branch_to_gerrit_changes: Vec<(String, Vec<but_gerrit::push::PushedChange>)>,
 }
 
 fn find_base_tree<'a>(
@@ -436,6 +440,84 @@ pub fn is_remote_branch_mergeable(
     Ok(mergeable)
 }
 
+/// What would happen when applying a branch to the workspace, as computed by [`preview_apply_branch()`].
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ApplyBranchPreview {
+    /// The paths that would conflict, or an empty list if the branch can be applied cleanly.
+    pub conflicts: Vec<ApplyBranchConflict>,
+}
+
+/// A path that conflicts when merging a branch into the workspace.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ApplyBranchConflict {
+    /// The conflicting path.
+    #[serde(with = "gitbutler_serde::bstring_lossy")]
+    #[schemars(with = "String")]
+    pub path: BString,
+    /// The applied stacks whose changes to `path` conflict with the branch or with each other.
+    pub stacks: Vec<ConflictingStack>,
+}
+
+/// An applied stack which takes part in a conflict.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ConflictingStack {
+    pub id: StackId,
+    pub name: String,
+}
+
+/// Merge the tip of `branch_name` with the heads of all applied stacks in memory, just like the workspace commit
+/// would be created after applying it, and list all paths that would conflict along with the stacks causing them.
+/// Nothing is written to the repository.
+pub fn preview_apply_branch(
+    ctx: &CommandContext,
+    branch_name: &Refname,
+) -> Result<ApplyBranchPreview> {
+    let vb_state = ctx.project().virtual_branches();
+    let branch = ctx
+        .repo()
+        .maybe_find_branch_by_refname(branch_name)?
+        .ok_or(anyhow!("branch not found"))?;
+    let branch_tip = branch.get().target().context("detatched head")?.to_gix();
+
+    let repo = ctx.gix_repo_for_merging_non_persisting()?;
+    let mut stacks = vb_state.list_stacks_in_workspace()?;
+    stacks.sort_by_key(|stack| stack.order);
+    let mut heads = Vec::with_capacity(stacks.len() + 1);
+    for stack in &stacks {
+        let head = stack.head_oid(&repo)?;
+        if head == branch_tip {
+            bail!("Branch '{branch_name}' is already applied");
+        }
+        heads.push((head, stack));
+    }
+    let mut commits: Vec<_> = heads.iter().map(|(head, _)| *head).collect();
+    commits.push(branch_tip);
+
+    let conflicts = but_rebase::merge::octopus_conflicts(&repo, &commits)?
+        .into_iter()
+        .map(|conflict| {
+            let stacks = heads
+                .iter()
+                .filter(|(head, _)| conflict.commits.contains(head))
+                .map(|(_, stack)| {
+                    Ok(ConflictingStack {
+                        id: stack.id,
+                        name: stack.derived_name()?,
+                    })
+                })
+                .collect::<Result<_>>()?;
+            Ok(ApplyBranchConflict {
+                path: conflict.path,
+                stacks,
+            })
+        })
+        .collect::<Result<_>>()?;
+    Ok(ApplyBranchPreview { conflicts })
+}
+
 // create and insert a blank commit (no tree change) either above or below a commit
 // if offset is positive, insert below, if negative, insert above
 // return map of the updated commit ids
@@ -1,10 +1,11 @@
 use std::collections::HashMap;
 
 use anyhow::Result;
+use but_rebase::RebaseStep;
 use git2::Oid;
-use gitbutler_branch_actions::{reorder_stack, SeriesOrder, StackOrder};
+use gitbutler_branch_actions::{rebase_stack, reorder_stack, SeriesOrder, StackOrder};
 use gitbutler_command_context::CommandContext;
-use gitbutler_oxidize::{ObjectIdExt, RepoExt};
+use gitbutler_oxidize::{ObjectIdExt, OidExt, RepoExt};
 use gitbutler_stack::VirtualBranchesHandle;
 use gitbutler_testsupport::testing_repository::assert_commit_tree_matches;
 use itertools::Itertools;
@@ -396,6 +397,145 @@ fn conflicting_reorder_stack() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn rebase_stack_squashes_and_moves_series_boundary() -> Result<()> {
+    let (ctx, _temp_dir) = command_ctx("multiple-commits")?;
+    let test_ctx = test_ctx(&ctx)?;
+    let steps = vec![
+        pick(test_ctx.bottom_commits["commit 1"]),
+        squash(test_ctx.bottom_commits["commit 2"]),
+        reference("my_stack"),
+        pick(test_ctx.bottom_commits["commit 3"]),
+        pick(test_ctx.top_commits["commit 4"]),
+        pick(test_ctx.top_commits["commit 6"]),
+        pick(test_ctx.top_commits["commit 5"]),
+        reference("top-series"),
+    ];
+    rebase_stack(&ctx, test_ctx.stack.id, steps)?;
+    let commits = vb_commits(&ctx);
+
+    assert_eq!(
+        commits[0].msgs(),
+        vec!["commit 5", "commit 6", "commit 4", "commit 3"]
+    );
+    assert_eq!(
+        commits[1].msgs(),
+        vec!["commit 2"],
+        "without a new message, the message of the squashed commit is used"
+    );
+    assert_eq!(commits[1].conflicted(), vec![false]);
+    Ok(())
+}
+
+#[test]
+fn rebase_stack_requires_all_commits_and_series() -> Result<()> {
+    let (ctx, _temp_dir) = command_ctx("multiple-commits")?;
+    let test_ctx = test_ctx(&ctx)?;
+    let commit_1 = test_ctx.bottom_commits["commit 1"];
+    let others = [
+        test_ctx.bottom_commits["commit 2"],
+        test_ctx.bottom_commits["commit 3"],
+        test_ctx.top_commits["commit 4"],
+        test_ctx.top_commits["commit 5"],
+        test_ctx.top_commits["commit 6"],
+    ];
+
+    let steps = others
+        .iter()
+        .map(|id| pick(*id))
+        .chain([reference("my_stack"), reference("top-series")])
+        .collect();
+    let err = rebase_stack(&ctx, test_ctx.stack.id, steps).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        format!("Commit '{commit_1}' of the stack is missing")
+    );
+
+    let steps = std::iter::once(commit_1)
+        .chain(others)
+        .map(pick)
+        .chain([reference("top-series"), reference("my_stack")])
+        .collect();
+    let err = rebase_stack(&ctx, test_ctx.stack.id, steps).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "The series must be referenced exactly once and in their current order, from bottom to top: my_stack, top-series"
+    );
+    Ok(())
+}
+
+#[test]
+fn rebase_stack_rejects_squashing_past_dropped_commits() -> Result<()> {
+    let (ctx, _temp_dir) = command_ctx("multiple-commits")?;
+    let test_ctx = test_ctx(&ctx)?;
+    let commit_3 = test_ctx.bottom_commits["commit 3"];
+    let commit_4 = test_ctx.top_commits["commit 4"];
+
+    let steps = vec![
+        pick(test_ctx.bottom_commits["commit 1"]),
+        pick(test_ctx.bottom_commits["commit 2"]),
+        reference("my_stack"),
+        drop_commit(commit_3),
+        squash(commit_4),
+        pick(test_ctx.top_commits["commit 5"]),
+        pick(test_ctx.top_commits["commit 6"]),
+        reference("top-series"),
+    ];
+    let err = rebase_stack(&ctx, test_ctx.stack.id, steps).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        format!(
+            "Commit '{commit_4}' can't be squashed as no commit of its series was picked before it"
+        ),
+        "squashing must not cross into the series below"
+    );
+
+    let commit_2 = test_ctx.bottom_commits["commit 2"];
+    let steps = vec![
+        drop_commit(test_ctx.bottom_commits["commit 1"]),
+        squash(commit_2),
+        pick(commit_3),
+        reference("my_stack"),
+        pick(commit_4),
+        pick(test_ctx.top_commits["commit 5"]),
+        pick(test_ctx.top_commits["commit 6"]),
+        reference("top-series"),
+    ];
+    let err = rebase_stack(&ctx, test_ctx.stack.id, steps).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        format!(
+            "Commit '{commit_2}' can't be squashed as no commit of its series was picked before it"
+        ),
+        "the merge base must not be squashed into"
+    );
+    Ok(())
+}
+
+fn pick(commit_id: Oid) -> RebaseStep {
+    RebaseStep::Pick {
+        commit_id: commit_id.to_gix(),
+        new_message: None,
+    }
+}
+
+fn squash(commit_id: Oid) -> RebaseStep {
+    RebaseStep::SquashIntoPreceding {
+        commit_id: commit_id.to_gix(),
+        new_message: None,
+    }
+}
+
+fn drop_commit(commit_id: Oid) -> RebaseStep {
+    RebaseStep::Drop {
+        commit_id: commit_id.to_gix(),
+    }
+}
+
+fn reference(name: &str) -> RebaseStep {
+    RebaseStep::Reference(but_core::Reference::Virtual(name.to_owned()))
+}
+
 fn order(series: Vec<Vec<Oid>>) -> StackOrder {
     StackOrder {
         series: vec![
@@ -2,7 +2,9 @@ use std::{io::Write, path::Path, time::Duration};
 
 use gitbutler_branch::BranchCreateRequest;
 use gitbutler_branch_actions::list_commit_files;
-use gitbutler_oplog::OplogExt;
+use gitbutler_oplog::{
+    diff::StackChangeKind, entry::OperationKind, retention::RetentionPolicy, OplogExt,
+};
 use gitbutler_oxidize::ObjectIdExt;
 use gitbutler_stack::VirtualBranchesHandle;
 use gitbutler_testsupport::stack_details;
@@ -456,3 +458,286 @@ fn first_snapshot_diff_works() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn prune_keeps_most_recent_snapshots() -> anyhow::Result<()> {
+    let Test { project, ctx, .. } = &Test::default();
+
+    gitbutler_branch_actions::set_base_branch(
+        ctx,
+        &"refs/remotes/origin/master".parse()?,
+        false,
+        ctx.project().exclusive_worktree_access().write_permission(),
+    )?;
+    for name in ["a", "b", "c"] {
+        gitbutler_branch_actions::create_virtual_branch(
+            ctx,
+            &BranchCreateRequest {
+                name: Some(name.into()),
+                ..Default::default()
+            },
+            ctx.project().exclusive_worktree_access().write_permission(),
+        )?;
+    }
+    let before = ctx.list_snapshots(100, None, Vec::new())?;
+    assert!(before.len() > 2);
+
+    let policy = RetentionPolicy {
+        keep_count: 2,
+        max_age: None,
+        keep_operations: vec![],
+    };
+    let mut guard = project.exclusive_worktree_access();
+    let outcome = ctx.prune_snapshots(&policy, true, guard.write_permission())?;
+    assert_eq!(outcome.kept, 2);
+    assert_eq!(outcome.pruned, before.len() - 2);
+    assert_eq!(outcome.new_head, None);
+    assert_eq!(
+        ctx.list_snapshots(100, None, Vec::new())?,
+        before,
+        "a dry-run doesn't change anything"
+    );
+
+    let outcome = ctx.prune_snapshots(&policy, false, guard.write_permission())?;
+    let after = ctx.list_snapshots(100, None, Vec::new())?;
+    assert_eq!(after.len(), 2);
+    assert_eq!(outcome.new_head, Some(after[0].commit_id));
+    assert_eq!(ctx.oplog_head()?, Some(after[0].commit_id));
+    for (after, before) in after.iter().zip(&before) {
+        assert_eq!(
+            after.details, before.details,
+            "snapshots are recreated as is"
+        );
+        assert_eq!(after.created_at, before.created_at);
+    }
+
+    ctx.restore_snapshot(after[1].commit_id, guard.write_permission())
+        .expect("recreated snapshots can be restored");
+    Ok(())
+}
+
+#[test]
+fn prune_with_huge_keep_days_keeps_recent_snapshots() -> anyhow::Result<()> {
+    let Test { project, ctx, .. } = &Test::default();
+
+    gitbutler_branch_actions::set_base_branch(
+        ctx,
+        &"refs/remotes/origin/master".parse()?,
+        false,
+        ctx.project().exclusive_worktree_access().write_permission(),
+    )?;
+    for name in ["a", "b"] {
+        gitbutler_branch_actions::create_virtual_branch(
+            ctx,
+            &BranchCreateRequest {
+                name: Some(name.into()),
+                ..Default::default()
+            },
+            ctx.project().exclusive_worktree_access().write_permission(),
+        )?;
+    }
+    let before = ctx.list_snapshots(100, None, Vec::new())?;
+
+    let policy = RetentionPolicy {
+        keep_count: 0,
+        max_age: Some(RetentionPolicy::max_age_from_days(u64::MAX)),
+        keep_operations: vec![],
+    };
+    let mut guard = project.exclusive_worktree_access();
+    let outcome = ctx.prune_snapshots(&policy, false, guard.write_permission())?;
+    assert_eq!(
+        outcome.pruned, 0,
+        "all snapshots are younger than the maximum age"
+    );
+    assert_eq!(outcome.kept, before.len());
+    assert_eq!(ctx.list_snapshots(100, None, Vec::new())?, before);
+    Ok(())
+}
+
+#[test]
+fn prune_drops_restored_from_trailers_of_pruned_snapshots() -> anyhow::Result<()> {
+    let Test { project, ctx, .. } = &Test::default();
+
+    gitbutler_branch_actions::set_base_branch(
+        ctx,
+        &"refs/remotes/origin/master".parse()?,
+        false,
+        ctx.project().exclusive_worktree_access().write_permission(),
+    )?;
+    for name in ["a", "b"] {
+        gitbutler_branch_actions::create_virtual_branch(
+            ctx,
+            &BranchCreateRequest {
+                name: Some(name.into()),
+                ..Default::default()
+            },
+            ctx.project().exclusive_worktree_access().write_permission(),
+        )?;
+    }
+    let mut guard = project.exclusive_worktree_access();
+    let snapshots = ctx.list_snapshots(100, None, Vec::new())?;
+    ctx.restore_snapshot(snapshots[0].commit_id, guard.write_permission())?;
+    let restored_from = |snapshot: &gitbutler_oplog::entry::Snapshot| {
+        snapshot.details.as_ref().and_then(|details| {
+            details
+                .trailers
+                .iter()
+                .find(|t| t.key == "restored_from")
+                .map(|t| t.value.clone())
+        })
+    };
+    let head = ctx.list_snapshots(1, None, Vec::new())?;
+    assert_eq!(
+        restored_from(&head[0]),
+        Some(snapshots[0].commit_id.to_string())
+    );
+
+    let policy = RetentionPolicy {
+        keep_count: 1,
+        max_age: None,
+        keep_operations: vec![],
+    };
+    ctx.prune_snapshots(&policy, false, guard.write_permission())?;
+    let after = ctx.list_snapshots(100, None, Vec::new())?;
+    assert_eq!(after.len(), 1);
+    assert_eq!(
+        after[0].details.as_ref().map(|d| d.operation),
+        Some(OperationKind::RestoreFromSnapshot)
+    );
+    assert_eq!(
+        restored_from(&after[0]),
+        None,
+        "the restored snapshot was pruned, so the trailer would point to nothing"
+    );
+    Ok(())
+}
+
+#[test]
+fn redo_restores_the_state_before_undo() -> anyhow::Result<()> {
+    let Test { project, ctx, .. } = &Test::default();
+
+    gitbutler_branch_actions::set_base_branch(
+        ctx,
+        &"refs/remotes/origin/master".parse()?,
+        false,
+        ctx.project().exclusive_worktree_access().write_permission(),
+    )?;
+    let create_branch = |name: &str| {
+        gitbutler_branch_actions::create_virtual_branch(
+            ctx,
+            &BranchCreateRequest {
+                name: Some(name.into()),
+                ..Default::default()
+            },
+            ctx.project().exclusive_worktree_access().write_permission(),
+        )
+    };
+    let stack_count = || -> anyhow::Result<usize> {
+        Ok(VirtualBranchesHandle::new(project.gb_dir())
+            .list_stacks_in_workspace()?
+            .len())
+    };
+    create_branch("a")?;
+    create_branch("b")?;
+    assert_eq!(stack_count()?, 2);
+    assert_eq!(ctx.redo_snapshot()?, None, "nothing was undone yet");
+
+    let mut guard = project.exclusive_worktree_access();
+    let snapshots = ctx.list_snapshots(10, None, Vec::new())?;
+    let undo_id = ctx.restore_snapshot(snapshots[0].commit_id, guard.write_permission())?;
+    assert_eq!(stack_count()?, 1, "creating 'b' was undone");
+    assert_eq!(
+        ctx.redo_snapshot()?.map(|s| s.commit_id),
+        Some(undo_id),
+        "the undo can be redone"
+    );
+
+    let redo_id = ctx.redo(guard.write_permission())?;
+    assert!(redo_id.is_some());
+    assert_eq!(stack_count()?, 2, "'b' is back");
+    assert_eq!(ctx.redo_snapshot()?, None, "the undo was redone already");
+    assert_eq!(ctx.redo(guard.write_permission())?, None);
+
+    let snapshots = ctx.list_snapshots(10, None, Vec::new())?;
+    let restore_of_redo = snapshots
+        .iter()
+        .find(|s| s.commit_id == redo_id.unwrap())
+        .and_then(|s| s.details.clone())
+        .expect("redo is recorded");
+    assert_eq!(restore_of_redo.title, "Redo");
+
+    let last_create = snapshots
+        .iter()
+        .find(|s| {
+            s.details
+                .as_ref()
+                .is_some_and(|d| d.operation == OperationKind::CreateBranch)
+        })
+        .expect("branches were created");
+    ctx.restore_snapshot(last_create.commit_id, guard.write_permission())?;
+    assert!(ctx.redo_snapshot()?.is_some());
+    drop(guard);
+
+    create_branch("c")?;
+    assert_eq!(
+        ctx.redo_snapshot()?,
+        None,
+        "any other operation invalidates redo"
+    );
+    Ok(())
+}
+
+#[test]
+fn compare_snapshots_shows_added_stacks() -> anyhow::Result<()> {
+    let Test { ctx, .. } = &Test::default();
+
+    gitbutler_branch_actions::set_base_branch(
+        ctx,
+        &"refs/remotes/origin/master".parse()?,
+        false,
+        ctx.project().exclusive_worktree_access().write_permission(),
+    )?;
+    for name in ["a", "b", "c"] {
+        gitbutler_branch_actions::create_virtual_branch(
+            ctx,
+            &BranchCreateRequest {
+                name: Some(name.into()),
+                ..Default::default()
+            },
+            ctx.project().exclusive_worktree_access().write_permission(),
+        )?;
+    }
+    // Snapshots capture the state before each operation, the most recent one is before creating 'c'.
+    let creations: Vec<_> = ctx
+        .list_snapshots(10, None, Vec::new())?
+        .into_iter()
+        .filter(|s| {
+            s.details
+                .as_ref()
+                .is_some_and(|d| d.operation == OperationKind::CreateBranch)
+        })
+        .collect();
+    let (before_c, before_a) = (creations[0].commit_id, creations[2].commit_id);
+
+    let comparison = ctx.compare_snapshots(before_a, before_c)?;
+    assert_eq!(comparison.from, before_a);
+    assert_eq!(comparison.to, before_c);
+    assert_eq!(
+        comparison
+            .stacks
+            .iter()
+            .map(|s| (s.name.as_str(), s.kind))
+            .collect::<Vec<_>>(),
+        [("a", StackChangeKind::Added), ("b", StackChangeKind::Added)]
+    );
+    assert!(comparison.worktree_changes.is_empty());
+    assert_eq!(comparison.conflicts, None);
+
+    let comparison = ctx.compare_snapshots(before_c, before_a)?;
+    assert!(comparison
+        .stacks
+        .iter()
+        .all(|s| s.kind == StackChangeKind::Removed));
+    assert_eq!(comparison.stacks.len(), 2);
+    Ok(())
+}
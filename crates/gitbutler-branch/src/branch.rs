@@ -1,10 +1,11 @@
 use bstr::{BStr, ByteSlice};
 use but_graph::virtual_branches_legacy_types;
 use gitbutler_stack::StackId;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize, Serializer};
 use std::ops::Deref;
 
-#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+#[derive(Debug, Serialize, Deserialize, Default, Clone, JsonSchema)]
 pub struct BranchUpdateRequest {
     pub id: Option<StackId>,
     pub name: Option<String>,
@@ -16,7 +17,7 @@ pub struct BranchUpdateRequest {
     pub allow_rebasing: Option<bool>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Default)]
+#[derive(Debug, Serialize, Deserialize, Default, JsonSchema)]
 pub struct BranchCreateRequest {
     pub name: Option<String>,
     pub ownership: Option<virtual_branches_legacy_types::BranchOwnershipClaims>,
@@ -32,9 +33,10 @@ pub struct BranchCreateRequest {
 ///   or `feat/one` for `refs/remotes/my/special/remote/feat/one`.
 /// * For virtual branches, it's either the above if there is a `source_refname` or an `upstream`, or it's the normalized
 ///   name of the virtual branch.
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Ord, PartialOrd, JsonSchema)]
 pub struct BranchIdentity(
     /// The identity is always a valid reference name, full or partial.
+    #[schemars(with = "String")]
     pub gix::refs::PartialName,
 );
 
@@ -6,13 +6,14 @@ use git2::DiffHunk;
 use gitbutler_cherry_pick::RepositoryExt;
 use gitbutler_command_context::RepositoryExtLite;
 use gitbutler_serde::BStringForFrontend;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use tracing::instrument;
 
 pub type DiffByPathMap = HashMap<PathBuf, FileDiff>;
 
 /// The type of change
-#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum ChangeType {
     /// Entry does not exist in old version
@@ -44,7 +45,7 @@ impl From<git2::Delta> for ChangeType {
 
 /// A description of a hunk, as identified by its line number and the amount of lines it spans
 /// before and after the change.
-#[derive(Debug, PartialEq, Clone, Serialize)]
+#[derive(Debug, PartialEq, Clone, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct GitHunk {
     pub old_start: u32,
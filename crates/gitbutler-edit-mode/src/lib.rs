@@ -24,6 +24,7 @@ use gitbutler_repo::RepositoryExt;
 use gitbutler_repo::{signature, SignaturePurpose};
 use gitbutler_stack::VirtualBranchesHandle;
 use gitbutler_workspace::branch_trees::{update_uncommited_changes_with_tree, WorkspaceState};
+use schemars::JsonSchema;
 use serde::Serialize;
 
 pub mod commands;
@@ -303,7 +304,7 @@ pub(crate) fn save_and_return_to_workspace(
     Ok(())
 }
 
-#[derive(Serialize, Debug, Clone)]
+#[derive(Serialize, Debug, Clone, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ConflictEntryPresence {
     pub ours: bool,
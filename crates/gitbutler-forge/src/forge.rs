@@ -1,6 +1,8 @@
+use gitbutler_url::Url;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, JsonSchema)]
 #[serde(tag = "name", rename_all = "lowercase")]
 /// Supported git forge types
 pub enum ForgeName {
@@ -9,3 +11,129 @@ pub enum ForgeName {
     Bitbucket,
     Azure,
 }
+
+impl ForgeName {
+    /// Parse the lowercase `name` of a forge, as used in settings, like `gitlab`.
+    pub fn from_name(name: &str) -> Option<ForgeName> {
+        match name.to_ascii_lowercase().as_str() {
+            "github" => Some(ForgeName::GitHub),
+            "gitlab" => Some(ForgeName::GitLab),
+            "bitbucket" => Some(ForgeName::Bitbucket),
+            "azure" => Some(ForgeName::Azure),
+            _ => None,
+        }
+    }
+
+    /// Deduce the forge from the remote `url`, or `None` if the host isn't recognized.
+    ///
+    /// Self-hosted instances are detected if their host has a label like `gitlab.example.com`,
+    /// or, for Azure DevOps Server, if the repository path contains `/_git/`.
+    pub fn from_url(url: &Url) -> Option<ForgeName> {
+        if url.is_github() || url.host.as_deref().is_some_and(is_github_enterprise_host) {
+            Some(ForgeName::GitHub)
+        } else if url.is_gitlab() {
+            Some(ForgeName::GitLab)
+        } else if url.is_bitbucket() {
+            Some(ForgeName::Bitbucket)
+        } else if url.is_azure() {
+            Some(ForgeName::Azure)
+        } else {
+            None
+        }
+    }
+}
+
+fn is_github_enterprise_host(host: &str) -> bool {
+    host.to_ascii_lowercase()
+        .split('.')
+        .any(|label| label == "github" || label == "ghe")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    fn forge(url: &str) -> Option<ForgeName> {
+        ForgeName::from_url(&Url::from_str(url).expect("valid url"))
+    }
+
+    #[test]
+    fn test_from_url_cloud_hosts() {
+        assert_eq!(
+            forge("https://github.com/gitbutlerapp/gitbutler.git"),
+            Some(ForgeName::GitHub)
+        );
+        assert_eq!(
+            forge("git@gitlab.com:group/project.git"),
+            Some(ForgeName::GitLab)
+        );
+        assert_eq!(
+            forge("git@bitbucket.org:team/repo.git"),
+            Some(ForgeName::Bitbucket)
+        );
+        assert_eq!(
+            forge("https://org@dev.azure.com/org/project/_git/repo"),
+            Some(ForgeName::Azure)
+        );
+        assert_eq!(
+            forge("git@ssh.dev.azure.com:v3/org/project/repo"),
+            Some(ForgeName::Azure)
+        );
+        assert_eq!(
+            forge("https://Org.VisualStudio.com/project/_git/repo"),
+            Some(ForgeName::Azure),
+            "hosts are case-insensitive"
+        );
+        assert_eq!(
+            forge("git@SSH.Dev.Azure.com:v3/org/project/repo"),
+            Some(ForgeName::Azure)
+        );
+    }
+
+    #[test]
+    fn test_from_url_self_hosted() {
+        assert_eq!(
+            forge("https://github.example.com/org/repo.git"),
+            Some(ForgeName::GitHub)
+        );
+        assert_eq!(
+            forge("ssh://git@gitlab.example.com:2222/group/project.git"),
+            Some(ForgeName::GitLab)
+        );
+        assert_eq!(
+            forge("https://bitbucket.example.com/scm/team/repo.git"),
+            Some(ForgeName::Bitbucket)
+        );
+        assert_eq!(
+            forge("https://tfs.example.com/DefaultCollection/project/_git/repo"),
+            Some(ForgeName::Azure)
+        );
+    }
+
+    #[test]
+    fn test_from_url_unknown() {
+        assert_eq!(forge("https://git.example.com/org/repo.git"), None);
+        assert_eq!(forge("git@example.com:org/repo.git"), None);
+    }
+
+    #[test]
+    fn test_from_url_lookalike_hosts() {
+        assert_eq!(forge("https://gitlabs.example.com/group/project.git"), None);
+        assert_eq!(
+            forge("https://mygitlab.example.com/group/project.git"),
+            None
+        );
+        assert_eq!(
+            forge("https://bitbucketeer.example.com/team/repo.git"),
+            None
+        );
+        assert_eq!(forge("https://evildev.azure.com/org/project/repo"), None);
+        assert_eq!(
+            forge("https://dev.azure.com.example.com/org/project/repo"),
+            None
+        );
+        assert_eq!(forge("https://notvisualstudio.com/project/repo"), None);
+    }
+}
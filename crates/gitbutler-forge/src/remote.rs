@@ -0,0 +1,320 @@
+use std::collections::BTreeMap;
+
+use gitbutler_url::{Scheme, Url};
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::forge::ForgeName;
+
+/// The forge a repository is hosted on, along with its location there.
+#[derive(Serialize, PartialEq, Debug, Clone, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ForgeRepoInfo {
+    /// The forge that hosts the repository.
+    pub forge: ForgeName,
+    /// The scheme, host and port of the forge's web interface, like `https://github.com`.
+    pub web_base_url: String,
+    /// The owner of the repository, possibly with multiple components, like GitLab subgroups
+    /// or Azure DevOps `organization/project` pairs.
+    pub owner: String,
+    /// The name of the repository, without `.git` suffix.
+    pub repo: String,
+}
+
+impl ForgeRepoInfo {
+    /// The `owner/repo` slug that identifies the repository on its forge.
+    pub fn slug(&self) -> String {
+        format!("{}/{}", self.owner, self.repo)
+    }
+
+    /// The URL of the repository's home page.
+    pub fn repo_url(&self) -> String {
+        match self.forge {
+            ForgeName::Azure => format!("{}/{}/_git/{}", self.web_base_url, self.owner, self.repo),
+            _ => format!("{}/{}", self.web_base_url, self.slug()),
+        }
+    }
+
+    /// The URL showing the commit with the full hex `commit_id`.
+    pub fn commit_url(&self, commit_id: &str) -> String {
+        let repo_url = self.repo_url();
+        match self.forge {
+            ForgeName::GitHub | ForgeName::Azure => format!("{repo_url}/commit/{commit_id}"),
+            ForgeName::GitLab => format!("{repo_url}/-/commit/{commit_id}"),
+            ForgeName::Bitbucket => format!("{repo_url}/commits/{commit_id}"),
+        }
+    }
+
+    /// The URL showing the remote branch with the short name `branch`, like `feature`.
+    pub fn branch_url(&self, branch: &str) -> String {
+        let repo_url = self.repo_url();
+        let branch = encode_branch(branch);
+        match self.forge {
+            ForgeName::GitHub => format!("{repo_url}/tree/{branch}"),
+            ForgeName::GitLab => format!("{repo_url}/-/tree/{branch}"),
+            ForgeName::Bitbucket => format!("{repo_url}/branch/{branch}"),
+            ForgeName::Azure => format!("{repo_url}?version=GB{branch}"),
+        }
+    }
+
+    /// The URL comparing the `head` branch against the `base` branch, which is also where reviews are opened.
+    pub fn compare_url(&self, base: &str, head: &str) -> String {
+        let repo_url = self.repo_url();
+        let (base, head) = (encode_branch(base), encode_branch(head));
+        match self.forge {
+            ForgeName::GitHub => format!("{repo_url}/compare/{base}...{head}"),
+            ForgeName::GitLab => format!("{repo_url}/-/compare/{base}...{head}"),
+            ForgeName::Bitbucket => format!("{repo_url}/branches/compare/{head}%0D{base}"),
+            ForgeName::Azure => {
+                format!("{repo_url}/branchCompare?baseVersion=GB{base}&targetVersion=GB{head}")
+            }
+        }
+    }
+}
+
+/// The characters that are kept as they are in URL path segments, all others are percent-encoded.
+const PATH_SEGMENT: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'_')
+    .remove(b'.')
+    .remove(b'~');
+
+/// Percent-encode each `/`-separated component of the branch name `branch`, keeping the separators.
+fn encode_branch(branch: &str) -> String {
+    branch
+        .split('/')
+        .map(|component| utf8_percent_encode(component, PATH_SEGMENT).to_string())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Find the forge of the first of `urls` that can be resolved, in order.
+///
+/// `host_overrides` maps hosts to forge names as understood by [`ForgeName::from_name()`], and takes
+/// precedence over the detection by host name. This is how self-hosted instances on hosts like
+/// `git.corp.example.com` are assigned a forge.
+pub fn deduce_forge_repo_info<'a>(
+    urls: impl IntoIterator<Item = &'a Url>,
+    host_overrides: &BTreeMap<String, String>,
+) -> Option<ForgeRepoInfo> {
+    urls.into_iter()
+        .find_map(|url| forge_repo_info(url, host_overrides))
+}
+
+fn forge_repo_info(url: &Url, host_overrides: &BTreeMap<String, String>) -> Option<ForgeRepoInfo> {
+    let host = url.host.as_deref()?.to_ascii_lowercase();
+    let forge = host_overrides
+        .iter()
+        .find(|(override_host, _)| override_host.eq_ignore_ascii_case(&host))
+        .and_then(|(_, name)| ForgeName::from_name(name))
+        .or_else(|| ForgeName::from_url(url))?;
+
+    let path = url.path.to_string();
+    let mut components: Vec<&str> = path
+        .trim_matches('/')
+        .split('/')
+        .filter(|c| !c.is_empty())
+        .collect();
+    let repo = components.pop()?;
+    let repo = repo.strip_suffix(".git").unwrap_or(repo);
+
+    let mut web_host = host.clone();
+    if forge == ForgeName::Azure {
+        if components.last() == Some(&"_git") {
+            components.pop();
+        } else if components.first() == Some(&"v3") {
+            // SSH URLs look like `git@ssh.dev.azure.com:v3/organization/project/repo`.
+            components.remove(0);
+            if host.starts_with("ssh.") {
+                web_host = host.trim_start_matches("ssh.").to_owned();
+            } else if host.starts_with("vs-ssh.") && !components.is_empty() {
+                let organization = components.remove(0);
+                web_host = format!("{organization}.{}", host.trim_start_matches("vs-ssh."));
+            }
+        }
+    }
+    if components.is_empty() {
+        return None;
+    }
+
+    // Ports of SSH URLs don't say anything about the port of the web interface.
+    let port = match (&url.scheme, url.port) {
+        (Scheme::Https | Scheme::Http, Some(port)) => format!(":{port}"),
+        _ => String::new(),
+    };
+    let web_scheme = if url.scheme == Scheme::Http {
+        "http"
+    } else {
+        "https"
+    };
+
+    Some(ForgeRepoInfo {
+        forge,
+        web_base_url: format!("{web_scheme}://{web_host}{port}"),
+        owner: components.join("/"),
+        repo: repo.to_owned(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    fn info(url: &str) -> Option<ForgeRepoInfo> {
+        info_with_overrides(url, &[])
+    }
+
+    fn info_with_overrides(url: &str, overrides: &[(&str, &str)]) -> Option<ForgeRepoInfo> {
+        let overrides = overrides
+            .iter()
+            .map(|(host, forge)| (host.to_string(), forge.to_string()))
+            .collect();
+        deduce_forge_repo_info(&[Url::from_str(url).expect("valid url")], &overrides)
+    }
+
+    #[test]
+    fn test_github_ssh_and_https() {
+        for url in [
+            "git@github.com:gitbutlerapp/gitbutler.git",
+            "ssh://git@github.com/gitbutlerapp/gitbutler",
+            "https://github.com/gitbutlerapp/gitbutler.git",
+        ] {
+            let info = info(url).expect("resolves");
+            assert_eq!(info.forge, ForgeName::GitHub);
+            assert_eq!(info.slug(), "gitbutlerapp/gitbutler");
+            assert_eq!(info.repo_url(), "https://github.com/gitbutlerapp/gitbutler");
+        }
+    }
+
+    #[test]
+    fn test_github_web_urls() {
+        let info = info("git@github.com:gitbutlerapp/gitbutler.git").unwrap();
+        assert_eq!(
+            info.commit_url("abc123"),
+            "https://github.com/gitbutlerapp/gitbutler/commit/abc123"
+        );
+        assert_eq!(
+            info.branch_url("feature"),
+            "https://github.com/gitbutlerapp/gitbutler/tree/feature"
+        );
+        assert_eq!(
+            info.compare_url("main", "feature"),
+            "https://github.com/gitbutlerapp/gitbutler/compare/main...feature"
+        );
+    }
+
+    #[test]
+    fn test_branch_names_are_percent_encoded() {
+        let info = info("git@github.com:gitbutlerapp/gitbutler.git").unwrap();
+        assert_eq!(
+            info.branch_url("feat/100%#fix"),
+            "https://github.com/gitbutlerapp/gitbutler/tree/feat/100%25%23fix"
+        );
+        assert_eq!(
+            info.compare_url("release/1.0", "user/feat?x&y=ü"),
+            "https://github.com/gitbutlerapp/gitbutler/compare/release/1.0...user/feat%3Fx%26y%3D%C3%BC"
+        );
+
+        let info = info("https://org@dev.azure.com/org/project/_git/repo").unwrap();
+        assert_eq!(
+            info.compare_url("main", "a b&c"),
+            "https://dev.azure.com/org/project/_git/repo/branchCompare?baseVersion=GBmain&targetVersion=GBa%20b%26c"
+        );
+    }
+
+    #[test]
+    fn test_gitlab_subgroups() {
+        let info = info("git@gitlab.com:group/subgroup/project.git").unwrap();
+        assert_eq!(info.forge, ForgeName::GitLab);
+        assert_eq!(info.owner, "group/subgroup");
+        assert_eq!(info.repo, "project");
+        assert_eq!(
+            info.compare_url("main", "feature"),
+            "https://gitlab.com/group/subgroup/project/-/compare/main...feature"
+        );
+    }
+
+    #[test]
+    fn test_bitbucket() {
+        let info = info("https://user@bitbucket.org/team/repo.git").unwrap();
+        assert_eq!(info.forge, ForgeName::Bitbucket);
+        assert_eq!(
+            info.commit_url("abc123"),
+            "https://bitbucket.org/team/repo/commits/abc123"
+        );
+        assert_eq!(
+            info.compare_url("main", "feature"),
+            "https://bitbucket.org/team/repo/branches/compare/feature%0Dmain"
+        );
+    }
+
+    #[test]
+    fn test_azure_https_and_ssh() {
+        let https = info("https://org@dev.azure.com/org/project/_git/repo").unwrap();
+        let ssh = info("git@ssh.dev.azure.com:v3/org/project/repo").unwrap();
+        assert_eq!(https, ssh);
+        assert_eq!(https.forge, ForgeName::Azure);
+        assert_eq!(https.slug(), "org/project/repo");
+        assert_eq!(
+            https.repo_url(),
+            "https://dev.azure.com/org/project/_git/repo"
+        );
+        assert_eq!(
+            https.branch_url("feature"),
+            "https://dev.azure.com/org/project/_git/repo?version=GBfeature"
+        );
+    }
+
+    #[test]
+    fn test_azure_visualstudio_ssh() {
+        let info = info("org@vs-ssh.visualstudio.com:v3/org/project/repo").unwrap();
+        assert_eq!(
+            info.repo_url(),
+            "https://org.visualstudio.com/project/_git/repo"
+        );
+    }
+
+    #[test]
+    fn test_self_hosted_with_override() {
+        assert_eq!(info("git@git.corp.example.com:team/repo.git"), None);
+
+        let info = info_with_overrides(
+            "ssh://git@git.corp.example.com:2222/team/repo.git",
+            &[("git.corp.example.com", "gitlab")],
+        )
+        .unwrap();
+        assert_eq!(info.forge, ForgeName::GitLab);
+        assert_eq!(info.web_base_url, "https://git.corp.example.com");
+        assert_eq!(info.slug(), "team/repo");
+    }
+
+    #[test]
+    fn test_override_takes_precedence() {
+        let info = info_with_overrides(
+            "https://github.example.com:8443/team/repo",
+            &[("GitHub.example.com", "bitbucket")],
+        )
+        .unwrap();
+        assert_eq!(info.forge, ForgeName::Bitbucket);
+        assert_eq!(info.web_base_url, "https://github.example.com:8443");
+    }
+
+    #[test]
+    fn test_first_resolvable_url_wins() {
+        let urls = [
+            Url::from_str("/local/path/repo").unwrap(),
+            Url::from_str("git@gitlab.com:group/project.git").unwrap(),
+            Url::from_str("git@github.com:owner/repo.git").unwrap(),
+        ];
+        let info = deduce_forge_repo_info(&urls, &BTreeMap::new()).unwrap();
+        assert_eq!(info.forge, ForgeName::GitLab);
+    }
+
+    #[test]
+    fn test_missing_owner() {
+        assert_eq!(info("https://github.com/repo.git"), None);
+    }
+}
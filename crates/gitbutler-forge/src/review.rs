@@ -90,13 +90,21 @@ pub fn get_review_template_functions(forge_name: &ForgeName) -> ReviewTemplateFu
             is_review_template: is_review_template_bitbucket,
             get_root: get_bitbucket_directory_path,
             is_valid_review_template_path: is_valid_review_template_path_bitbucket,
-            supported_template_directories: &[SupportedTemplateDirectory::ForgeRoot],
+            supported_template_directories: &[
+                SupportedTemplateDirectory::ForgeRoot,
+                SupportedTemplateDirectory::ProjectRoot,
+            ],
         },
         ForgeName::Azure => ReviewTemplateFunctions {
             is_review_template: is_review_template_azure,
             get_root: get_azure_directory_path,
             is_valid_review_template_path: is_valid_review_template_path_azure,
-            supported_template_directories: &[SupportedTemplateDirectory::ForgeRoot],
+            supported_template_directories: &[
+                SupportedTemplateDirectory::ForgeRoot,
+                SupportedTemplateDirectory::ProjectRoot,
+                SupportedTemplateDirectory::Custom(".vsts"),
+                SupportedTemplateDirectory::Custom("docs"),
+            ],
         },
     }
 }
@@ -141,33 +149,55 @@ fn is_valid_review_template_path_gitlab(path: &path::Path) -> bool {
 }
 
 fn get_bitbucket_directory_path(root_path: &path::Path) -> path::PathBuf {
-    // TODO: implement
-    root_path.to_path_buf()
+    let mut path = root_path.to_path_buf();
+    path.push(".bitbucket");
+    path
 }
 
-fn is_review_template_bitbucket(_path_str: &str) -> bool {
-    // TODO: implement
-    false
+/// Matches `pull_request_template.md` at the root or in `.bitbucket/`, and Markdown files directly in
+/// `.bitbucket/pull_request_template/`, ignoring case.
+fn is_review_template_bitbucket(path_str: &str) -> bool {
+    let normalized_path = path_str.replace('\\', "/").to_lowercase();
+    let Some(path_in_dir) = normalized_path.strip_prefix(".bitbucket/") else {
+        return normalized_path == "pull_request_template.md";
+    };
+    path_in_dir == "pull_request_template.md"
+        || path_in_dir
+            .strip_prefix("pull_request_template/")
+            .and_then(|file_name| file_name.strip_suffix(".md"))
+            .is_some_and(|stem| !stem.is_empty() && !stem.contains('/'))
 }
 
-fn is_valid_review_template_path_bitbucket(_path: &path::Path) -> bool {
-    // TODO: implement
-    false
+fn is_valid_review_template_path_bitbucket(path: &path::Path) -> bool {
+    is_review_template_bitbucket(path.to_str().unwrap_or_default())
 }
 
+/// The directories Azure DevOps looks for pull request templates in, relative to the project root.
+const AZURE_TEMPLATE_DIRECTORIES: &[&str] = &["", ".azuredevops/", ".vsts/", "docs/"];
+
 fn get_azure_directory_path(root_path: &path::Path) -> path::PathBuf {
-    // TODO: implement
-    root_path.to_path_buf()
+    let mut path = root_path.to_path_buf();
+    path.push(".azuredevops");
+    path
 }
 
-fn is_review_template_azure(_path_str: &str) -> bool {
-    // TODO: implement
-    false
+fn is_review_template_azure(path_str: &str) -> bool {
+    let normalized_path = path_str.replace('\\', "/").to_lowercase();
+    AZURE_TEMPLATE_DIRECTORIES.iter().any(|dir| {
+        let Some(relative_path) = normalized_path.strip_prefix(dir) else {
+            return false;
+        };
+        let is_template_file = relative_path == "pull_request_template.md"
+            || relative_path == "pull_request_template.txt";
+        // Additional and branch-specific templates live in a `pull_request_template` directory.
+        let is_in_template_dir = relative_path.starts_with("pull_request_template/")
+            && (relative_path.ends_with(".md") || relative_path.ends_with(".txt"));
+        is_template_file || is_in_template_dir
+    })
 }
 
-fn is_valid_review_template_path_azure(_path: &path::Path) -> bool {
-    // TODO: implement
-    false
+fn is_valid_review_template_path_azure(path: &path::Path) -> bool {
+    is_review_template_azure(path.to_str().unwrap_or_default())
 }
 
 #[cfg(test)]
@@ -295,4 +325,113 @@ mod tests {
             ".gitlab\\merge_request_templates\\Default.md"
         ));
     }
+
+    #[test]
+    fn test_is_valid_review_template_path_bitbucket() {
+        assert!(is_valid_review_template_path_bitbucket(p(
+            ".bitbucket/PULL_REQUEST_TEMPLATE.md"
+        )));
+        assert!(is_valid_review_template_path_bitbucket(p(
+            ".bitbucket/pull_request_template.md"
+        )));
+        assert!(is_valid_review_template_path_bitbucket(p(
+            ".bitbucket/PULL_REQUEST_TEMPLATE/feature.md"
+        )));
+        assert!(is_valid_review_template_path_bitbucket(p(
+            "PULL_REQUEST_TEMPLATE.md"
+        )));
+        assert!(!is_valid_review_template_path_bitbucket(p("README.md")));
+        assert!(!is_valid_review_template_path_bitbucket(p(
+            ".bitbucket/PULL_REQUEST_TEMPLATE.txt"
+        )));
+        assert!(!is_valid_review_template_path_bitbucket(p(
+            ".github/PULL_REQUEST_TEMPLATE.md"
+        )));
+        assert!(!is_valid_review_template_path_bitbucket(p(
+            ".bitbucket/pull_request_template_old.md"
+        )));
+        assert!(!is_valid_review_template_path_bitbucket(p(
+            ".bitbucket/pull_request_templates/feature.md"
+        )));
+        assert!(!is_valid_review_template_path_bitbucket(p(
+            ".bitbucket/pull_request_template/nested/feature.md"
+        )));
+        assert!(!is_valid_review_template_path_bitbucket(p(
+            ".bitbucket/pull_request_template/.md"
+        )));
+        assert!(!is_valid_review_template_path_bitbucket(p(
+            "docs/pull_request_template.md"
+        )));
+    }
+
+    #[test]
+    fn test_is_valid_review_template_path_bitbucket_windows() {
+        assert!(is_valid_review_template_path_bitbucket(p(
+            ".bitbucket\\PULL_REQUEST_TEMPLATE.md"
+        )));
+        assert!(is_valid_review_template_path_bitbucket(p(
+            ".bitbucket\\pull_request_template.md"
+        )));
+        assert!(is_valid_review_template_path_bitbucket(p(
+            ".bitbucket\\PULL_REQUEST_TEMPLATE\\feature.md"
+        )));
+        assert!(!is_valid_review_template_path_bitbucket(p("README.md")));
+    }
+
+    #[test]
+    fn test_get_bitbucket_directory_path() {
+        let root_path = p("/path/to/project");
+        let bitbucket_path = get_bitbucket_directory_path(root_path);
+        assert_eq!(bitbucket_path, p("/path/to/project/.bitbucket"));
+    }
+
+    #[test]
+    fn test_is_valid_review_template_path_azure() {
+        assert!(is_valid_review_template_path_azure(p(
+            "pull_request_template.md"
+        )));
+        assert!(is_valid_review_template_path_azure(p(
+            ".azuredevops/pull_request_template.md"
+        )));
+        assert!(is_valid_review_template_path_azure(p(
+            ".vsts/pull_request_template.txt"
+        )));
+        assert!(is_valid_review_template_path_azure(p(
+            "docs/PULL_REQUEST_TEMPLATE.md"
+        )));
+        assert!(is_valid_review_template_path_azure(p(
+            ".azuredevops/pull_request_template/hotfix.md"
+        )));
+        assert!(is_valid_review_template_path_azure(p(
+            ".azuredevops/pull_request_template/branches/main.md"
+        )));
+        assert!(!is_valid_review_template_path_azure(p("README.md")));
+        assert!(!is_valid_review_template_path_azure(p(
+            "src/pull_request_template.md"
+        )));
+        assert!(!is_valid_review_template_path_azure(p(
+            ".azuredevops/pull_request_template.html"
+        )));
+    }
+
+    #[test]
+    fn test_is_valid_review_template_path_azure_windows() {
+        assert!(is_valid_review_template_path_azure(p(
+            ".azuredevops\\pull_request_template.md"
+        )));
+        assert!(is_valid_review_template_path_azure(p(
+            "docs\\pull_request_template.txt"
+        )));
+        assert!(is_valid_review_template_path_azure(p(
+            ".vsts\\pull_request_template\\branches\\main.md"
+        )));
+        assert!(!is_valid_review_template_path_azure(p("README.md")));
+    }
+
+    #[test]
+    fn test_get_azure_directory_path() {
+        let root_path = p("/path/to/project");
+        let azure_path = get_azure_directory_path(root_path);
+        assert_eq!(azure_path, p("/path/to/project/.azuredevops"));
+    }
 }
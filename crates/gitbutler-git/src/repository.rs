@@ -337,6 +337,9 @@ where
 /// Any prompts for the user are passed to the asynchronous callback `on_prompt`,
 /// which should return the user's response or `None` if the operation should be
 /// aborted, in which case an `Err` value is returned from this function.
+///
+/// On success, the messages printed by `git` to stderr are returned, which includes
+/// messages sent by the remote.
 #[expect(clippy::too_many_arguments)]
 pub async fn push<P, F, Fut, E, Extra>(
     repo_path: P,
@@ -347,7 +350,7 @@ pub async fn push<P, F, Fut, E, Extra>(
     force_push_protection: bool,
     on_prompt: F,
     extra: Extra,
-) -> Result<(), crate::Error<Error<E>>>
+) -> Result<String, crate::Error<Error<E>>>
 where
     P: AsRef<Path>,
     E: GitExecutor,
@@ -355,10 +358,15 @@ where
     Fut: std::future::Future<Output = Option<String>>,
     Extra: Send + Clone,
 {
-    let mut args = vec!["push", "--quiet", "--no-verify"];
-
     let refspec = refspec.to_string();
 
+    // Gerrit reports the changes it created or updated as remote messages, which `--quiet` would suppress.
+    let mut args = if refspec.contains(":refs/for/") {
+        vec!["push", "--no-verify"]
+    } else {
+        vec!["push", "--quiet", "--no-verify"]
+    };
+
     args.push(remote);
     args.push(&refspec);
 
@@ -375,7 +383,7 @@ where
         execute_with_auth_harness(repo_path, &executor, &args, None, on_prompt, extra).await?;
 
     if status == 0 {
-        return Ok(());
+        return Ok(stderr);
     }
 
     let base_error = Error::<E>::Failed {
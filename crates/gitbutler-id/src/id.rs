@@ -3,6 +3,7 @@
 
 use std::{fmt, hash::Hash, marker::PhantomData, str};
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use uuid::Uuid;
 
@@ -70,6 +71,16 @@ impl<'de, T> Deserialize<'de> for Id<T> {
     }
 }
 
+impl<T> JsonSchema for Id<T> {
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        Uuid::schema_name()
+    }
+
+    fn json_schema(generator: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        Uuid::json_schema(generator)
+    }
+}
+
 impl<T> Serialize for Id<T> {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
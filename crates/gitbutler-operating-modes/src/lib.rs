@@ -6,6 +6,7 @@ use but_workspace::StackId;
 use gitbutler_command_context::CommandContext;
 use gitbutler_serde::BStringForFrontend;
 use gitbutler_stack::VirtualBranchesHandle;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 /// The reference the app will checkout when the workspace is open
@@ -53,27 +54,29 @@ pub fn write_edit_mode_metadata(
 }
 
 /// Holds relevant state required to switch to and from edit mode
-#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct EditModeMetadata {
     /// The sha of the commit getting edited.
     #[serde(with = "gitbutler_serde::oid")]
+    #[schemars(with = "String")]
     pub commit_oid: git2::Oid,
     /// The ref of the vbranch which owns this commit.
     pub stack_id: StackId,
 }
 
-#[derive(Debug, Default, Serialize, PartialEq, Clone)]
+#[derive(Debug, Default, Serialize, PartialEq, Clone, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct OutsideWorkspaceMetadata {
     /// The name of the currently checked out branch or None if in detached head state.
     #[serde(with = "gitbutler_serde::bstring_opt_lossy")]
+    #[schemars(with = "Option<String>")]
     pub branch_name: Option<BString>,
     /// The paths of any files that would conflict with the workspace as it currently is
     pub worktree_conflicts: Vec<BStringForFrontend>,
 }
 
-#[derive(PartialEq, Debug, Clone, Serialize)]
+#[derive(PartialEq, Debug, Clone, Serialize, JsonSchema)]
 #[serde(tag = "type", content = "subject")]
 pub enum OperatingMode {
     /// The typical app state when it's on the gitbutler/workspace branch
@@ -0,0 +1,397 @@
+//! Comparing the state captured by two snapshots, as opposed to the changes of a single snapshot.
+use std::str::from_utf8;
+
+use anyhow::{Context, Result};
+use but_core::ref_metadata::StackId;
+use but_graph::virtual_branches_legacy_types::{CommitOrChangeId, Stack, VirtualBranches};
+use schemars::JsonSchema;
+use serde::Serialize;
+
+/// All differences between the state of the project at two snapshots.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotComparison {
+    /// The snapshot that is compared from.
+    #[serde(with = "gitbutler_serde::oid")]
+    #[schemars(with = "String")]
+    pub from: git2::Oid,
+    /// The snapshot that is compared to.
+    #[serde(with = "gitbutler_serde::oid")]
+    #[schemars(with = "String")]
+    pub to: git2::Oid,
+    /// The changes to the worktree, with all applied branches merged.
+    pub worktree_changes: Vec<but_core::ui::TreeChange>,
+    /// The changes to the stacks in the workspace, in the order they have in `to`, followed by removed stacks.
+    pub stacks: Vec<StackChange>,
+    /// The conflict state before and after, or `None` if it didn't change.
+    pub conflicts: Option<ConflictsChange>,
+}
+
+/// How a stack differs between two snapshots.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct StackChange {
+    /// The id of the stack.
+    pub id: StackId,
+    /// The name of the top-most branch of the stack, as seen in the most recent of both snapshots that contains it.
+    pub name: String,
+    /// What happened to the stack as a whole.
+    pub kind: StackChangeKind,
+    /// The branches of the stack whose head changed, were added or removed.
+    pub heads: Vec<HeadChange>,
+}
+
+/// What happened to a stack as a whole between two snapshots.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(tag = "type", content = "subject", rename_all = "camelCase")]
+pub enum StackChangeKind {
+    /// The stack was applied to the workspace.
+    Added,
+    /// The stack was removed from the workspace.
+    Removed,
+    /// The stack moved from one position in the workspace to another, with `0` being the leftmost position.
+    #[serde(rename_all = "camelCase")]
+    Reordered {
+        from_position: usize,
+        to_position: usize,
+    },
+    /// The stack stayed in place, but some of its heads changed.
+    Modified,
+}
+
+/// How the head of a branch in a stack differs between two snapshots.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct HeadChange {
+    /// The name of the branch.
+    pub name: String,
+    /// The commit or change id the branch pointed to before, or `None` if it didn't exist.
+    pub before: Option<String>,
+    /// The commit or change id the branch points to after, or `None` if it was removed.
+    pub after: Option<String>,
+}
+
+/// The conflict state captured by a snapshot, as left behind by a merge of upstream changes.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ConflictState {
+    /// The commit the conflicting merge was based on, if there was a merge in progress.
+    pub base_merge_parent: Option<String>,
+    /// The paths that are marked as conflicted.
+    pub paths: Vec<String>,
+}
+
+/// The conflict state before and after, if it changed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ConflictsChange {
+    pub before: ConflictState,
+    pub after: ConflictState,
+}
+
+/// Read the virtual branches state stored in the snapshot `tree`.
+pub(crate) fn virtual_branches_of(
+    repo: &git2::Repository,
+    tree: &git2::Tree<'_>,
+) -> Result<VirtualBranches> {
+    let entry = tree
+        .get_name("virtual_branches.toml")
+        .context("failed to get virtual_branches.toml blob")?;
+    let blob = repo.find_blob(entry.id())?;
+    Ok(toml::from_str(from_utf8(blob.content())?)?)
+}
+
+/// Read the conflict state stored in the snapshot `tree`.
+pub(crate) fn conflict_state_of(
+    repo: &git2::Repository,
+    tree: &git2::Tree<'_>,
+) -> Result<ConflictState> {
+    let Some(entry) = tree.get_name("conflicts") else {
+        return Ok(ConflictState::default());
+    };
+    let conflicts_tree = repo.find_tree(entry.id())?;
+    let read = |name: &str| -> Result<Option<String>> {
+        conflicts_tree
+            .get_name(name)
+            .map(|entry| -> Result<String> {
+                let blob = repo.find_blob(entry.id())?;
+                Ok(String::from_utf8_lossy(blob.content()).into_owned())
+            })
+            .transpose()
+    };
+    Ok(ConflictState {
+        base_merge_parent: read("base_merge_parent")?.map(|sha| sha.trim().to_owned()),
+        paths: read("conflicts")?
+            .map(|paths| {
+                paths
+                    .lines()
+                    .filter(|line| !line.is_empty())
+                    .map(ToOwned::to_owned)
+                    .collect()
+            })
+            .unwrap_or_default(),
+    })
+}
+
+/// Return the conflict change between `before` and `after`, or `None` if they are the same.
+pub(crate) fn compare_conflicts(
+    before: ConflictState,
+    after: ConflictState,
+) -> Option<ConflictsChange> {
+    (before != after).then_some(ConflictsChange { before, after })
+}
+
+/// Compare the stacks in the workspace of `before` with the ones in `after`.
+pub(crate) fn compare_stacks(
+    before: &VirtualBranches,
+    after: &VirtualBranches,
+) -> Vec<StackChange> {
+    let before = stacks_in_workspace(before);
+    let after = stacks_in_workspace(after);
+
+    let mut changes = Vec::new();
+    for (to_position, stack) in after.iter().enumerate() {
+        let previous = before
+            .iter()
+            .enumerate()
+            .find(|(_, previous)| previous.id == stack.id);
+        let (kind, heads) = match previous {
+            None => (StackChangeKind::Added, compare_heads(&[], &stack.heads)),
+            Some((from_position, previous)) => {
+                let heads = compare_heads(&previous.heads, &stack.heads);
+                let kind = if from_position != to_position {
+                    StackChangeKind::Reordered {
+                        from_position,
+                        to_position,
+                    }
+                } else if !heads.is_empty() {
+                    StackChangeKind::Modified
+                } else {
+                    continue;
+                };
+                (kind, heads)
+            }
+        };
+        changes.push(StackChange {
+            id: stack.id,
+            name: stack_name(stack),
+            kind,
+            heads,
+        });
+    }
+    for stack in before
+        .iter()
+        .filter(|previous| !after.iter().any(|stack| stack.id == previous.id))
+    {
+        changes.push(StackChange {
+            id: stack.id,
+            name: stack_name(stack),
+            kind: StackChangeKind::Removed,
+            heads: compare_heads(&stack.heads, &[]),
+        });
+    }
+    changes
+}
+
+/// The name of the top-most branch of `stack`, which is what users know it by.
+fn stack_name(stack: &Stack) -> String {
+    stack.derived_name().unwrap_or_else(|_| stack.name.clone())
+}
+
+/// The stacks in the workspace, from left to right.
+fn stacks_in_workspace(state: &VirtualBranches) -> Vec<&Stack> {
+    let mut stacks: Vec<_> = state
+        .branches
+        .values()
+        .filter(|stack| stack.in_workspace)
+        .collect();
+    stacks.sort_by_key(|stack| stack.order);
+    stacks
+}
+
+/// Compare branch heads by name, keeping the order of `after` and putting removed branches last.
+fn compare_heads(
+    before: &[but_graph::virtual_branches_legacy_types::StackBranch],
+    after: &[but_graph::virtual_branches_legacy_types::StackBranch],
+) -> Vec<HeadChange> {
+    let target = |head: &CommitOrChangeId| match head {
+        CommitOrChangeId::CommitId(id) | CommitOrChangeId::ChangeId(id) => id.clone(),
+    };
+    let mut changes: Vec<_> = after
+        .iter()
+        .filter_map(|branch| {
+            let previous = before
+                .iter()
+                .find(|previous| previous.name == branch.name)
+                .map(|previous| target(&previous.head));
+            let current = Some(target(&branch.head));
+            (previous != current).then(|| HeadChange {
+                name: branch.name.clone(),
+                before: previous,
+                after: current,
+            })
+        })
+        .collect();
+    changes.extend(
+        before
+            .iter()
+            .filter(|previous| !after.iter().any(|branch| branch.name == previous.name))
+            .map(|previous| HeadChange {
+                name: previous.name.clone(),
+                before: Some(target(&previous.head)),
+                after: None,
+            }),
+    );
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use but_graph::virtual_branches_legacy_types::StackBranch;
+
+    use super::*;
+
+    fn stack(id: StackId, order: usize, heads: &[(&str, &str)]) -> Stack {
+        let heads = heads
+            .iter()
+            .map(|(name, head)| {
+                let mut branch =
+                    StackBranch::new_with_zero_head(name.to_string(), None, None, None, false);
+                branch.head = CommitOrChangeId::CommitId(head.to_string());
+                branch
+            })
+            .collect();
+        Stack {
+            id,
+            ..Stack::new_with_just_heads(heads, 0, order, true)
+        }
+    }
+
+    fn state(stacks: impl IntoIterator<Item = Stack>) -> VirtualBranches {
+        VirtualBranches {
+            branches: stacks.into_iter().map(|s| (s.id, s)).collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn unchanged_stacks_are_omitted() {
+        let a = StackId::generate();
+        let before = state([stack(a, 0, &[("a", "1")])]);
+        assert_eq!(compare_stacks(&before, &before.clone()), vec![]);
+    }
+
+    #[test]
+    fn added_removed_and_moved_heads() {
+        let (a, b, c) = (
+            StackId::generate(),
+            StackId::generate(),
+            StackId::generate(),
+        );
+        let before = state([stack(a, 0, &[("a", "1")]), stack(b, 1, &[("b", "2")])]);
+        let after = state([
+            stack(a, 0, &[("a2", "3"), ("a", "2")]),
+            stack(c, 1, &[("c", "4")]),
+        ]);
+        let changes = compare_stacks(&before, &after);
+        assert_eq!(
+            changes
+                .iter()
+                .map(|c| (c.id, c.kind, c.heads.clone()))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    a,
+                    StackChangeKind::Modified,
+                    vec![
+                        HeadChange {
+                            name: "a2".into(),
+                            before: None,
+                            after: Some("3".into())
+                        },
+                        HeadChange {
+                            name: "a".into(),
+                            before: Some("1".into()),
+                            after: Some("2".into())
+                        }
+                    ]
+                ),
+                (
+                    c,
+                    StackChangeKind::Added,
+                    vec![HeadChange {
+                        name: "c".into(),
+                        before: None,
+                        after: Some("4".into())
+                    }]
+                ),
+                (
+                    b,
+                    StackChangeKind::Removed,
+                    vec![HeadChange {
+                        name: "b".into(),
+                        before: Some("2".into()),
+                        after: None
+                    }]
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn reordered_stacks() {
+        let (a, b) = (StackId::generate(), StackId::generate());
+        let before = state([stack(a, 0, &[("a", "1")]), stack(b, 1, &[("b", "2")])]);
+        let after = state([stack(a, 1, &[("a", "1")]), stack(b, 0, &[("b", "2")])]);
+        let changes = compare_stacks(&before, &after);
+        assert_eq!(
+            changes.iter().map(|c| (c.id, c.kind)).collect::<Vec<_>>(),
+            vec![
+                (
+                    b,
+                    StackChangeKind::Reordered {
+                        from_position: 1,
+                        to_position: 0
+                    }
+                ),
+                (
+                    a,
+                    StackChangeKind::Reordered {
+                        from_position: 0,
+                        to_position: 1
+                    }
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn unapplied_stacks_count_as_removed() {
+        let a = StackId::generate();
+        let before = state([stack(a, 0, &[("a", "1")])]);
+        let mut unapplied = stack(a, 0, &[("a", "1")]);
+        unapplied.in_workspace = false;
+        let changes = compare_stacks(&before, &state([unapplied]));
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].kind, StackChangeKind::Removed);
+    }
+
+    #[test]
+    fn conflicts_only_when_changed() {
+        let conflicted = ConflictState {
+            base_merge_parent: Some("abc".into()),
+            paths: vec!["file".into()],
+        };
+        assert_eq!(
+            compare_conflicts(conflicted.clone(), conflicted.clone()),
+            None
+        );
+        assert_eq!(
+            compare_conflicts(ConflictState::default(), conflicted.clone()),
+            Some(ConflictsChange {
+                before: ConflictState::default(),
+                after: conflicted
+            })
+        );
+    }
+}
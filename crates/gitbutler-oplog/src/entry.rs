@@ -6,19 +6,22 @@ use std::{
 
 use anyhow::{anyhow, Result};
 use itertools::Itertools;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use strum::EnumString;
 
 /// A snapshot of the repository and virtual branches state that GitButler can restore to.
 /// It captures the state of the working directory, virtual branches and commits.
-#[derive(Debug, PartialEq, Clone, Serialize)]
+#[derive(Debug, PartialEq, Clone, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Snapshot {
     /// The id of the commit that represents the snapshot
     #[serde(rename = "id", with = "gitbutler_serde::oid")]
+    #[schemars(with = "String")]
     pub commit_id: git2::Oid,
     /// Snapshot creation time in seconds from Unix epoch seconds, based on a commit as `commit_id`.
     #[serde(serialize_with = "gitbutler_serde::as_time_seconds_from_unix_epoch")]
+    #[schemars(with = "i64")]
     pub created_at: git2::Time,
     /// Snapshot details as persisted in the commit message, or `None` if the details couldn't be parsed.
     pub details: Option<SnapshotDetails>,
@@ -27,7 +30,7 @@ pub struct Snapshot {
 /// The payload of a snapshot commit
 ///
 /// This is persisted as a commit message in the title, body and trailers format (<https://git-scm.com/docs/git-interpret-trailers>)
-#[derive(Debug, PartialEq, Clone, Serialize)]
+#[derive(Debug, PartialEq, Clone, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct SnapshotDetails {
     /// The version of the snapshot format
@@ -118,7 +121,9 @@ impl Display for SnapshotDetails {
     }
 }
 
-#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize, EnumString, Default)]
+#[derive(
+    Debug, PartialEq, Clone, Copy, Serialize, Deserialize, EnumString, Default, JsonSchema,
+)]
 pub enum OperationKind {
     CreateCommit,
     CreateBranch,
@@ -162,6 +167,7 @@ pub enum OperationKind {
     AutoHandleChangesBefore,
     AutoHandleChangesAfter,
     SplitBranch,
+    SplitCommit,
     #[default]
     Unknown,
 }
@@ -178,7 +184,7 @@ impl fmt::Display for OperationKind {
     }
 }
 
-#[derive(Debug, PartialEq, Clone, Copy, Serialize)]
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, JsonSchema)]
 pub struct Version(pub u32);
 impl Default for Version {
     fn default() -> Self {
@@ -202,7 +208,7 @@ impl FromStr for Version {
 
 /// Represents a key value pair stored in a snapshot, like `key: value\n`
 /// Using the git trailer format (<https://git-scm.com/docs/git-interpret-trailers>)
-#[derive(Debug, PartialEq, Clone, Serialize)]
+#[derive(Debug, PartialEq, Clone, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Trailer {
     /// Trailer key
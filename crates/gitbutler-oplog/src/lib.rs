@@ -1,7 +1,9 @@
+pub mod diff;
 pub mod entry;
 mod oplog;
 pub use oplog::OplogExt;
 pub mod reflog;
+pub mod retention;
 mod snapshot;
 pub use snapshot::SnapshotExt;
 mod state;
@@ -5,7 +5,12 @@ use std::{
     time::Duration,
 };
 
-use crate::{entry::Version, reflog::ReflogCommits};
+use crate::{
+    diff::{self, SnapshotComparison},
+    entry::Version,
+    reflog::ReflogCommits,
+    retention::{self, PruneOutcome, RetentionPolicy},
+};
 
 use super::{
     entry::{OperationKind, Snapshot, SnapshotDetails, Trailer},
@@ -123,6 +128,19 @@ pub trait OplogExt {
         guard: &mut WorktreeWritePermission,
     ) -> Result<git2::Oid>;
 
+    /// Returns the snapshot that [`redo`](Self::redo) would restore, or `None` if there is nothing to redo.
+    ///
+    /// Each restore, like the ones done to undo an operation, records the state right before it, which is what
+    /// redo brings back. Restores can be redone in reverse order, as long as no other operation was recorded
+    /// after them.
+    fn redo_snapshot(&self) -> Result<Option<Snapshot>>;
+
+    /// Re-applies the state that was undone by the most recent restore which wasn't redone yet.
+    /// This is a restore itself, marked so it takes the redone restore off the redo stack.
+    ///
+    /// Returns the sha of the created restore snapshot commit, or `None` if there is nothing to redo.
+    fn redo(&self, guard: &mut WorktreeWritePermission) -> Result<Option<git2::Oid>>;
+
     /// Determines if a new snapshot should be created due to file changes being created since the last snapshot.
     /// The needs for the automatic snapshotting are:
     ///  - It needs to facilitate backup of work in progress code
@@ -144,8 +162,31 @@ pub trait OplogExt {
     /// This is useful to show what has changed in this particular snapshot
     fn snapshot_diff(&self, sha: git2::Oid) -> Result<Vec<TreeChange>>;
 
+    /// Compares the state of the project at the snapshot `from` with the state at the snapshot `to`, which
+    /// don't have to be related. This includes the worktree, the stacks in the workspace and the conflict state.
+    ///
+    /// This is useful to see what a series of operations did before restoring to one of them.
+    fn compare_snapshots(&self, from: git2::Oid, to: git2::Oid) -> Result<SnapshotComparison>;
+
     /// Gets the sha of the last snapshot commit if present.
     fn oplog_head(&self) -> Result<Option<git2::Oid>>;
+
+    /// Removes all snapshots that `policy` doesn't retain from the oplog, so `git gc` can collect them.
+    /// The remaining snapshots are recreated on top of each other, which changes their commit ids.
+    ///
+    /// With `dry_run`, nothing is changed and the outcome only tells how many snapshots would be pruned.
+    fn prune_snapshots(
+        &self,
+        policy: &RetentionPolicy,
+        dry_run: bool,
+        perm: &mut WorktreeWritePermission,
+    ) -> Result<PruneOutcome>;
+
+    /// Determines if snapshots should be pruned automatically with the policy from the app settings.
+    /// This is the case if automatic pruning is enabled and snapshots weren't pruned within the last day.
+    ///
+    /// It's fast enough to be called on filesystem events.
+    fn should_auto_prune_snapshots(&self) -> Result<bool>;
 }
 
 impl OplogExt for CommandContext {
@@ -254,7 +295,55 @@ impl OplogExt for CommandContext {
         guard: &mut WorktreeWritePermission,
     ) -> Result<git2::Oid> {
         // let mut guard = self.exclusive_worktree_access();
-        restore_snapshot(self, snapshot_commit_id, guard)
+        restore_snapshot(self, snapshot_commit_id, false, guard)
+    }
+
+    fn redo_snapshot(&self) -> Result<Option<Snapshot>> {
+        let repo = git2::Repository::open(self.project().path.as_path())?;
+        let Some(head_id) = OplogHandle::new(&self.project().gb_dir()).oplog_head()? else {
+            return Ok(None);
+        };
+
+        // Collect the restores on top of the oplog, most recent first - any other operation invalidates redo.
+        let mut restores = Vec::new();
+        let mut next = Some(repo.find_commit(head_id)?);
+        while let Some(commit) = next {
+            let details = commit
+                .message()
+                .and_then(|msg| SnapshotDetails::from_str(msg).ok());
+            let Some(details) =
+                details.filter(|details| details.operation == OperationKind::RestoreFromSnapshot)
+            else {
+                break;
+            };
+            next = commit.parent(0).ok();
+            restores.push(Snapshot {
+                commit_id: commit.id(),
+                created_at: commit.time(),
+                details: Some(details),
+            });
+        }
+
+        let mut redo_stack = Vec::new();
+        for restore in restores.into_iter().rev() {
+            let is_redo = restore
+                .details
+                .as_ref()
+                .is_some_and(|details| details.trailers.iter().any(|t| t.key == REDO_OF_TRAILER));
+            if is_redo {
+                redo_stack.pop();
+            } else {
+                redo_stack.push(restore);
+            }
+        }
+        Ok(redo_stack.pop())
+    }
+
+    fn redo(&self, guard: &mut WorktreeWritePermission) -> Result<Option<git2::Oid>> {
+        let Some(snapshot) = self.redo_snapshot()? else {
+            return Ok(None);
+        };
+        restore_snapshot(self, snapshot.commit_id, true, guard).map(Some)
     }
 
     #[instrument(level = tracing::Level::DEBUG, skip(self), err(Debug))]
@@ -293,6 +382,35 @@ impl OplogExt for CommandContext {
         Ok(tree_changes)
     }
 
+    fn compare_snapshots(&self, from: git2::Oid, to: git2::Oid) -> Result<SnapshotComparison> {
+        let worktree_dir = self.project().path.as_path();
+        let gix_repo = gitbutler_command_context::gix_repo_for_merging(worktree_dir)?;
+        let repo = git2::Repository::open(worktree_dir)?;
+
+        let from_tree = repo.find_commit(from)?.tree()?;
+        let to_tree = repo.find_commit(to)?.tree()?;
+
+        let from_wd_tree_id = get_workdir_tree(None, from.to_gix(), &gix_repo, self)?;
+        let to_wd_tree_id = get_workdir_tree(None, to.to_gix(), &gix_repo, self)?;
+        let (worktree_changes, _) = tree_changes(&gix_repo, Some(from_wd_tree_id), to_wd_tree_id)?;
+
+        let stacks = diff::compare_stacks(
+            &diff::virtual_branches_of(&repo, &from_tree)?,
+            &diff::virtual_branches_of(&repo, &to_tree)?,
+        );
+        let conflicts = diff::compare_conflicts(
+            diff::conflict_state_of(&repo, &from_tree)?,
+            diff::conflict_state_of(&repo, &to_tree)?,
+        );
+        Ok(SnapshotComparison {
+            from,
+            to,
+            worktree_changes: worktree_changes.into_iter().map(Into::into).collect(),
+            stacks,
+            conflicts,
+        })
+    }
+
     fn snapshot_workspace_tree(&self, sha: gix::ObjectId) -> Result<gix::ObjectId> {
         let repo = self.gix_repo()?;
         let tree = repo.find_commit(sha)?.tree()?;
@@ -307,6 +425,35 @@ impl OplogExt for CommandContext {
         let oplog_state = OplogHandle::new(&self.project().gb_dir());
         oplog_state.oplog_head()
     }
+
+    #[instrument(skip(self, perm), err(Debug))]
+    fn prune_snapshots(
+        &self,
+        policy: &RetentionPolicy,
+        dry_run: bool,
+        perm: &mut WorktreeWritePermission,
+    ) -> Result<PruneOutcome> {
+        retention::prune_snapshots(
+            self.project(),
+            policy,
+            std::time::SystemTime::now(),
+            dry_run,
+            perm,
+        )
+    }
+
+    #[instrument(level = tracing::Level::DEBUG, skip(self), err(Debug))]
+    fn should_auto_prune_snapshots(&self) -> Result<bool> {
+        if !self.app_settings().oplog.auto_prune {
+            return Ok(false);
+        }
+        let pruned_at = OplogHandle::new(&self.project().gb_dir()).pruned_at()?;
+        Ok(pruned_at.is_none_or(|pruned_at| {
+            pruned_at
+                .elapsed()
+                .is_ok_and(|elapsed| elapsed > Duration::from_secs(24 * 60 * 60))
+        }))
+    }
 }
 
 /// Get a tree of the working dir (applied branches merged)
@@ -520,9 +667,14 @@ fn commit_snapshot(
     Ok(snapshot_commit_id)
 }
 
+/// The trailer of restore snapshots created by redo, with the sha of the restore snapshot that was redone.
+const REDO_OF_TRAILER: &str = "redo_of";
+
+/// Restore the snapshot at `snapshot_commit_id`, and mark the snapshot created for the restore as redo if `is_redo` is set.
 fn restore_snapshot(
     ctx: &CommandContext,
     snapshot_commit_id: git2::Oid,
+    is_redo: bool,
     exclusive_access: &mut WorktreeWritePermission,
 ) -> Result<git2::Oid> {
     let worktree_dir = ctx.project().path.as_path();
@@ -667,7 +819,7 @@ fn restore_snapshot(
     // create new snapshot
     let before_restore_snapshot_tree_id = before_restore_snapshot_result?;
     let restored_date_ms = snapshot_commit.time().seconds() * 1000;
-    let details = SnapshotDetails {
+    let mut details = SnapshotDetails {
         version: Default::default(),
         operation: OperationKind::RestoreFromSnapshot,
         title: "Restored from snapshot".to_string(),
@@ -687,6 +839,13 @@ fn restore_snapshot(
             },
         ],
     };
+    if is_redo {
+        details.title = "Redo".to_string();
+        details.trailers.push(Trailer {
+            key: REDO_OF_TRAILER.to_string(),
+            value: snapshot_commit_id.to_string(),
+        });
+    }
     commit_snapshot(
         ctx.project(),
         before_restore_snapshot_tree_id,
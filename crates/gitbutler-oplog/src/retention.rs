@@ -0,0 +1,276 @@
+use std::{
+    collections::{HashMap, HashSet},
+    str::FromStr,
+    time::{Duration, SystemTime},
+};
+
+use anyhow::Result;
+use gitbutler_project::{access::WorktreeWritePermission, Project};
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::{
+    entry::{OperationKind, SnapshotDetails},
+    reflog::{set_reference_to_oplog, ReflogCommits},
+    state::OplogHandle,
+};
+
+/// Decides which snapshots of the oplog to keep when pruning it.
+///
+/// A snapshot is kept if any of the rules retains it, and the most recent snapshot is always kept.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetentionPolicy {
+    /// The amount of most recent snapshots to keep.
+    pub keep_count: usize,
+    /// Keep all snapshots that were created within this duration, if set.
+    pub max_age: Option<Duration>,
+    /// Keep all snapshots of these operations, no matter how old they are.
+    pub keep_operations: Vec<OperationKind>,
+}
+
+impl RetentionPolicy {
+    /// Create a policy from the user's `settings`, ignoring operations that aren't known.
+    pub fn from_settings(settings: &but_settings::app_settings::Oplog) -> Self {
+        RetentionPolicy {
+            keep_count: settings.keep_count,
+            max_age: Some(Self::max_age_from_days(settings.keep_days)),
+            keep_operations: settings
+                .keep_operations
+                .iter()
+                .filter_map(|name| OperationKind::from_str(name).ok())
+                .collect(),
+        }
+    }
+
+    /// Return the age up to which snapshots are kept if they should be kept for `keep_days`.
+    pub fn max_age_from_days(keep_days: u64) -> Duration {
+        // Keeping snapshots for an absurd amount of days is the same as keeping them forever.
+        Duration::from_secs(keep_days.saturating_mul(24 * 60 * 60))
+    }
+
+    fn retains(
+        &self,
+        index_from_head: usize,
+        created_at: SystemTime,
+        operation: Option<OperationKind>,
+        now: SystemTime,
+    ) -> bool {
+        index_from_head == 0
+            || index_from_head < self.keep_count
+            || self.max_age.is_some_and(|max_age| {
+                // Snapshots from the future count as new.
+                now.duration_since(created_at).unwrap_or_default() <= max_age
+            })
+            || operation.is_some_and(|op| self.keep_operations.contains(&op))
+    }
+}
+
+/// The result of pruning the oplog.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PruneOutcome {
+    /// The amount of snapshots that remain.
+    pub kept: usize,
+    /// The amount of snapshots that were removed from the oplog, or would be removed in a dry-run.
+    pub pruned: usize,
+    /// The new head of the oplog, if the history of snapshots was rewritten.
+    #[serde(with = "gitbutler_serde::oid_opt")]
+    #[schemars(with = "Option<String>")]
+    pub new_head: Option<git2::Oid>,
+}
+
+/// Remove all snapshots from the oplog of `project` that `policy` doesn't retain, as seen at `now`.
+///
+/// The snapshots that are kept are recreated on top of each other, preserving their trees, messages, authors and committers.
+/// `restored_from` trailers are updated to point to the recreated snapshots, and removed if the snapshot they point to was pruned.
+/// Afterwards, the reflog that keeps the oplog reachable is updated so `git gc` can collect the pruned snapshots.
+///
+/// With `dry_run`, nothing is changed and the outcome only tells how many snapshots would be pruned.
+pub(crate) fn prune_snapshots(
+    project: &Project,
+    policy: &RetentionPolicy,
+    now: SystemTime,
+    dry_run: bool,
+    _exclusive_access: &mut WorktreeWritePermission,
+) -> Result<PruneOutcome> {
+    let repo = git2::Repository::open(project.path.as_path())?;
+    let oplog_state = OplogHandle::new(&project.gb_dir());
+    let Some(head_id) = oplog_state.oplog_head()? else {
+        return Ok(PruneOutcome::default());
+    };
+
+    // Collect the chain of snapshots with the most recent one first, stopping at merges just like listing does.
+    let mut chain = Vec::new();
+    let mut next = Some(repo.find_commit(head_id)?);
+    while let Some(commit) = next {
+        if commit.parent_count() > 1 {
+            break;
+        }
+        next = commit.parent(0).ok();
+        chain.push(commit);
+    }
+    let base_id = chain.last().and_then(|oldest| oldest.parent_id(0).ok());
+
+    let retained: Vec<bool> = chain
+        .iter()
+        .enumerate()
+        .map(|(idx, commit)| {
+            let created_at =
+                SystemTime::UNIX_EPOCH + Duration::from_secs(commit.time().seconds().max(0) as u64);
+            let operation = commit
+                .message()
+                .and_then(|msg| SnapshotDetails::from_str(msg).ok())
+                .map(|details| details.operation);
+            policy.retains(idx, created_at, operation, now)
+        })
+        .collect();
+    let kept = retained.iter().filter(|keep| **keep).count();
+    let pruned = chain.len() - kept;
+    if pruned == 0 || dry_run {
+        if !dry_run {
+            oplog_state.set_pruned_oplog_head(None)?;
+        }
+        return Ok(PruneOutcome {
+            kept,
+            pruned,
+            new_head: None,
+        });
+    }
+
+    let pruned_ids: HashSet<git2::Oid> = chain
+        .iter()
+        .zip(&retained)
+        .filter(|(_, keep)| !**keep)
+        .map(|(commit, _)| commit.id())
+        .collect();
+
+    // Recreate the snapshots oldest first. Those below the first pruned snapshot stay as they are.
+    let mut rewritten = HashMap::<git2::Oid, git2::Oid>::new();
+    let mut parent_id = base_id;
+    for (commit, keep) in chain.iter().zip(retained).rev() {
+        if !keep {
+            continue;
+        }
+        let original_parent_id = commit.parent_id(0).ok();
+        if original_parent_id == parent_id {
+            parent_id = Some(commit.id());
+            continue;
+        }
+
+        let message = commit.message_raw().unwrap_or_default();
+        let message = match SnapshotDetails::from_str(message) {
+            Ok(mut details) if details.operation == OperationKind::RestoreFromSnapshot => {
+                details.trailers.retain_mut(|trailer| {
+                    if trailer.key != "restored_from" {
+                        return true;
+                    }
+                    let Ok(restored_from) = git2::Oid::from_str(&trailer.value) else {
+                        return true;
+                    };
+                    if let Some(new_id) = rewritten.get(&restored_from) {
+                        trailer.value = new_id.to_string();
+                    }
+                    !pruned_ids.contains(&restored_from)
+                });
+                details.to_string()
+            }
+            _ => message.to_owned(),
+        };
+        let parent = parent_id.map(|id| repo.find_commit(id)).transpose()?;
+        let new_id = repo.commit(
+            None,
+            &commit.author(),
+            &commit.committer(),
+            &message,
+            &commit.tree()?,
+            parent.iter().collect::<Vec<_>>().as_slice(),
+        )?;
+        rewritten.insert(commit.id(), new_id);
+        parent_id = Some(new_id);
+    }
+
+    oplog_state.set_pruned_oplog_head(parent_id)?;
+    set_reference_to_oplog(&project.path, ReflogCommits::new(project)?)?;
+
+    Ok(PruneOutcome {
+        kept,
+        pruned,
+        new_head: parent_id,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+    fn policy(keep_count: usize, max_age: Option<Duration>) -> RetentionPolicy {
+        RetentionPolicy {
+            keep_count,
+            max_age,
+            keep_operations: vec![OperationKind::RestoreFromSnapshot],
+        }
+    }
+
+    #[test]
+    fn head_is_always_retained() {
+        let now = SystemTime::now();
+        let policy = policy(0, None);
+        assert!(policy.retains(0, now - 100 * DAY, None, now));
+        assert!(!policy.retains(1, now - 100 * DAY, None, now));
+    }
+
+    #[test]
+    fn any_rule_retains() {
+        let now = SystemTime::now();
+        let policy = policy(2, Some(7 * DAY));
+        assert!(policy.retains(1, now - 100 * DAY, None, now), "count");
+        assert!(policy.retains(5, now - DAY, None, now), "age");
+        assert!(
+            policy.retains(
+                5,
+                now - 100 * DAY,
+                Some(OperationKind::RestoreFromSnapshot),
+                now
+            ),
+            "operation"
+        );
+        assert!(!policy.retains(5, now - 100 * DAY, Some(OperationKind::CreateCommit), now));
+    }
+
+    #[test]
+    fn settings_with_unknown_operations() {
+        let policy = RetentionPolicy::from_settings(&but_settings::app_settings::Oplog {
+            auto_prune: true,
+            keep_count: 10,
+            keep_days: 2,
+            keep_operations: vec!["RestoreFromSnapshot".into(), "NotAnOperation".into()],
+        });
+        assert_eq!(
+            policy,
+            RetentionPolicy {
+                keep_count: 10,
+                max_age: Some(2 * DAY),
+                keep_operations: vec![OperationKind::RestoreFromSnapshot],
+            }
+        );
+    }
+
+    #[test]
+    fn settings_with_huge_keep_days_dont_overflow() {
+        let policy = RetentionPolicy::from_settings(&but_settings::app_settings::Oplog {
+            auto_prune: true,
+            keep_count: 0,
+            keep_days: u64::MAX,
+            keep_operations: vec![],
+        });
+        assert_eq!(
+            policy.max_age,
+            Some(RetentionPolicy::max_age_from_days(u64::MAX))
+        );
+        assert_eq!(policy.max_age, Some(Duration::from_secs(u64::MAX)));
+        let now = SystemTime::now();
+        assert!(policy.retains(5, SystemTime::UNIX_EPOCH, None, now));
+    }
+}
@@ -34,6 +34,9 @@ pub struct Oplog {
         default = "unix_epoch"
     )]
     pub modified_at: SystemTime,
+    /// The time when snapshots were last pruned, if they ever were.
+    #[serde(default)]
+    pub pruned_at: Option<SystemTime>,
 }
 
 impl Default for Oplog {
@@ -41,6 +44,7 @@ impl Default for Oplog {
         Self {
             head_sha: None,
             modified_at: SystemTime::UNIX_EPOCH,
+            pruned_at: None,
         }
     }
 }
@@ -63,10 +67,24 @@ impl OplogHandle {
     pub fn set_oplog_head(&self, sha: git2::Oid) -> Result<()> {
         let mut oplog = self.read_file()?;
         oplog.head_sha = Some(sha);
+        oplog.modified_at = SystemTime::now();
         self.write_file(oplog)?;
         Ok(())
     }
 
+    /// Persists the time of pruning, along with the new oplog head `sha` if pruning rewrote the snapshot history.
+    /// Unlike [`set_oplog_head()`](Self::set_oplog_head()), this doesn't count as creating a snapshot.
+    ///
+    /// Errors if the file cannot be read or written.
+    pub fn set_pruned_oplog_head(&self, sha: Option<git2::Oid>) -> Result<()> {
+        let mut oplog = self.read_file()?;
+        if sha.is_some() {
+            oplog.head_sha = sha;
+        }
+        oplog.pruned_at = Some(SystemTime::now());
+        self.write_file(oplog)
+    }
+
     /// Gets the oplog head sha for the given repository.
     ///
     /// Errors if the file cannot be read or written.
@@ -83,6 +101,14 @@ impl OplogHandle {
         Ok(oplog.modified_at)
     }
 
+    /// Gets the time when snapshots were last pruned, if ever.
+    ///
+    /// Errors if the file cannot be read or written.
+    pub fn pruned_at(&self) -> Result<Option<SystemTime>> {
+        let oplog = self.read_file()?;
+        Ok(oplog.pruned_at)
+    }
+
     /// Reads and parses the state file.
     ///
     /// If the file does not exist, it will be created.
@@ -90,8 +116,7 @@ impl OplogHandle {
         read_toml_file_or_default(&self.file_path)
     }
 
-    fn write_file(&self, mut oplog: Oplog) -> Result<()> {
-        oplog.modified_at = SystemTime::now();
+    fn write_file(&self, oplog: Oplog) -> Result<()> {
         gitbutler_fs::write(&self.file_path, toml::to_string(&oplog)?)
     }
 }
@@ -1,4 +1,4 @@
-#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, schemars::JsonSchema)]
 pub struct DefaultTrue(bool);
 
 impl core::fmt::Debug for DefaultTrue {
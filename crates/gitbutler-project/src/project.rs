@@ -1,5 +1,6 @@
 use anyhow::Context;
 use gitbutler_id::id::Id;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::path::Path;
 use std::{
@@ -9,7 +10,7 @@ use std::{
 
 use crate::default_true::DefaultTrue;
 
-#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub enum AuthKey {
     GitCredentialsHelper,
@@ -22,7 +23,7 @@ pub enum AuthKey {
     SystemExecutable,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, JsonSchema)]
 pub struct ApiProject {
     pub name: String,
     pub description: Option<String>,
@@ -43,7 +44,7 @@ pub struct ApiProject {
     pub reviews: bool,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub enum FetchResult {
     Fetched {
@@ -63,16 +64,17 @@ impl FetchResult {
     }
 }
 
-#[derive(Debug, Deserialize, Serialize, Copy, Clone)]
+#[derive(Debug, Deserialize, Serialize, Copy, Clone, JsonSchema)]
 pub struct CodePushState {
     #[serde(with = "gitbutler_serde::oid")]
+    #[schemars(with = "String")]
     pub id: git2::Oid,
     pub timestamp: time::SystemTime,
 }
 
 pub type ProjectId = Id<Project>;
 
-#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default, JsonSchema)]
 pub struct Project {
     pub id: ProjectId,
     pub title: String,
@@ -183,7 +185,7 @@ impl Project {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone, strum::Display)]
+#[derive(Debug, Serialize, Deserialize, Clone, strum::Display, JsonSchema)]
 #[serde(rename_all = "camelCase", tag = "type", content = "subject")]
 pub enum AddProjectOutcome {
     Added(Project),
@@ -1,6 +1,7 @@
 use std::path::PathBuf;
 
 use anyhow::{Context, Result};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::{ApiProject, AuthKey, CodePushState, FetchResult, Project, ProjectId};
@@ -12,7 +13,7 @@ pub(crate) struct Storage {
     inner: gitbutler_storage::Storage,
 }
 
-#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+#[derive(Debug, Serialize, Deserialize, Default, Clone, JsonSchema)]
 pub struct UpdateRequest {
     pub id: ProjectId,
     pub title: Option<String>,
@@ -1,5 +1,6 @@
 use std::{fmt, str::FromStr};
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use super::{error::Error, remote};
@@ -42,6 +43,19 @@ impl<'d> Deserialize<'d> for Refname {
     }
 }
 
+impl JsonSchema for Refname {
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        "LocalRefname".into()
+    }
+
+    fn json_schema(_generator: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        schemars::json_schema!({
+            "type": "string",
+            "description": "A local branch, like `refs/heads/main`.",
+        })
+    }
+}
+
 impl fmt::Display for Refname {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "refs/heads/{}", self.branch)
@@ -9,6 +9,7 @@ pub use error::Error;
 pub use local::Refname as LocalRefname;
 pub use r#virtual::Refname as VirtualRefname;
 pub use remote::Refname as RemoteRefname;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -138,3 +139,16 @@ impl<'d> Deserialize<'d> for Refname {
         name.parse().map_err(serde::de::Error::custom)
     }
 }
+
+impl JsonSchema for Refname {
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        "Refname".into()
+    }
+
+    fn json_schema(_generator: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        schemars::json_schema!({
+            "type": "string",
+            "description": "A reference name, like `refs/heads/main` or `refs/remotes/origin/main`.",
+        })
+    }
+}
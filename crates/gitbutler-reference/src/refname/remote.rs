@@ -1,5 +1,6 @@
 use super::error::Error;
 use gix::refs::FullNameRef;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::{fmt, str::FromStr};
 
@@ -60,6 +61,19 @@ impl<'d> Deserialize<'d> for Refname {
     }
 }
 
+impl JsonSchema for Refname {
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        "RemoteRefname".into()
+    }
+
+    fn json_schema(_generator: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        schemars::json_schema!({
+            "type": "string",
+            "description": "A remote tracking branch, like `refs/remotes/origin/main`.",
+        })
+    }
+}
+
 impl FromStr for Refname {
     type Err = Error;
 
@@ -1,5 +1,6 @@
 use std::{fmt, str::FromStr};
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use super::error::Error;
@@ -31,6 +32,19 @@ impl<'d> Deserialize<'d> for Refname {
     }
 }
 
+impl JsonSchema for Refname {
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        "VirtualRefname".into()
+    }
+
+    fn json_schema(_generator: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        schemars::json_schema!({
+            "type": "string",
+            "description": "A virtual branch, like `refs/gitbutler/name`.",
+        })
+    }
+}
+
 impl fmt::Display for Refname {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "refs/gitbutler/{}", self.branch)
@@ -5,11 +5,12 @@ use git2::Oid;
 use gitbutler_project::Project;
 use infer::MatcherType;
 use itertools::Itertools;
+use schemars::JsonSchema;
 use serde::Serialize;
 use std::path::Path;
 use tracing::warn;
 
-#[derive(Default, Debug, Serialize)]
+#[derive(Default, Debug, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 // TODO: turn this whole struct into an enum, it's : everything is an option style tells us that.
 pub struct FileInfo {
@@ -5,23 +5,24 @@ use git2_hooks;
 use git2_hooks::HookResult as H;
 use gitbutler_command_context::CommandContext;
 use gitbutler_diff::GitHunk;
+use schemars::JsonSchema;
 use serde::Serialize;
 use std::io::Write;
 use std::path::PathBuf;
 use std::process::Stdio;
 
-#[derive(Serialize, PartialEq, Debug, Clone)]
+#[derive(Serialize, PartialEq, Debug, Clone, JsonSchema)]
 pub struct MessageData {
     pub message: String,
 }
 
-#[derive(Serialize, PartialEq, Debug, Clone)]
+#[derive(Serialize, PartialEq, Debug, Clone, JsonSchema)]
 pub struct ErrorData {
     pub error: String,
 }
 
 /// Hook result indicating either success or failure.
-#[derive(Serialize, PartialEq, Debug, Clone)]
+#[derive(Serialize, PartialEq, Debug, Clone, JsonSchema)]
 #[serde(tag = "status", rename_all = "lowercase")]
 pub enum HookResult {
     Success,
@@ -33,7 +34,7 @@ pub enum HookResult {
 ///
 /// A message hook can optionally mutate the message, so this special type is
 /// needed to distinguish between success, and success with message.
-#[derive(Serialize, PartialEq, Debug, Clone)]
+#[derive(Serialize, PartialEq, Debug, Clone, JsonSchema)]
 #[serde(tag = "status", rename_all = "lowercase")]
 pub enum MessageHookResult {
     Success,
@@ -1,7 +1,8 @@
+use schemars::JsonSchema;
 use serde::Serialize;
 
 /// Struct for exposing remote information to the front end.
-#[derive(Default, Debug, Serialize)]
+#[derive(Default, Debug, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct GitRemote {
     pub name: Option<String>,
@@ -1,4 +1,5 @@
 use bstr::{BStr, BString, ByteSlice};
+use schemars::JsonSchema;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::ffi::OsString;
 use std::ops::{Deref, DerefMut};
@@ -31,6 +32,16 @@ impl<'de> Deserialize<'de> for BStringForFrontend {
     }
 }
 
+impl JsonSchema for BStringForFrontend {
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        String::schema_name()
+    }
+
+    fn json_schema(generator: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        String::json_schema(generator)
+    }
+}
+
 impl Deref for BStringForFrontend {
     type Target = BString;
 
@@ -205,3 +205,17 @@ pub mod oid {
             .map_err(|err: git2::Error| serde::de::Error::custom(err.to_string()))
     }
 }
+
+/// JSON schemas for foreign types that don't implement [`schemars::JsonSchema`], to be used with
+/// `#[schemars(schema_with = "…")]`.
+pub mod schema {
+    use schemars::{json_schema, Schema, SchemaGenerator};
+
+    /// The schema of a [`gix::object::tree::EntryKind`].
+    pub fn entry_kind(_generator: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "type": "string",
+            "enum": ["Tree", "Blob", "BlobExecutable", "Link", "Commit"],
+        })
+    }
+}
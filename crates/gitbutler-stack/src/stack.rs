@@ -828,6 +828,27 @@ impl Stack {
         }
     }
 
+    /// Record the Gerrit changes that were created or updated by pushing the branch `branch_name`.
+    pub fn set_gerrit_change_urls(
+        &mut self,
+        ctx: &CommandContext,
+        branch_name: &str,
+        change_urls: Vec<String>,
+    ) -> Result<()> {
+        self.ensure_initialized()?;
+        match self.heads.iter_mut().find(|r| r.name() == branch_name) {
+            Some(head) => {
+                head.gerrit_change_urls = change_urls;
+                branch_state(ctx).set_stack(self.clone())
+            }
+            None => bail!(
+                "Series {} does not exist on stack {}",
+                branch_name,
+                self.name
+            ),
+        }
+    }
+
     pub fn heads(&self, exclude_archived: bool) -> Vec<String> {
         if !exclude_archived {
             self.heads.iter().map(|h| h.name().clone()).collect()
@@ -12,6 +12,7 @@ use gix::refs::{
     Target,
 };
 use itertools::Itertools;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::{fmt::Display, str::FromStr};
 
@@ -36,6 +37,8 @@ pub struct StackBranch {
     pub archived: bool,
 
     pub review_id: Option<String>,
+    /// The URLs of the Gerrit changes that were created or updated when the branch was last pushed in Gerrit mode.
+    pub gerrit_change_urls: Vec<String>,
 }
 
 impl From<virtual_branches_legacy_types::StackBranch> for StackBranch {
@@ -47,6 +50,7 @@ impl From<virtual_branches_legacy_types::StackBranch> for StackBranch {
             pr_number,
             archived,
             review_id,
+            gerrit_change_urls,
         }: virtual_branches_legacy_types::StackBranch,
     ) -> Self {
         StackBranch {
@@ -56,6 +60,7 @@ impl From<virtual_branches_legacy_types::StackBranch> for StackBranch {
             pr_number,
             archived,
             review_id,
+            gerrit_change_urls,
         }
     }
 }
@@ -69,6 +74,7 @@ impl From<StackBranch> for virtual_branches_legacy_types::StackBranch {
             pr_number,
             archived,
             review_id,
+            gerrit_change_urls,
         }: StackBranch,
     ) -> Self {
         virtual_branches_legacy_types::StackBranch {
@@ -78,13 +84,14 @@ impl From<StackBranch> for virtual_branches_legacy_types::StackBranch {
             pr_number,
             archived,
             review_id,
+            gerrit_change_urls,
         }
     }
 }
 
 /// A patch identifier which is either `CommitId` or a `ChangeId`.
 /// ChangeId should always be used if available.
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
 pub enum CommitOrChangeId {
     /// A reference that points directly to a commit.
     CommitId(String),
@@ -161,6 +168,7 @@ impl StackBranch {
             pr_number: None,
             archived: false,
             review_id: None,
+            gerrit_change_urls: Vec::new(),
         };
         branch.set_real_reference(repo, &branch.head)?;
         Ok(branch)
@@ -179,6 +187,7 @@ impl StackBranch {
             pr_number,
             archived,
             review_id,
+            gerrit_change_urls: Vec::new(),
             head: CommitOrChangeId::CommitId(git2::Oid::zero().to_string()),
         }
     }
@@ -144,7 +144,8 @@ fn main() {
 
                     let broadcaster = Arc::new(Mutex::new(Broadcaster::new()));
 
-                    let (send, mut recv) = tokio::sync::mpsc::unbounded_channel();
+                    let (send, mut recv) =
+                        but_broadcaster::channel(but_broadcaster::DEFAULT_CAPACITY);
                     let broadcaster2 = broadcaster.clone();
                     tokio::spawn(async move {
                         broadcaster2
@@ -154,9 +155,11 @@ fn main() {
                     });
 
                     let window2 = window.clone();
-                    std::thread::spawn(move || {
-                        while let Some(message) = recv.blocking_recv() {
-                            window2.emit(&message.name, message.payload).unwrap();
+                    tokio::spawn(async move {
+                        while let Some(message) = recv.recv().await {
+                            window2
+                                .emit(&message.event.name, message.event.payload)
+                                .unwrap();
                         }
                     });
 
@@ -251,6 +254,7 @@ fn main() {
                     virtual_branches::unapply_stack,
                     virtual_branches::create_virtual_branch_from_branch,
                     virtual_branches::can_apply_remote_branch,
+                    virtual_branches::preview_apply_branch,
                     virtual_branches::list_commit_files,
                     virtual_branches::amend_virtual_branch,
                     virtual_branches::undo_commit,
@@ -282,7 +286,11 @@ fn main() {
                     secret::secret_set_global,
                     undo::list_snapshots,
                     undo::restore_snapshot,
+                    undo::redo_snapshot,
+                    undo::redo,
                     undo::snapshot_diff,
+                    undo::compare_snapshots,
+                    undo::prune_snapshots,
                     config::get_gb_config,
                     config::set_gb_config,
                     config::store_author_globally_if_unset,
@@ -303,6 +311,7 @@ fn main() {
                     open::show_in_finder,
                     forge::pr_templates,
                     forge::pr_template,
+                    forge::forge_repo_info,
                     but_api::settings::get_app_settings,
                     settings::update_onboarding_complete,
                     settings::update_telemetry,
@@ -311,6 +320,8 @@ fn main() {
                     settings::update_claude,
                     settings::update_fetch,
                     settings::update_reviews,
+                    settings::update_forges,
+                    settings::update_oplog,
                     action::list_actions,
                     action::handle_changes,
                     action::list_workflows,
@@ -325,9 +336,12 @@ fn main() {
                     rules::delete_workspace_rule,
                     rules::update_workspace_rule,
                     rules::list_workspace_rules,
+                    rules::preview_workspace_rule,
+                    rules::evaluate_workspace_rules,
                     workspace::head_info,
                     workspace::stacks,
                     workspace::stack_details,
+                    workspace::commit_signatures,
                     workspace::branch_details,
                     workspace::create_commit_from_worktree_changes,
                     workspace::amend_commit_from_worktree_changes,
@@ -339,6 +353,7 @@ fn main() {
                     workspace::uncommit_changes,
                     workspace::split_branch,
                     workspace::split_branch_into_dependent_branch,
+                    workspace::split_commit,
                     diff::changes_in_worktree,
                     diff::commit_details,
                     diff::changes_in_branch,
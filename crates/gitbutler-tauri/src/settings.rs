@@ -1,7 +1,8 @@
 #![allow(deprecated)]
 use but_api::commands::settings;
 use but_settings::api::{
-    ClaudeUpdate, FeatureFlagsUpdate, FetchUpdate, ReviewsUpdate, TelemetryUpdate,
+    ClaudeUpdate, FeatureFlagsUpdate, FetchUpdate, ForgesUpdate, OplogUpdate, ReviewsUpdate,
+    TelemetryUpdate,
 };
 use but_settings::AppSettingsWithDiskSync;
 use tauri::State;
@@ -83,3 +84,21 @@ pub fn update_reviews(
 ) -> Result<(), Error> {
     settings::update_reviews(&app_settings_sync, settings::UpdateReviewsParams { update })
 }
+
+#[tauri::command(async)]
+#[instrument(skip(app_settings_sync), err(Debug))]
+pub fn update_forges(
+    app_settings_sync: State<'_, AppSettingsWithDiskSync>,
+    update: ForgesUpdate,
+) -> Result<(), Error> {
+    settings::update_forges(&app_settings_sync, settings::UpdateForgesParams { update })
+}
+
+#[tauri::command(async)]
+#[instrument(skip(app_settings_sync), err(Debug))]
+pub fn update_oplog(
+    app_settings_sync: State<'_, AppSettingsWithDiskSync>,
+    update: OplogUpdate,
+) -> Result<(), Error> {
+    settings::update_oplog(&app_settings_sync, settings::UpdateOplogParams { update })
+}
@@ -33,6 +33,43 @@ impl Url {
             .as_ref()
             .is_some_and(|host| host.contains("github.com"))
     }
+
+    /// Returns `true` for `gitlab.com` and self-hosted instances with a host like `gitlab.example.com`.
+    pub fn is_gitlab(&self) -> bool {
+        self.host_has_label("gitlab")
+    }
+
+    /// Returns `true` for `bitbucket.org` and self-hosted instances with a host like `bitbucket.example.com`.
+    pub fn is_bitbucket(&self) -> bool {
+        self.host_has_label("bitbucket")
+    }
+
+    /// Returns `true` for Azure DevOps Services, and for Azure DevOps Server whose repository paths contain `/_git/`.
+    pub fn is_azure(&self) -> bool {
+        self.host_is_or_is_below("dev.azure.com")
+            || self.host_is_or_is_below("visualstudio.com")
+            || self.path.contains_str("/_git/")
+    }
+
+    /// Returns `true` if any dot-separated label of the host is `label`.
+    fn host_has_label(&self, label: &str) -> bool {
+        self.host.as_ref().is_some_and(|host| {
+            host.to_ascii_lowercase()
+                .split('.')
+                .any(|host_label| host_label == label)
+        })
+    }
+
+    /// Returns `true` if the host is `domain` or one of its subdomains.
+    fn host_is_or_is_below(&self, domain: &str) -> bool {
+        self.host.as_ref().is_some_and(|host| {
+            let host = host.to_ascii_lowercase();
+            host == domain
+                || host
+                    .strip_suffix(domain)
+                    .is_some_and(|subdomain| subdomain.ends_with('.'))
+        })
+    }
 }
 
 impl std::fmt::Display for Url {
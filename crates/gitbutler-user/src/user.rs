@@ -2,9 +2,10 @@ use std::cell::RefCell;
 
 use anyhow::{Context, Result};
 use gitbutler_secret::{secret, Sensitive};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default, JsonSchema)]
 pub struct User {
     pub id: u64,
     pub name: Option<String>,
@@ -18,10 +19,12 @@ pub struct User {
     /// as it's not actually stored anymore, but fetch on demand in a separate step as its
     /// storage location is the [secrets store](crate::secret).
     #[serde(skip_serializing)]
+    #[schemars(with = "Option<String>")]
     pub(super) access_token: RefCell<Option<Sensitive<String>>>,
     pub role: Option<String>,
     /// The semantics here are the same as for `access_token`, but this token is truly optional.
     #[serde(skip_serializing)]
+    #[schemars(with = "Option<String>")]
     pub(super) github_access_token: RefCell<Option<Sensitive<String>>>,
     #[serde(default)]
     pub github_username: Option<String>,
@@ -1,4 +1,9 @@
-use std::{path::PathBuf, sync::Arc};
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
 
 use crate::Change;
 use anyhow::{Context, Result};
@@ -9,6 +14,7 @@ use but_settings::{AppSettings, AppSettingsWithDiskSync};
 use gitbutler_command_context::CommandContext;
 use gitbutler_filemonitor::InternalEvent;
 use gitbutler_operating_modes::operating_mode;
+use gitbutler_oplog::{retention::RetentionPolicy, OplogExt};
 use gitbutler_project::ProjectId;
 use tracing::instrument;
 
@@ -24,13 +30,20 @@ pub struct Handler {
     // need extra protection.
     /// A function to send events - decoupled from app-handle for testing purposes.
     send_event: Arc<dyn Fn(Change) -> Result<()> + Send + Sync + 'static>,
+    /// When each project was last checked for automatic oplog pruning, which blocks the workspace while it runs.
+    last_prune_check: Arc<Mutex<HashMap<ProjectId, Instant>>>,
 }
 
+/// How often filesystem events may check whether the oplog is due for automatic pruning, whether or not the
+/// previous check pruned anything or failed.
+const PRUNE_CHECK_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
 impl Handler {
     /// A constructor whose primary use is the test-suite.
     pub fn new(send_event: impl Fn(Change) -> Result<()> + Send + Sync + 'static) -> Self {
         Handler {
             send_event: Arc::new(send_event),
+            last_prune_check: Default::default(),
         }
     }
 
@@ -73,7 +86,45 @@ impl Handler {
     #[instrument(skip(self, paths, ctx), fields(paths = paths.len()))]
     fn project_files_change(&self, paths: Vec<PathBuf>, ctx: &mut CommandContext) -> Result<()> {
         let _ = self.emit_worktree_changes(ctx);
+        if let Err(err) = self.prune_oplog_if_due(ctx) {
+            tracing::warn!(?err, "failed to prune the oplog");
+        }
+
+        Ok(())
+    }
 
+    /// Prune the oplog with the retention policy from the app settings, if automatic pruning is due.
+    ///
+    /// This is checked at most once per [`PRUNE_CHECK_INTERVAL`] so bursts of events don't repeatedly
+    /// take the exclusive workspace lock, even if pruning fails.
+    fn prune_oplog_if_due(&self, ctx: &CommandContext) -> Result<()> {
+        {
+            let mut last_check = self
+                .last_prune_check
+                .lock()
+                .unwrap_or_else(|err| err.into_inner());
+            let now = Instant::now();
+            match last_check.get(&ctx.project().id) {
+                Some(checked_at) if now.duration_since(*checked_at) < PRUNE_CHECK_INTERVAL => {
+                    return Ok(());
+                }
+                _ => {
+                    last_check.insert(ctx.project().id, now);
+                }
+            }
+        }
+        if !ctx.should_auto_prune_snapshots()? {
+            return Ok(());
+        }
+        let policy = RetentionPolicy::from_settings(&ctx.app_settings().oplog);
+        let mut guard = ctx.project().exclusive_worktree_access();
+        let outcome = ctx.prune_snapshots(&policy, false, guard.write_permission())?;
+        tracing::info!(
+            project_id = %ctx.project().id,
+            kept = outcome.kept,
+            pruned = outcome.pruned,
+            "pruned oplog"
+        );
         Ok(())
     }
 
@@ -101,12 +152,19 @@ impl Handler {
                 .map(|err| serde_error::Error::new(&**err)),
         };
         if ctx.app_settings().feature_flags.rules {
-            if let Ok(update_count) = but_rules::handler::process_workspace_rules(
+            if let Ok(outcome) = but_rules::handler::process_workspace_rules(
                 ctx,
                 &assignments,
                 &dependencies.as_ref().ok().cloned(),
             ) {
-                if update_count > 0 {
+                for err in &outcome.errors {
+                    tracing::warn!(
+                        rule = %err.rule_id,
+                        "failed to apply workspace rule: {}",
+                        err.error
+                    );
+                }
+                if outcome.updates > 0 {
                     // Getting these again since they were updated
                     let (assignments, assignments_error) =
                         assignments_and_errors(ctx, wt_changes.changes.clone(), &dependencies)?;